@@ -0,0 +1,148 @@
+// Draws the low-res canvas onto the window. A flash of chromatic aberration pulls red and blue
+// apart towards the edges. Ordered dithering rounds it to fewer shades in a fixed pattern, stepped
+// per canvas pixel, and a palette can snap every pixel to its nearest color, both at the canvas's
+// own resolution before it's scaled up. Plain scanlines darken every other canvas row. The old tube
+// screen look adds softer rows of scanlines, glass that bulges out a little, and corners that fall
+// off into the dark. Every effect scales with its strength, at zero the canvas comes through
+// untouched.
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+// intensity, then the scanline, curvature and vignette strengths it scales
+@group(2) @binding(0) var<uniform> settings: vec4<f32>;
+@group(2) @binding(1) var canvas_texture: texture_2d<f32>;
+@group(2) @binding(2) var canvas_sampler: sampler;
+// dither strength, then the matrix's bits and the shades per channel
+@group(2) @binding(3) var<uniform> dither: vec4<f32>;
+// 1 while the sampler blends canvas pixels together on the way up to the window, 0 for off
+@group(2) @binding(4) var<uniform> upscale: vec4<f32>;
+@group(2) @binding(5) var palette_texture: texture_1d<f32>;
+// 1 to snap to the palette, 0 for off
+@group(2) @binding(6) var<uniform> quantize: vec4<f32>;
+// how dark every other canvas row gets, whether or not the rest of the tube look is on
+@group(2) @binding(7) var<uniform> plain_scanlines: vec4<f32>;
+// how far red and blue are pushed apart at the canvas's edges, in canvas pixels
+@group(2) @binding(8) var<uniform> aberration: vec4<f32>;
+
+const TAU: f32 = 6.28318530718;
+
+// Threshold from the Bayer matrix 2^bits across, between 0 and 1. Each lower coordinate bit weighs
+// more, so neighbouring pixels land far apart in the order.
+fn bayer(pixel: vec2<u32>, bits: u32) -> f32 {
+    var value = 0u;
+    for (var bit = 0u; bit < bits; bit++) {
+        let x = (pixel.x >> bit) & 1u;
+        let y = (pixel.y >> bit) & 1u;
+        value = (value << 2u) | ((x ^ y) << 1u) | y;
+    }
+    let cells = f32(1u << (2u * bits));
+    return (f32(value) + 0.5) / cells;
+}
+
+// Rounded in roughly the space the eye sees, so the shades come out evenly spaced.
+fn dithered(color: vec3<f32>, pixel: vec2<u32>) -> vec3<f32> {
+    let strength = dither.x;
+    let levels = dither.z;
+    let offset = (bayer(pixel, u32(dither.y)) - 0.5) * strength;
+    let perceptual = pow(clamp(color, vec3(0.0), vec3(1.0)), vec3(1.0 / 2.2));
+    let rounded = clamp(floor(perceptual * levels + 0.5 + offset) / levels, vec3(0.0), vec3(1.0));
+    return select(color, pow(rounded, vec3(2.2)), strength > 0.0);
+}
+
+// The palette color that looks closest, measured in roughly the space the eye sees with green
+// counting for the most.
+fn quantized(color: vec3<f32>) -> vec3<f32> {
+    let perceptual = pow(clamp(color, vec3(0.0), vec3(1.0)), vec3(1.0 / 2.2));
+    var nearest = color;
+    // further than any two colors can be
+    var best = 4.0;
+    let count = textureDimensions(palette_texture);
+    for (var i = 0u; i < count; i++) {
+        let entry = textureLoad(palette_texture, i, 0).rgb;
+        let offset = pow(entry, vec3(1.0 / 2.2)) - perceptual;
+        let distance = dot(offset * offset, vec3(0.3, 0.59, 0.11));
+        if distance < best {
+            best = distance;
+            nearest = entry;
+        }
+    }
+    return select(color, nearest, quantize.x > 0.0);
+}
+
+fn canvas_pixel(pixel: vec2<i32>, size: vec2<u32>) -> vec4<f32> {
+    return textureLoad(canvas_texture, clamp(pixel, vec2(0), vec2<i32>(size) - 1), 0);
+}
+
+// One canvas pixel the way the per pixel effects leave it, red and blue split by whole pixels, then
+// dithered and snapped to the palette.
+fn processed(pixel: vec2<i32>, shift: vec2<i32>, size: vec2<u32>) -> vec4<f32> {
+    let color = canvas_pixel(pixel, size);
+    let red = canvas_pixel(pixel + shift, size).r;
+    let blue = canvas_pixel(pixel - shift, size).b;
+    let at = vec2<u32>(clamp(pixel, vec2(0), vec2<i32>(size) - 1));
+    return vec4(quantized(dithered(vec3(red, color.g, blue), at)), color.a);
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let intensity = settings.x;
+    let scanlines = settings.y * intensity;
+    let curvature = settings.z * intensity;
+    let vignette_strength = settings.w * intensity;
+
+    let centered = mesh.uv * 2.0 - 1.0;
+    let warped = centered * (1.0 + curvature * dot(centered, centered));
+    let uv = warped * 0.5 + 0.5;
+
+    // sampled before deciding anything, texture reads have to happen for every pixel alike
+    let canvas_uv = clamp(uv, vec2(0.0), vec2(1.0));
+    var color = textureSample(canvas_texture, canvas_sampler, canvas_uv);
+    // red out from the middle and blue in towards it, nothing at the very center
+    let size = textureDimensions(canvas_texture);
+    let shift = centered / vec2<f32>(size) * aberration.x;
+    let red_uv = clamp(canvas_uv + shift, vec2(0.0), vec2(1.0));
+    let blue_uv = clamp(canvas_uv - shift, vec2(0.0), vec2(1.0));
+    let red = textureSample(canvas_texture, canvas_sampler, red_uv);
+    let blue = textureSample(canvas_texture, canvas_sampler, blue_uv);
+    color = vec4(red.r, color.g, blue.b, color.a);
+
+    // the pattern follows canvas pixels, and the bulge with them, never window pixels
+    let pixel = min(vec2<u32>(canvas_uv * vec2<f32>(size)), size - 1u);
+    // dithered and snapped per canvas pixel on the canvas's own colors, before any scanlines darken
+    // them. A blend of neighbouring pixels would land between the shades, so under the smooth
+    // sampler the four around are each done first and blended after, as if upscaled from there.
+    if dither.x > 0.0 || quantize.x > 0.0 {
+        let shift_pixels = vec2<i32>(round(centered * aberration.x));
+        if upscale.x > 0.0 {
+            let texel = canvas_uv * vec2<f32>(size) - 0.5;
+            let base = vec2<i32>(floor(texel));
+            let blend = fract(texel);
+            let top = mix(
+                processed(base, shift_pixels, size),
+                processed(base + vec2(1, 0), shift_pixels, size),
+                blend.x,
+            );
+            let bottom = mix(
+                processed(base + vec2(0, 1), shift_pixels, size),
+                processed(base + vec2(1, 1), shift_pixels, size),
+                blend.x,
+            );
+            color = mix(top, bottom, blend.y);
+        } else {
+            color = processed(vec2<i32>(pixel), shift_pixels, size);
+        }
+    }
+    // one canvas row dark and one light, so it scales up with the canvas
+    let dark_row = (pixel.y & 1u) == 1u;
+    color = vec4(color.rgb * select(1.0, 1.0 - plain_scanlines.x, dark_row), color.a);
+
+    // darkest where one canvas row meets the next
+    let rows = f32(size.y);
+    let row = fract(uv.y * rows);
+    let scanline = 1.0 - scanlines * (0.5 + 0.5 * cos(row * TAU));
+    let vignette = 1.0 - vignette_strength * 0.5 * dot(centered, centered);
+    color = vec4(color.rgb * scanline * vignette, color.a);
+
+    // the bulge pulls the edges in, leaving nothing to show past them
+    let inside = all(abs(warped) <= vec2(1.0));
+    return select(vec4(0.0), color, inside);
+}
@@ -0,0 +1,44 @@
+// Darkens every canvas pixel that has something nearer within the outline's width of it, which
+// rings each silhouette on its outside. Runs at the canvas's size, one texel per canvas pixel.
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct CanvasOutline {
+    // linear
+    color: vec4<f32>,
+    // in canvas pixels
+    width: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var depth_texture: texture_depth_2d;
+@group(0) @binding(2) var<uniform> outline: CanvasOutline;
+
+// how much nearer a neighbour has to be, as a ratio of the depths, to count as in front. The cube
+// is thin next to its distance, so only a real edge gets anywhere close.
+const IN_FRONT: f32 = 1.15;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let size = vec2<i32>(textureDimensions(screen_texture));
+    let pixel = vec2<i32>(in.position.xy);
+    let color = textureLoad(screen_texture, pixel, 0);
+    // reversed, bigger is nearer and nothing at all is 0
+    let depth = textureLoad(depth_texture, pixel, 0);
+
+    let width = i32(outline.width);
+    var edge = false;
+    for (var y = -width; y <= width; y++) {
+        for (var x = -width; x <= width; x++) {
+            // round, not square
+            if x * x + y * y > width * width {
+                continue;
+            }
+            let neighbour = clamp(pixel + vec2(x, y), vec2(0), size - 1);
+            let nearer = textureLoad(depth_texture, neighbour, 0);
+            edge = edge || nearer > depth * IN_FRONT;
+        }
+    }
+
+    // opaque, so it shows around the cube in pet mode too
+    return select(color, vec4(outline.color.rgb, 1.0), edge);
+}
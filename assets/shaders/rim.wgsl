@@ -0,0 +1,29 @@
+// The cube's usual lighting with a glow added around its edges, where the faces turn away from the
+// camera. Only the main pass gets it, the prepasses and shadows stay the base material's.
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    forward_io::{VertexOutput, FragmentOutput},
+}
+
+// rgb is the color in linear light, a how much of it shows
+@group(2) @binding(100) var<uniform> rim: vec4<f32>;
+
+// higher keeps the glow tighter to the silhouette
+const RIM_POWER: f32 = 3.0;
+
+@fragment
+fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+
+    let facing = saturate(dot(pbr_input.N, pbr_input.V));
+    let fresnel = pow(1.0 - facing, RIM_POWER);
+    out.color = vec4(out.color.rgb + rim.rgb * rim.a * fresnel, out.color.a);
+
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
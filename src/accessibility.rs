@@ -0,0 +1,294 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::RenderLayers,
+    },
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+use crate::{
+    canvas::CanvasSettings, light_mood::StateLights, settings::Settings, CubeState, CANVAS_LAYER,
+};
+
+const EMBEDDED_COLORS: &str = include_str!("../assets/colors.ron");
+// canvas pixels between the state badge and the canvas's top left corner
+const BADGE_MARGIN: f32 = 4.0;
+// under the multisampling comparison, over everything else on the canvas
+const BADGE_Z: f32 = 40.0;
+
+const SUN: [&str; 9] = [
+    "#...#...#",
+    ".#..#..#.",
+    "..#####..",
+    "..#####..",
+    "#########",
+    "..#####..",
+    "..#####..",
+    ".#..#..#.",
+    "#...#...#",
+];
+const CLOUD: [&str; 9] = [
+    ".........",
+    "...###...",
+    "..#####..",
+    ".#######.",
+    "#########",
+    "#########",
+    ".#######.",
+    ".........",
+    ".........",
+];
+const STORM: [&str; 9] = [
+    "...###...",
+    "..#####..",
+    ".#######.",
+    "#########",
+    ".#######.",
+    "....#....",
+    "...##....",
+    "....##...",
+    "....#....",
+];
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (warn_about_unknown_preset, setup_state_badges))
+            .add_systems(Update, show_state_badge);
+    }
+}
+
+// Which colors the background, the lights and the outline pick for each state. `--colors
+// high-contrast` switches it from the command line, into the settings, so it's saved along with
+// them the next time they change.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorPreset {
+    #[default]
+    Default,
+    // keeps the states apart by blue against orange and by brightness, never red against green
+    Deuteranopia,
+    // light against dark, with a badge in the corner so the state never rests on color alone
+    HighContrast,
+}
+
+impl ColorPreset {
+    pub const ALL: [ColorPreset; 3] = [
+        ColorPreset::Default,
+        ColorPreset::Deuteranopia,
+        ColorPreset::HighContrast,
+    ];
+
+    // The one after `--colors`, or the name that didn't match any of them.
+    pub fn from_args() -> Option<Result<ColorPreset, String>> {
+        let name = env::args().skip_while(|arg| arg != "--colors").nth(1)?;
+        Some(
+            ColorPreset::ALL
+                .into_iter()
+                .find(|preset| preset.arg() == name)
+                .ok_or(name),
+        )
+    }
+
+    fn arg(self) -> &'static str {
+        match self {
+            ColorPreset::Default => "default",
+            ColorPreset::Deuteranopia => "deuteranopia",
+            ColorPreset::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorPreset::Default => "Default",
+            ColorPreset::Deuteranopia => "Deuteranopia",
+            ColorPreset::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn next(self) -> ColorPreset {
+        match self {
+            ColorPreset::Default => ColorPreset::Deuteranopia,
+            ColorPreset::Deuteranopia => ColorPreset::HighContrast,
+            ColorPreset::HighContrast => ColorPreset::Default,
+        }
+    }
+}
+
+// How one state looks under a preset, see colors.ron.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct PresetLook {
+    pub clear: Option<String>,
+    pub gradient: Option<(Vec3, Vec3)>,
+    pub outline: Option<String>,
+    pub lights: Option<StateLights>,
+}
+
+// Every preset's colors for every state, for the background, the outline and the lights to each
+// take their part of.
+pub struct ColorTables(HashMap<ColorPreset, HashMap<CubeState, PresetLook>>);
+
+impl ColorTables {
+    pub fn load() -> Self {
+        ColorTables(ron::from_str(EMBEDDED_COLORS).expect("embedded colors.ron is valid"))
+    }
+
+    // What `pick` finds for each state under `preset`, with the default preset's filling the gaps.
+    pub fn each<T: Clone>(
+        &self,
+        preset: ColorPreset,
+        pick: impl Fn(&PresetLook) -> Option<&T>,
+    ) -> HashMap<CubeState, T> {
+        let mut picked = HashMap::new();
+        for preset in [ColorPreset::Default, preset] {
+            for (state, look) in self.0.get(&preset).into_iter().flatten() {
+                if let Some(value) = pick(look) {
+                    picked.insert(*state, value.clone());
+                }
+            }
+        }
+        picked
+    }
+}
+
+fn warn_about_unknown_preset() {
+    if let Some(Err(name)) = ColorPreset::from_args() {
+        let names: Vec<_> = ColorPreset::ALL.iter().map(|preset| preset.arg()).collect();
+        warn!(
+            "there are no {name:?} colors, keeping the ones from the settings, try one of {}",
+            names.join(", ")
+        );
+    }
+}
+
+// Weather for the mood: sunny when it's doing well, a cloud when it's down, a storm when it's
+// angry.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BadgeIcon {
+    Sun,
+    Cloud,
+    Storm,
+}
+
+impl BadgeIcon {
+    fn for_state(state: CubeState) -> BadgeIcon {
+        match state {
+            CubeState::Happy | CubeState::Excited | CubeState::Dizzy | CubeState::Hungry => {
+                BadgeIcon::Sun
+            }
+            CubeState::Sad | CubeState::Sleepy | CubeState::Scared | CubeState::Sulking => {
+                BadgeIcon::Cloud
+            }
+            CubeState::Angry => BadgeIcon::Storm,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct BadgeImages {
+    sun: Handle<Image>,
+    cloud: Handle<Image>,
+    storm: Handle<Image>,
+}
+
+impl BadgeImages {
+    fn get(&self, icon: BadgeIcon) -> Handle<Image> {
+        match icon {
+            BadgeIcon::Sun => self.sun.clone(),
+            BadgeIcon::Cloud => self.cloud.clone(),
+            BadgeIcon::Storm => self.storm.clone(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct StateBadge(BadgeIcon);
+
+// The icon in white on a dark tile a pixel bigger all round, with its corners cut off, so it shows
+// against any background.
+fn badge_image(icon: &[&str]) -> Image {
+    let side = icon.len() + 2;
+    let mut data = Vec::with_capacity(side * side * 4);
+    for y in 0..side {
+        for x in 0..side {
+            let corner = (x == 0 || x == side - 1) && (y == 0 || y == side - 1);
+            let lit = (1..side - 1).contains(&x)
+                && (1..side - 1).contains(&y)
+                && icon[y - 1].as_bytes()[x - 1] == b'#';
+            data.extend_from_slice(match (corner, lit) {
+                (true, _) => &[0, 0, 0, 0],
+                (false, true) => &[255, 255, 255, 255],
+                (false, false) => &[0, 0, 0, 200],
+            });
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: side as u32,
+            height: side as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+fn setup_state_badges(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    commands.insert_resource(BadgeImages {
+        sun: images.add(badge_image(&SUN)),
+        cloud: images.add(badge_image(&CLOUD)),
+        storm: images.add(badge_image(&STORM)),
+    });
+}
+
+// Only with the high contrast colors, kept in the corner as the canvas changes size.
+fn show_state_badge(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    state: Res<State<CubeState>>,
+    canvas: Res<CanvasSettings>,
+    images: Res<BadgeImages>,
+    mut badges: Query<(Entity, &mut StateBadge, &mut Handle<Image>, &mut Transform)>,
+) {
+    let wanted =
+        (settings.colors == ColorPreset::HighContrast).then(|| BadgeIcon::for_state(*state.get()));
+    let Some(icon) = wanted else {
+        for (entity, ..) in &badges {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let side = (SUN.len() + 2) as f32;
+    let corner = Vec2::new(-canvas.size().x, canvas.size().y) / 2.0;
+    let position = corner + Vec2::new(BADGE_MARGIN + side / 2.0, -BADGE_MARGIN - side / 2.0);
+    let translation = position.extend(BADGE_Z);
+
+    let Ok((_, mut badge, mut image, mut transform)) = badges.get_single_mut() else {
+        commands.spawn((
+            SpriteBundle {
+                texture: images.get(icon),
+                transform: Transform::from_translation(translation),
+                ..default()
+            },
+            RenderLayers::layer(CANVAS_LAYER),
+            StateBadge(icon),
+        ));
+        return;
+    };
+    if badge.0 != icon {
+        badge.0 = icon;
+        *image = images.get(icon);
+    }
+    if transform.translation != translation {
+        transform.translation = translation;
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::{
+    pbr::{
+        ScreenSpaceAmbientOcclusionBundle, ScreenSpaceAmbientOcclusionQualityLevel,
+        ScreenSpaceAmbientOcclusionSettings,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+// Darkens the ambient light in the cube's creases and where it's near the wall, once per canvas
+// pixel rather than per window pixel.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AmbientOcclusion {
+    #[default]
+    Off,
+    Low,
+    Medium,
+    High,
+    Ultra,
+}
+
+impl AmbientOcclusion {
+    fn quality_level(self) -> Option<ScreenSpaceAmbientOcclusionQualityLevel> {
+        match self {
+            AmbientOcclusion::Off => None,
+            AmbientOcclusion::Low => Some(ScreenSpaceAmbientOcclusionQualityLevel::Low),
+            AmbientOcclusion::Medium => Some(ScreenSpaceAmbientOcclusionQualityLevel::Medium),
+            AmbientOcclusion::High => Some(ScreenSpaceAmbientOcclusionQualityLevel::High),
+            AmbientOcclusion::Ultra => Some(ScreenSpaceAmbientOcclusionQualityLevel::Ultra),
+        }
+    }
+}
+
+// What the canvas camera needs for ambient occlusion, none when it's off in the settings. The
+// prepasses come with it, so off skips those too.
+pub fn camera_ambient_occlusion(settings: &Settings) -> Option<ScreenSpaceAmbientOcclusionBundle> {
+    settings
+        .ambient_occlusion
+        .quality_level()
+        .map(|quality_level| ScreenSpaceAmbientOcclusionBundle {
+            settings: ScreenSpaceAmbientOcclusionSettings { quality_level },
+            ..default()
+        })
+}
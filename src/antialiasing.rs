@@ -0,0 +1,29 @@
+use bevy::{core_pipeline::fxaa::Fxaa, prelude::*};
+
+use crate::settings::Settings;
+
+pub struct AntialiasingPlugin;
+
+impl Plugin for AntialiasingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, smooth_edges.run_if(resource_changed::<Settings>));
+    }
+}
+
+// Softens the stair steps along the cube's edges before the canvas is scaled up, for a cleaner
+// look over crunchy pixels. It's a pass over the finished canvas rather than multisampling, which
+// stays off, so it's always on the camera and only switched on and off.
+pub fn camera_fxaa(settings: &Settings) -> Fxaa {
+    Fxaa {
+        enabled: settings.fxaa,
+        ..default()
+    }
+}
+
+fn smooth_edges(settings: Res<Settings>, mut cameras: Query<&mut Fxaa>) {
+    for mut fxaa in &mut cameras {
+        if fxaa.enabled != settings.fxaa {
+            fxaa.enabled = settings.fxaa;
+        }
+    }
+}
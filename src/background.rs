@@ -0,0 +1,371 @@
+use bevy::{
+    asset::LoadState,
+    core_pipeline::Skybox,
+    ecs::system::SystemParam,
+    prelude::*,
+    render::render_resource::{TextureViewDescriptor, TextureViewDimension},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accessibility::{ColorPreset, ColorTables},
+    fade::Fade,
+    pet_mode::PetMode,
+    settings::Settings,
+    time_of_day::TimeOfDay,
+    CubeState, CUBE_POSITION,
+};
+
+// far enough behind the cube that its shadow falls clear of it
+const BACKDROP_DEPTH: f32 = 7.0;
+// a little taller than the view at that depth, so the whole gradient shows, and wider than any
+// canvas shape
+const BACKDROP_SIZE: Vec2 = Vec2::new(100.0, 20.0);
+// per second, most of the way to a new state's colors in a couple of seconds
+const SHIFT_RATE: f32 = 1.5;
+// about what a sky looks like under the cube's lights
+const SKYBOX_BRIGHTNESS: f32 = 1000.0;
+// how long the plain background takes to fade over to a new state's color
+const CLEAR_FADE_SECS: f32 = 1.0;
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_background).add_systems(
+            Update,
+            (
+                attach_skybox.run_if(resource_exists::<SkyboxImage>),
+                shift_backdrop.run_if(resource_exists::<BackgroundColors>),
+                shift_skybox,
+                (
+                    reload_background_colors.run_if(resource_changed::<Settings>),
+                    fade_clear_color,
+                )
+                    .chain()
+                    .run_if(resource_exists::<BackgroundColors>),
+            ),
+        );
+    }
+}
+
+// What's behind the cube. Pet mode always goes without, the desktop is the background there.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Background {
+    // the flat clear color, with a wall in it only while there's a shadow to catch
+    Plain,
+    // top to bottom in colors that follow the cube's mood
+    #[default]
+    Gradient,
+    // a cubemap under assets/, its six square faces stacked top to bottom in the order
+    // +X, -X, +Y, -Y, +Z, -Z
+    Skybox(String),
+}
+
+// The wall behind the cube. Lit like the cube, so it catches its shadow.
+#[derive(Component)]
+struct Backdrop {
+    // the gradient, top then bottom, as it's shown right now
+    colors: Option<(Vec3, Vec3)>,
+}
+
+// A color for each state, the built-in ones with the color preset's and then any from the settings
+// on top.
+pub struct StateColors(HashMap<CubeState, Color>);
+
+impl StateColors {
+    pub fn load(
+        colors: HashMap<CubeState, String>,
+        overrides: &HashMap<CubeState, String>,
+        what: &str,
+    ) -> Self {
+        let mut colors: HashMap<_, _> = colors
+            .into_iter()
+            .map(|(state, hex)| (state, Color::from(Srgba::hex(hex).expect("valid hex"))))
+            .collect();
+
+        for (state, hex) in overrides {
+            match Srgba::hex(hex) {
+                Ok(color) => {
+                    colors.insert(*state, color.into());
+                }
+                Err(error) => warn!("{what} {hex:?} for {state:?} isn't a hex color: {error}"),
+            }
+        }
+
+        StateColors(colors)
+    }
+
+    pub fn get(&self, state: CubeState) -> Color {
+        self.0.get(&state).copied().unwrap_or_default()
+    }
+}
+
+// The plain background's color and the gradient's for each state, and the preset they were
+// picked for.
+#[derive(Resource)]
+struct BackgroundColors {
+    clear: StateColors,
+    gradients: HashMap<CubeState, (Vec3, Vec3)>,
+    preset: ColorPreset,
+}
+
+impl BackgroundColors {
+    fn load(settings: &Settings) -> Self {
+        let tables = ColorTables::load();
+        BackgroundColors {
+            clear: StateColors::load(
+                tables.each(settings.colors, |look| look.clear.as_ref()),
+                &settings.clear_colors,
+                "clear color",
+            ),
+            gradients: tables.each(settings.colors, |look| look.gradient.as_ref()),
+            preset: settings.colors,
+        }
+    }
+
+    // Top and bottom of the gradient, in sRGB.
+    fn gradient(&self, state: CubeState) -> (Vec3, Vec3) {
+        self.gradients
+            .get(&state)
+            .copied()
+            .unwrap_or((Vec3::ZERO, Vec3::ZERO))
+    }
+}
+
+// What the background follows, the cube's state dimmed and tinted by the time of day.
+#[derive(SystemParam)]
+struct Ambience<'w> {
+    state: Res<'w, State<CubeState>>,
+    time_of_day: Res<'w, TimeOfDay>,
+}
+
+// Held until it loads, the asset server hands it over as a flat image.
+#[derive(Resource)]
+struct SkyboxImage(Handle<Image>);
+
+// How bright the sky is next to happy. A skybox can't be tinted, so the mood only dims it.
+fn skybox_brightness(state: CubeState) -> f32 {
+    match state {
+        CubeState::Happy | CubeState::Excited | CubeState::Hungry => 1.0,
+        CubeState::Angry | CubeState::Dizzy => 0.8,
+        CubeState::Sad | CubeState::Sulking | CubeState::Scared => 0.5,
+        CubeState::Sleepy => 0.3,
+    }
+}
+
+fn setup_background(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    pet_mode: Res<PetMode>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if pet_mode.enabled {
+        return;
+    }
+    let background_colors = BackgroundColors::load(&settings);
+    let starting_gradient = background_colors.gradient(CubeState::default());
+    commands.insert_resource(background_colors);
+
+    let colors = match &settings.background {
+        Background::Plain if settings.shadows.enabled() => None,
+        Background::Gradient => Some(starting_gradient),
+        Background::Plain => return,
+        Background::Skybox(path) => {
+            commands.insert_resource(SkyboxImage(asset_server.load(path.clone())));
+            return;
+        }
+    };
+
+    // plain takes on the clear color around it once it starts fading
+    let (top, bottom) = colors.map_or((Color::BLACK, Color::BLACK), |(top, bottom)| {
+        (srgb(top), srgb(bottom))
+    });
+    let mesh = Mesh::from(Rectangle::from_size(BACKDROP_SIZE))
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, gradient(top, bottom));
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(mesh),
+            material: materials.add(StandardMaterial {
+                perceptual_roughness: 1.0,
+                reflectance: 0.1,
+                ..default()
+            }),
+            transform: Transform::from_translation(CUBE_POSITION - Vec3::Z * BACKDROP_DEPTH),
+            ..default()
+        },
+        Backdrop { colors },
+        Name::new("Backdrop"),
+    ));
+}
+
+fn srgb(color: Vec3) -> Color {
+    Color::srgb(color.x, color.y, color.z)
+}
+
+fn tinted(color: Color, tint: Vec3) -> Color {
+    let color = color.to_srgba();
+    srgb(Vec3::new(color.red, color.green, color.blue) * tint)
+}
+
+// Vertex colors for a rectangle, which lists its top corners first.
+fn gradient(top: Color, bottom: Color) -> Vec<[f32; 4]> {
+    let top = top.to_linear().to_f32_array();
+    let bottom = bottom.to_linear().to_f32_array();
+    vec![top, top, bottom, bottom]
+}
+
+fn shift_backdrop(
+    time: Res<Time>,
+    ambience: Ambience,
+    colors: Res<BackgroundColors>,
+    mut backdrops: Query<(&mut Backdrop, &Handle<Mesh>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let tint = ambience.time_of_day.tint();
+    let (top, bottom) = colors.gradient(*ambience.state.get());
+    let (top, bottom) = (top * tint, bottom * tint);
+    let blend = (SHIFT_RATE * time.delta_seconds()).min(1.0);
+
+    for (mut backdrop, handle) in &mut backdrops {
+        // plain stays plain
+        let Some((current_top, current_bottom)) = backdrop.colors else {
+            continue;
+        };
+        if (current_top, current_bottom) == (top, bottom) {
+            continue;
+        }
+        let Some(mesh) = meshes.get_mut(handle) else {
+            continue;
+        };
+
+        // close enough is all the way, or it would creep on forever
+        let snap = |current: Vec3, target: Vec3| {
+            let next = current.lerp(target, blend);
+            if next.distance_squared(target) < 1e-6 {
+                target
+            } else {
+                next
+            }
+        };
+        let colors = (snap(current_top, top), snap(current_bottom, bottom));
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_COLOR,
+            gradient(srgb(colors.0), srgb(colors.1)),
+        );
+        backdrop.colors = Some(colors);
+    }
+}
+
+// Once the stacked image is in, it's reinterpreted as the six sides of a cube and put behind the
+// canvas camera.
+fn attach_skybox(
+    mut commands: Commands,
+    skybox: Res<SkyboxImage>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<Entity, With<Camera3d>>,
+) {
+    if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&skybox.0) {
+        error!("couldn't load the skybox, keeping the plain background: {error}");
+        commands.remove_resource::<SkyboxImage>();
+        return;
+    }
+    let Some(image) = images.get_mut(&skybox.0) else {
+        return;
+    };
+    commands.remove_resource::<SkyboxImage>();
+
+    // one that's already a cubemap comes in with its layers
+    if image.texture_descriptor.size.depth_or_array_layers == 1 {
+        let size = image.size();
+        if size.y != size.x * 6 {
+            error!(
+                "the skybox should be six square faces stacked top to bottom, it's {}x{}",
+                size.x, size.y
+            );
+            return;
+        }
+        image.reinterpret_stacked_2d_as_array(6);
+    }
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..default()
+    });
+
+    for camera in &cameras {
+        commands.entity(camera).insert(Skybox {
+            image: skybox.0.clone(),
+            brightness: SKYBOX_BRIGHTNESS,
+        });
+    }
+}
+
+fn shift_skybox(time: Res<Time>, ambience: Ambience, mut skyboxes: Query<&mut Skybox>) {
+    let target = SKYBOX_BRIGHTNESS
+        * skybox_brightness(*ambience.state.get())
+        * ambience.time_of_day.brightness();
+    let blend = (SHIFT_RATE * time.delta_seconds()).min(1.0);
+
+    for mut skybox in &mut skyboxes {
+        if skybox.brightness != target {
+            skybox.brightness = if (skybox.brightness - target).abs() < 1.0 {
+                target
+            } else {
+                skybox.brightness + (target - skybox.brightness) * blend
+            };
+        }
+    }
+}
+
+fn reload_background_colors(settings: Res<Settings>, mut colors: ResMut<BackgroundColors>) {
+    if colors.preset != settings.colors {
+        *colors = BackgroundColors::load(&settings);
+    }
+}
+
+// Only the canvas camera's clear color, the window's bars around the canvas keep theirs.
+fn fade_clear_color(
+    time: Res<Time>,
+    ambience: Ambience,
+    colors: Res<BackgroundColors>,
+    mut cameras: Query<&mut Camera, With<Camera3d>>,
+    backdrops: Query<(&Backdrop, &Handle<Mesh>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut fade: Local<Option<Fade<Color>>>,
+) {
+    let target = tinted(
+        colors.clear.get(*ambience.state.get()),
+        ambience.time_of_day.tint(),
+    );
+    // toward the current state's color, eased in Oklab
+    let fade = fade.get_or_insert_with(|| {
+        Fade::new(target, CLEAR_FADE_SECS, |from, to, t| {
+            Oklaba::from(from).mix(&Oklaba::from(to), t).into()
+        })
+    });
+    fade.retarget(target);
+    // nothing left to move once it's there
+    if fade.finished() {
+        return;
+    }
+    fade.tick(time.delta_seconds());
+
+    let color = fade.value();
+    for mut camera in &mut cameras {
+        camera.clear_color = ClearColorConfig::Custom(color);
+    }
+    // a plain wall stands in for the clear color behind it
+    for (backdrop, handle) in &backdrops {
+        if backdrop.colors.is_some() {
+            continue;
+        }
+        if let Some(mesh) = meshes.get_mut(handle) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, gradient(color, color));
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    canvas_per_window_pixel, config::read_override, cube_click_update, gamepad::LookInput,
+    happy_cube_update, manual_override::no_manual_override, rushing_at_center,
+    time_of_day::TimeOfDay, CanvasPointer, Cube, CubeState, CubeSystems, CURSOR_MOVE_THRESHOLD,
+};
+
+const EMBEDDED_CONFIG: &str = include_str!("../assets/behavior.ron");
+const CONFIG_FILE_NAME: &str = "behavior.ron";
+
+pub struct BehaviorPlugin;
+
+impl Plugin for BehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BehaviorConfig::load()).add_systems(
+            Update,
+            // the state behaviors record what the cursor did this frame first
+            evaluate_transitions
+                .in_set(CubeSystems::Behavior)
+                .run_if(no_manual_override)
+                .after(happy_cube_update)
+                .after(cube_click_update),
+        );
+    }
+}
+
+// What has to happen for a transition to fire.
+#[derive(Deserialize, Debug, Clone, Copy)]
+enum Trigger {
+    CursorLost,
+    CursorReturned,
+    // the cursor moved away from where the cube last saw it
+    CursorMoved,
+    // the cursor sat still for this long, less in the middle of the night
+    Idle { secs: f32 },
+    // at least this many clicks on the cube within `secs`
+    ClickSpam { clicks: usize, secs: f32 },
+    // the cursor swept this far around the window center within `secs`
+    Swirl { degrees: f32, secs: f32 },
+    // the cursor moved faster than this in any direction, in canvas pixels per second
+    FastCursor { speed: f32 },
+    // the cursor dashed straight at the window center faster than this, in window pixels per second
+    RushAtCenter { speed: f32 },
+}
+
+#[derive(Deserialize)]
+struct TransitionRule {
+    from: String,
+    to: String,
+    when: Trigger,
+}
+
+#[derive(Deserialize)]
+struct BehaviorFile {
+    transitions: Vec<TransitionRule>,
+}
+
+#[derive(Debug)]
+struct Transition {
+    from: CubeState,
+    to: CubeState,
+    when: Trigger,
+}
+
+// The transition table, checked top to bottom every frame.
+#[derive(Resource, Debug)]
+pub struct BehaviorConfig {
+    transitions: Vec<Transition>,
+}
+
+impl BehaviorConfig {
+    // The file next to the executable wins over the embedded table, as long as it parses.
+    fn load() -> Self {
+        let file = read_override(CONFIG_FILE_NAME).unwrap_or_else(|| {
+            ron::from_str(EMBEDDED_CONFIG).expect("embedded behavior.ron is valid")
+        });
+
+        Self::from_file(file)
+    }
+
+    fn from_file(file: BehaviorFile) -> Self {
+        let transitions = file
+            .transitions
+            .into_iter()
+            .filter_map(|rule| {
+                let (Some(from), Some(to)) = (state_named(&rule.from), state_named(&rule.to))
+                else {
+                    error!(
+                        "skipping the {} -> {} transition, unknown state name",
+                        rule.from, rule.to
+                    );
+                    return None;
+                };
+
+                Some(Transition {
+                    from,
+                    to,
+                    when: rule.when,
+                })
+            })
+            .collect();
+
+        BehaviorConfig { transitions }
+    }
+}
+
+fn state_named(name: &str) -> Option<CubeState> {
+    match name {
+        "Happy" => Some(CubeState::Happy),
+        "Sad" => Some(CubeState::Sad),
+        "Sleepy" => Some(CubeState::Sleepy),
+        "Angry" => Some(CubeState::Angry),
+        "Dizzy" => Some(CubeState::Dizzy),
+        "Excited" => Some(CubeState::Excited),
+        "Scared" => Some(CubeState::Scared),
+        "Hungry" => Some(CubeState::Hungry),
+        "Sulking" => Some(CubeState::Sulking),
+        _ => None,
+    }
+}
+
+impl Trigger {
+    fn fired(
+        &self,
+        cube_prop: &Cube,
+        cursor: Option<Vec2>,
+        cursor_gone: bool,
+        now: f32,
+        canvas_scale: f32,
+        idle_scale: f32,
+    ) -> bool {
+        match *self {
+            Trigger::CursorLost => cursor_gone,
+            Trigger::CursorReturned => !cursor_gone,
+            Trigger::CursorMoved => cursor.is_some_and(|position| {
+                position.distance(cube_prop.last_cursor_pos) > CURSOR_MOVE_THRESHOLD
+            }),
+            Trigger::Idle { secs } => cube_prop.idle.elapsed_secs() >= secs * idle_scale,
+            Trigger::ClickSpam { clicks, secs } => {
+                let recent = cube_prop
+                    .recent_clicks
+                    .iter()
+                    .filter(|click| now - *click <= secs);
+                recent.count() >= clicks
+            }
+            Trigger::Swirl { degrees, secs } => {
+                let swept: f32 = cube_prop
+                    .swirl
+                    .iter()
+                    .filter(|(sample_time, _)| now - sample_time <= secs)
+                    .map(|(_, angle)| angle)
+                    .sum();
+                swept.abs() > degrees.to_radians()
+            }
+            Trigger::FastCursor { speed } => {
+                cube_prop.cursor_velocity.length() * canvas_scale > speed
+            }
+            Trigger::RushAtCenter { speed } => cube_prop.prev_cursor_pos.is_some_and(|position| {
+                rushing_at_center(position, cube_prop.cursor_velocity, speed)
+            }),
+        }
+    }
+}
+
+// The game clock for the click and swirl windows, and the wall clock for how soon the cube dozes.
+#[derive(SystemParam)]
+struct Clocks<'w> {
+    time: Res<'w, Time>,
+    time_of_day: Res<'w, TimeOfDay>,
+}
+
+fn evaluate_transitions(
+    clocks: Clocks,
+    config: Res<BehaviorConfig>,
+    state: Res<State<CubeState>>,
+    look_input: Res<LookInput>,
+    pointer: CanvasPointer,
+    query_cube: Query<&Cube>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    let cube_prop = query_cube.single();
+    if cube_prop.playing_clip {
+        return;
+    }
+
+    // the right stick counts as a cursor while it's held
+    let cursor = look_input.position(&pointer.input);
+    // the look math goes by the position, but leaving and coming back goes by the window's events
+    let cursor_gone = pointer.input.pointer_gone() && !look_input.stick_active();
+    let now = clocks.time.elapsed_seconds();
+    // a stretched canvas moves faster one way than the other, this goes by the slower
+    let canvas_scale =
+        canvas_per_window_pixel(pointer.input.window_size, pointer.projections.single())
+            .min_element();
+
+    let idle_scale = clocks.time_of_day.idle_scale();
+
+    let fired = config.transitions.iter().find(|transition| {
+        transition.from == *state.get()
+            && transition.when.fired(
+                cube_prop,
+                cursor,
+                cursor_gone,
+                now,
+                canvas_scale,
+                idle_scale,
+            )
+    });
+
+    if let Some(transition) = fired {
+        next_state.set(transition.to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        state::app::StatesPlugin,
+        time::TimeUpdateStrategy,
+        window::{CursorEntered, CursorLeft, PrimaryWindow},
+    };
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        canvas::CanvasSettings,
+        input::{track_pointer_presence, InputState},
+        settings::Settings,
+        MainCamera,
+    };
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    // Just the presence tracking and the transition table, with the window events sent by hand.
+    fn headless_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+            .init_state::<CubeState>()
+            .add_event::<CursorEntered>()
+            .add_event::<CursorLeft>()
+            .init_resource::<InputState>()
+            .init_resource::<LookInput>()
+            .init_resource::<TimeOfDay>()
+            .insert_resource(CanvasSettings::load(&Settings::default()))
+            .insert_resource(BehaviorConfig::from_file(
+                ron::from_str(EMBEDDED_CONFIG).unwrap(),
+            ))
+            .add_systems(PreUpdate, track_pointer_presence)
+            .add_systems(Update, evaluate_transitions);
+
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id();
+        app.world_mut()
+            .spawn((OrthographicProjection::default(), MainCamera));
+        app.world_mut().spawn(Cube::default());
+
+        app.world_mut().send_event(CursorEntered { window });
+        app.update();
+        (app, window)
+    }
+
+    fn state(app: &App) -> CubeState {
+        *app.world().resource::<State<CubeState>>().get()
+    }
+
+    #[test]
+    fn a_blink_out_of_the_window_stays_happy() {
+        let (mut app, window) = headless_app();
+
+        app.world_mut().send_event(CursorLeft { window });
+        app.update();
+        app.world_mut().send_event(CursorEntered { window });
+        for _ in 0..60 {
+            app.update();
+        }
+
+        assert_eq!(state(&app), CubeState::Happy);
+    }
+
+    #[test]
+    fn leaving_for_good_turns_sad() {
+        let (mut app, window) = headless_app();
+
+        app.world_mut().send_event(CursorLeft { window });
+        for _ in 0..60 {
+            app.update();
+        }
+
+        assert_eq!(state(&app), CubeState::Sad);
+    }
+}
@@ -0,0 +1,279 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        texture::ImageSampler,
+    },
+    sprite::Mesh2dHandle,
+    window::{PrimaryWindow, WindowResizeConstraints},
+};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+use crate::{
+    canvas_material::CanvasMaterial, compare::CompareStill, input::InputState, keybindings::Action,
+    settings::Settings, CanvasImage,
+};
+
+// what the hotkey steps through, all 16:9 like the window starts out
+const CANVAS_PRESETS: [UVec2; 3] = [
+    UVec2::new(640, 360),
+    UVec2::new(480, 270),
+    UVec2::new(320, 180),
+];
+// anything smaller can't fit the cube, anything bigger isn't low-res any more
+pub const MIN_CANVAS_SIZE: UVec2 = UVec2::new(64, 36);
+const MAX_CANVAS_SIZE: UVec2 = UVec2::new(1920, 1080);
+
+pub struct CanvasPlugin;
+
+impl Plugin for CanvasPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, warn_about_rejected_size)
+            .add_systems(
+                Update,
+                (
+                    cycle_canvas_size,
+                    cycle_canvas_scaling,
+                    (rebuild_canvas, constrain_window).run_if(resource_changed::<CanvasSettings>),
+                    // after the rebuild, a new image starts out with the default sampler
+                    smooth_canvas.run_if(
+                        resource_changed::<Settings>.or_else(resource_changed::<CanvasImage>),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// How the canvas is scaled up to the window. Only whole multiples keep every canvas pixel the same
+// size, the others trade that for filling more of the window.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CanvasScaling {
+    #[default]
+    Integer,
+    // as big as fits without cropping, letterboxed along one side
+    Fit,
+    // the whole window, out of shape if the window is
+    Stretch,
+}
+
+impl CanvasScaling {
+    // sampled smoothly, nearest would leave uneven pixels crawling around as the cube moves
+    fn fractional(self) -> bool {
+        self != CanvasScaling::Integer
+    }
+}
+
+// How many pixels the cube is drawn at before it's scaled up to the window. `--canvas 480x270`
+// wins over `canvas_size` in the settings for one run.
+#[derive(Resource, Clone, Debug)]
+pub struct CanvasSettings {
+    pub resolution: UVec2,
+    // held on to until logging is up
+    rejected: Option<String>,
+}
+
+impl CanvasSettings {
+    pub fn load(settings: &Settings) -> Self {
+        let mut args = env::args().skip_while(|arg| arg != "--canvas").skip(1);
+        let from_args = args.next().map(|arg| {
+            arg.split_once('x')
+                .and_then(|(width, height)| {
+                    Some(UVec2::new(width.parse().ok()?, height.parse().ok()?))
+                })
+                .ok_or(arg)
+        });
+
+        let (resolution, rejected) = match from_args.or(settings.canvas_size.map(Ok)) {
+            Some(Ok(resolution))
+                if resolution.clamp(MIN_CANVAS_SIZE, MAX_CANVAS_SIZE) == resolution =>
+            {
+                (resolution, None)
+            }
+            Some(Ok(resolution)) => (
+                CANVAS_PRESETS[0],
+                Some(format!("{}x{} is out of range", resolution.x, resolution.y)),
+            ),
+            Some(Err(arg)) => (
+                CANVAS_PRESETS[0],
+                Some(format!("{arg:?} isn't a size like 480x270")),
+            ),
+            None => (CANVAS_PRESETS[0], None),
+        };
+
+        CanvasSettings {
+            resolution,
+            rejected,
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.resolution.as_vec2()
+    }
+}
+
+fn warn_about_rejected_size(canvas: Res<CanvasSettings>) {
+    if let Some(rejected) = &canvas.rejected {
+        warn!(
+            "canvas size {rejected}, it has to be between {}x{} and {}x{}, using {}x{}",
+            MIN_CANVAS_SIZE.x,
+            MIN_CANVAS_SIZE.y,
+            MAX_CANVAS_SIZE.x,
+            MAX_CANVAS_SIZE.y,
+            canvas.resolution.x,
+            canvas.resolution.y
+        );
+    }
+}
+
+// The low-res image the 3D camera draws the cube into.
+pub fn canvas_image(resolution: UVec2) -> Image {
+    let canvas_size = Extent3d {
+        width: resolution.x,
+        height: resolution.y,
+        ..default()
+    };
+
+    let mut canvas = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size: canvas_size,
+            dimension: TextureDimension::D2,
+            // keeps its alpha through the render, so pet mode's desktop shows where nothing is drawn
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            // copied out of for screenshots
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+
+    // fill image.data with zeroes
+    canvas.resize(canvas_size);
+    canvas
+}
+
+fn cycle_canvas_size(
+    input: Res<InputState>,
+    mut settings: ResMut<Settings>,
+    mut canvas: ResMut<CanvasSettings>,
+) {
+    if !input.just_did(Action::CanvasSize) {
+        return;
+    }
+
+    // a size from the settings that isn't one of the presets goes back to the first
+    let next = CANVAS_PRESETS
+        .iter()
+        .position(|preset| *preset == canvas.resolution)
+        .map_or(0, |index| (index + 1) % CANVAS_PRESETS.len());
+    canvas.resolution = CANVAS_PRESETS[next];
+    settings.canvas_size = Some(canvas.resolution);
+    info!("drawing at {}x{}", canvas.resolution.x, canvas.resolution.y);
+}
+
+fn cycle_canvas_scaling(input: Res<InputState>, mut settings: ResMut<Settings>) {
+    if !input.just_did(Action::CanvasScaling) {
+        return;
+    }
+
+    settings.scaling = match settings.scaling {
+        CanvasScaling::Integer => CanvasScaling::Fit,
+        CanvasScaling::Fit => CanvasScaling::Stretch,
+        CanvasScaling::Stretch => CanvasScaling::Integer,
+    };
+    info!("scaling the canvas to {:?}", settings.scaling);
+}
+
+// Only the canvas gets the smooth sampler, the sprites on it stay crisp. Changing it rebuilds the
+// image on the GPU, so the canvas material's bind group is rebuilt too, or it would hang on to the
+// old sampler until the next restart. The material also hears which it is, to blend the canvas
+// itself wherever the dither or the palette need it taken a pixel at a time.
+fn smooth_canvas(
+    settings: Res<Settings>,
+    canvas: Res<CanvasImage>,
+    mut images: ResMut<Assets<Image>>,
+    mut canvas_materials: ResMut<Assets<CanvasMaterial>>,
+    quads: Query<&Handle<CanvasMaterial>>,
+    mut applied: Local<Option<(AssetId<Image>, bool)>>,
+) {
+    let wanted = (
+        canvas.0.id(),
+        settings.scaling.fractional() || settings.linear_upscale,
+    );
+    if *applied == Some(wanted) {
+        return;
+    }
+    let Some(image) = images.get_mut(&canvas.0) else {
+        return;
+    };
+
+    image.sampler = if wanted.1 {
+        ImageSampler::linear()
+    } else {
+        ImageSampler::Default
+    };
+    for material in &quads {
+        if let Some(material) = canvas_materials.get_mut(material) {
+            material.set_smooth(wanted.1);
+        }
+    }
+    *applied = Some(wanted);
+}
+
+// Swaps in a fresh image at the new size everywhere the old one was held. Those were its only
+// handles, so dropping them frees it, and fit_canvas picks up the new size by itself.
+fn rebuild_canvas(
+    canvas: Res<CanvasSettings>,
+    mut images: ResMut<Assets<Image>>,
+    mut current: ResMut<CanvasImage>,
+    mut cameras: Query<&mut Camera, With<Camera3d>>,
+    mut quads: Query<(&mut Mesh2dHandle, &Handle<CanvasMaterial>), Without<CompareStill>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut canvas_materials: ResMut<Assets<CanvasMaterial>>,
+) {
+    // setup_camera made the first one already
+    if canvas.is_added() {
+        return;
+    }
+
+    let image = images.add(canvas_image(canvas.resolution));
+    for mut camera in &mut cameras {
+        camera.target = RenderTarget::Image(image.clone());
+    }
+    for (mut mesh, material) in &mut quads {
+        *mesh = meshes.add(Rectangle::from_size(canvas.size())).into();
+        if let Some(material) = canvas_materials.get_mut(material) {
+            material.set_canvas(image.clone());
+        }
+    }
+    current.0 = image;
+}
+
+// The window can't shrink below the canvas, as PetMode::window set it up from the first size, so
+// a new one moves that limit along.
+fn constrain_window(
+    canvas: Res<CanvasSettings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let size = canvas.size();
+    for mut window in &mut windows {
+        let constraints = window.resize_constraints;
+        if constraints.min_width != size.x || constraints.min_height != size.y {
+            window.resize_constraints = WindowResizeConstraints {
+                min_width: size.x,
+                min_height: size.y,
+                ..constraints
+            };
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::{
+    asset::load_internal_asset,
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+    sprite::{Material2d, Material2dPlugin},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{input::InputState, keybindings::Action, palette::PALETTE_IMAGE, settings::Settings};
+
+pub const CANVAS_SHADER: Handle<Shader> =
+    Handle::weak_from_u128(0x6d63_6b65_6e7a_6965_6372_745f_7368_6472);
+
+// how much each effect shows at full intensity
+const SCANLINE_STRENGTH: f32 = 0.35;
+const CURVATURE: f32 = 0.06;
+const VIGNETTE_STRENGTH: f32 = 0.5;
+// shades per color channel the dither rounds to
+const DITHER_LEVELS: f32 = 6.0;
+
+pub struct CanvasMaterialPlugin;
+
+impl Plugin for CanvasMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            CANVAS_SHADER,
+            "../assets/shaders/canvas.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_plugins(Material2dPlugin::<CanvasMaterial>::default())
+            .add_systems(
+                Update,
+                (
+                    toggle_crt,
+                    dim_scanlines.run_if(resource_changed::<Settings>),
+                ),
+            );
+    }
+}
+
+// Breaks smooth shading into a fixed pattern of fewer shades, one step of the pattern per canvas
+// pixel however big the window scales them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct Dither {
+    pub matrix: DitherMatrix,
+    // 0 rounds every pixel the same way, 1 spreads the rounding over the whole pattern
+    pub strength: f32,
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Dither {
+            matrix: DitherMatrix::default(),
+            strength: 1.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DitherMatrix {
+    // coarse crosshatch, repeats every 4 pixels
+    #[default]
+    Bayer4,
+    // finer steps between shades, repeats every 8
+    Bayer8,
+}
+
+impl DitherMatrix {
+    // how many bits of each pixel coordinate pick the threshold
+    fn bits(self) -> f32 {
+        match self {
+            DitherMatrix::Bayer4 => 2.0,
+            DitherMatrix::Bayer8 => 3.0,
+        }
+    }
+}
+
+// What the main camera draws the canvas with. With the CRT look, the dither and the palette all
+// off, it's the canvas pixel for pixel, same as a sprite.
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct CanvasMaterial {
+    // intensity, 0 for off and 1 for the full look, then the scanline, curvature and vignette
+    // strengths it scales
+    #[uniform(0)]
+    settings: Vec4,
+    #[texture(1)]
+    #[sampler(2)]
+    canvas: Handle<Image>,
+    // strength, 0 for off, then the matrix's bits and the shades per channel
+    #[uniform(3)]
+    dither: Vec4,
+    // 1 while the canvas is blended up to the window by the smooth sampler, 0 while it's taken
+    // pixel by pixel, see canvas.rs
+    #[uniform(4)]
+    upscale: Vec4,
+    // one texel per color, see palette.rs
+    #[texture(5, dimension = "1d")]
+    palette: Handle<Image>,
+    // 1 to snap every canvas pixel to the nearest color in the palette, 0 for off
+    #[uniform(6)]
+    quantize: Vec4,
+    // how much every other canvas row is darkened, 0 for not at all, apart from the CRT look's
+    #[uniform(7)]
+    scanlines: Vec4,
+    // how far red and blue split at the edges in canvas pixels, 0 for not at all, see
+    // screen_effects.rs
+    #[uniform(8)]
+    aberration: Vec4,
+}
+
+impl CanvasMaterial {
+    pub fn new(canvas: Handle<Image>, settings: &Settings) -> Self {
+        let dither = settings.dither.map_or(Vec4::ZERO, |dither| {
+            Vec4::new(
+                dither.strength.clamp(0.0, 1.0),
+                dither.matrix.bits(),
+                DITHER_LEVELS,
+                0.0,
+            )
+        });
+
+        CanvasMaterial {
+            settings: Vec4::new(
+                intensity(settings.crt),
+                SCANLINE_STRENGTH,
+                CURVATURE,
+                VIGNETTE_STRENGTH,
+            ),
+            canvas,
+            dither,
+            // smooth_canvas sets it along with the sampler
+            upscale: Vec4::ZERO,
+            palette: PALETTE_IMAGE,
+            // palette.rs turns it on once the palette is loaded
+            quantize: Vec4::ZERO,
+            scanlines: Vec4::new(scanline_opacity(settings), 0.0, 0.0, 0.0),
+            aberration: Vec4::ZERO,
+        }
+    }
+
+    pub fn set_canvas(&mut self, canvas: Handle<Image>) {
+        self.canvas = canvas;
+    }
+
+    pub fn set_smooth(&mut self, smooth: bool) {
+        self.upscale.x = intensity(smooth);
+    }
+
+    pub fn limit_to_palette(&mut self, enabled: bool) {
+        self.quantize.x = intensity(enabled);
+    }
+
+    pub fn aberration(&self) -> f32 {
+        self.aberration.x
+    }
+
+    pub fn set_aberration(&mut self, pixels: f32) {
+        self.aberration.x = pixels;
+    }
+}
+
+impl Material2d for CanvasMaterial {
+    fn fragment_shader() -> ShaderRef {
+        CANVAS_SHADER.into()
+    }
+}
+
+fn intensity(enabled: bool) -> f32 {
+    if enabled {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn scanline_opacity(settings: &Settings) -> f32 {
+    settings.scanlines.clamp(0.0, 1.0)
+}
+
+// Follows the setting as it changes, the settings panel's slider moves it live.
+fn dim_scanlines(
+    settings: Res<Settings>,
+    canvases: Query<&Handle<CanvasMaterial>>,
+    mut materials: ResMut<Assets<CanvasMaterial>>,
+) {
+    let opacity = scanline_opacity(&settings);
+    for handle in &canvases {
+        if materials
+            .get(handle)
+            .is_some_and(|material| material.scanlines.x != opacity)
+        {
+            if let Some(material) = materials.get_mut(handle) {
+                material.scanlines.x = opacity;
+            }
+        }
+    }
+}
+
+fn toggle_crt(
+    input: Res<InputState>,
+    mut settings: ResMut<Settings>,
+    canvases: Query<&Handle<CanvasMaterial>>,
+    mut materials: ResMut<Assets<CanvasMaterial>>,
+) {
+    if !input.just_did(Action::ToggleCrt) {
+        return;
+    }
+
+    settings.crt = !settings.crt;
+    for handle in &canvases {
+        if let Some(material) = materials.get_mut(handle) {
+            material.settings.x = intensity(settings.crt);
+        }
+    }
+
+    if settings.crt {
+        info!("drawing like an old CRT");
+    } else {
+        info!("drawing the canvas plain");
+    }
+}
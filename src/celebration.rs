@@ -0,0 +1,169 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+use chrono::{Datelike, Local};
+use rand::prelude::*;
+use std::f32::consts::PI;
+
+use crate::{
+    canvas::CanvasSettings, input::InputState, keybindings::Action, Cube, CubeState, CubeSystems,
+    CANVAS_LAYER,
+};
+
+// (month, day) McKenzie celebrates on, overridable with MCKENZIE_CELEBRATION_DATE=MM-DD
+const CELEBRATION_DATE: (u32, u32) = (7, 4);
+const CELEBRATION_SECS: f32 = 30.0;
+
+pub struct CelebrationPlugin;
+
+impl Plugin for CelebrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, check_celebration_date)
+            .add_systems(
+                Update,
+                (
+                    (celebration_update, spawn_confetti, celebration_lights)
+                        .run_if(resource_exists::<Celebration>),
+                    // leftovers keep falling after the party is over
+                    confetti_update,
+                    celebration_cube.in_set(CubeSystems::Overlay).run_if(
+                        resource_exists::<Celebration>.and_then(in_state(CubeState::Happy)),
+                    ),
+                ),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct Celebration {
+    timer: Timer,
+    confetti_timer: Timer,
+}
+
+#[derive(Component)]
+struct Confetti {
+    velocity: Vec2,
+    sway_phase: f32,
+}
+
+fn celebration_date() -> (u32, u32) {
+    let Ok(date) = std::env::var("MCKENZIE_CELEBRATION_DATE") else {
+        return CELEBRATION_DATE;
+    };
+
+    let parsed = date
+        .split_once('-')
+        .and_then(|(month, day)| Some((month.parse().ok()?, day.parse().ok()?)));
+
+    parsed.unwrap_or_else(|| {
+        warn!("MCKENZIE_CELEBRATION_DATE should look like MM-DD, got {date:?}");
+        CELEBRATION_DATE
+    })
+}
+
+fn check_celebration_date(mut commands: Commands) {
+    let today = Local::now().date_naive();
+
+    if (today.month(), today.day()) == celebration_date() {
+        info!("it's a special day!");
+        commands.insert_resource(Celebration {
+            timer: Timer::from_seconds(CELEBRATION_SECS, TimerMode::Once),
+            confetti_timer: Timer::from_seconds(0.05, TimerMode::Repeating),
+        });
+    }
+}
+
+fn celebration_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<InputState>,
+    mut celebration: ResMut<Celebration>,
+    mut lights: Query<&mut PointLight>,
+) {
+    celebration.timer.tick(time.delta());
+
+    if celebration.timer.finished() || input.just_did(Action::Cancel) {
+        commands.remove_resource::<Celebration>();
+
+        for mut light in &mut lights {
+            light.color = Color::WHITE;
+        }
+    }
+}
+
+fn spawn_confetti(
+    mut commands: Commands,
+    time: Res<Time>,
+    canvas: Res<CanvasSettings>,
+    mut celebration: ResMut<Celebration>,
+) {
+    let mut rng = rand::thread_rng();
+    let half_size = canvas.size() / 2.0;
+
+    for _ in 0..celebration
+        .confetti_timer
+        .tick(time.delta())
+        .times_finished_this_tick()
+    {
+        let x = rng.gen_range(-half_size.x..half_size.x);
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::hsl(rng.gen_range(0.0..360.0), 0.9, 0.6),
+                    custom_size: Some(Vec2::new(3.0, 2.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(x, half_size.y + 4.0, 1.0)
+                    .with_rotation(Quat::from_rotation_z(rng.gen_range(0.0..PI))),
+                ..default()
+            },
+            RenderLayers::layer(CANVAS_LAYER),
+            Confetti {
+                velocity: Vec2::new(rng.gen_range(-10.0..10.0), -rng.gen_range(30.0..70.0)),
+                sway_phase: rng.gen_range(0.0..2.0 * PI),
+            },
+        ));
+    }
+}
+
+// Falls with a little flutter on the canvas layer, and goes away below the bottom edge.
+fn confetti_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    canvas: Res<CanvasSettings>,
+    mut query_confetti: Query<(Entity, &mut Transform, &Confetti)>,
+) {
+    let elapsed = time.elapsed_seconds();
+
+    for (entity, mut transform, confetti) in &mut query_confetti {
+        let sway = (elapsed * 3.0 + confetti.sway_phase).sin() * 15.0;
+
+        transform.translation.x += (confetti.velocity.x + sway) * time.delta_seconds();
+        transform.translation.y += confetti.velocity.y * time.delta_seconds();
+        transform.rotate_z(2.0 * time.delta_seconds());
+
+        if transform.translation.y < -canvas.size().y / 2.0 - 4.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn celebration_lights(time: Res<Time>, mut lights: Query<&mut PointLight>) {
+    let hue = (time.elapsed_seconds() * 90.0) % 360.0;
+
+    for (i, mut light) in lights.iter_mut().enumerate() {
+        light.color = Color::hsl((hue + i as f32 * 180.0) % 360.0, 0.8, 0.7);
+    }
+}
+
+// Every couple of seconds the cube hops and spins once, ending right where it was looking.
+fn celebration_cube(time: Res<Time>, mut query_cube: Query<(&mut Transform, &Cube)>) {
+    let (mut cube_transform, cube_prop) = query_cube.single_mut();
+    let t = (time.elapsed_seconds() % 2.0) / 0.8;
+
+    if t >= 1.0 {
+        return;
+    }
+
+    cube_transform.translation = cube_prop.home + Vec3::Y * (t * PI).sin() * 0.8;
+    cube_transform.rotation = Quat::from_rotation_y(t * 2.0 * PI) * cube_transform.rotation;
+}
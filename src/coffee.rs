@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{input::InputState, keybindings::Action, Cube, CubeSystems};
+
+const CAFFEINE_SECS: f32 = 60.0;
+// the buzz fades out over this many seconds at the end instead of stopping dead
+const CAFFEINE_FADE_SECS: f32 = 5.0;
+
+pub struct CoffeePlugin;
+
+impl Plugin for CoffeePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                give_coffee,
+                (caffeine_update, caffeine_jitter)
+                    .chain()
+                    .in_set(CubeSystems::Overlay),
+            ),
+        );
+    }
+}
+
+// Present for a minute after the cube gets coffee, behaviors read `strength` to speed themselves up.
+#[derive(Component)]
+pub struct Caffeinated {
+    timer: Timer,
+}
+
+impl Default for Caffeinated {
+    fn default() -> Self {
+        Caffeinated {
+            timer: Timer::from_seconds(CAFFEINE_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Caffeinated {
+    // 1.0 while fully buzzing, easing down to 0.0 as it wears off. No coffee is 0.0 too.
+    pub fn strength(caffeinated: Option<&Caffeinated>) -> f32 {
+        let Some(caffeinated) = caffeinated else {
+            return 0.0;
+        };
+        let remaining = caffeinated.timer.remaining_secs();
+
+        (remaining / CAFFEINE_FADE_SECS).min(1.0)
+    }
+}
+
+fn give_coffee(
+    mut commands: Commands,
+    input: Res<InputState>,
+    query_cube: Query<Entity, With<Cube>>,
+) {
+    if !input.just_did(Action::Coffee) {
+        return;
+    }
+
+    // another cup starts the full minute over
+    commands
+        .entity(query_cube.single())
+        .insert(Caffeinated::default());
+}
+
+fn caffeine_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Caffeinated)>,
+) {
+    for (entity, mut caffeinated) in &mut query_cube {
+        if caffeinated.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Caffeinated>();
+        }
+    }
+}
+
+// Tiny high-frequency shivers on top of whatever the cube is doing.
+fn caffeine_jitter(mut query_cube: Query<(&mut Transform, &mut Cube, &Caffeinated)>) {
+    let mut rng = rand::thread_rng();
+
+    for (mut cube_transform, mut cube_prop, caffeinated) in &mut query_cube {
+        let amount = 0.02 * Caffeinated::strength(Some(caffeinated));
+        let jitter = Quat::from_euler(
+            EulerRot::YXZ,
+            rng.gen_range(-amount..=amount),
+            rng.gen_range(-amount..=amount),
+            0.0,
+        );
+
+        cube_transform.rotation *= jitter;
+        cube_prop.look_offset *= jitter;
+    }
+}
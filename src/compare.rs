@@ -0,0 +1,198 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    render::{
+        mesh::VertexAttributeValues,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension},
+        view::RenderLayers,
+    },
+    sprite::{Anchor, MaterialMesh2dBundle},
+};
+
+use crate::{
+    canvas_material::CanvasMaterial,
+    readback::{CanvasFrame, ReadbackPurpose, ReadbackRequests},
+    tonemapping::Tonemap,
+    CanvasImage, CANVAS_LAYER,
+};
+
+// long enough for the pipelines to be rebuilt for the other look, so the still isn't blank
+const SETTLE_SECS: f32 = 0.3;
+// over the canvas and whatever sits on it
+const COMPARE_Z: f32 = 50.0;
+
+pub struct ComparePlugin;
+
+impl Plugin for ComparePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CanvasCompare>()
+            .add_systems(Update, (take_compare_still, show_compare_still).chain());
+    }
+}
+
+// What the left half of the canvas is drawn with instead of the settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Held {
+    MsaaOff,
+    Tonemapping(Tonemap),
+}
+
+// Half the canvas frozen as it looked another way, next to the live half as the settings have it.
+// The other look is held for a moment while the still is taken, then the settings come back.
+#[derive(Resource, Default)]
+pub enum CanvasCompare {
+    #[default]
+    Off,
+    Settling(Held, Timer),
+    // the copy is on its way back from the GPU
+    Waiting(Held),
+    Showing(Held, Entity),
+}
+
+impl CanvasCompare {
+    pub fn start(&mut self, commands: &mut Commands, held: Held) {
+        self.stop(commands);
+        *self = CanvasCompare::Settling(held, Timer::from_seconds(SETTLE_SECS, TimerMode::Once));
+    }
+
+    pub fn stop(&mut self, commands: &mut Commands) {
+        if let CanvasCompare::Showing(_, still) = *self {
+            commands.entity(still).despawn_recursive();
+        }
+        *self = CanvasCompare::Off;
+    }
+
+    // What the still on the left is, or is about to be, drawn with.
+    pub fn comparing(&self) -> Option<Held> {
+        match self {
+            CanvasCompare::Off => None,
+            CanvasCompare::Settling(held, _)
+            | CanvasCompare::Waiting(held)
+            | CanvasCompare::Showing(held, _) => Some(*held),
+        }
+    }
+
+    // What has to be drawn instead of the settings right now, only until the still is taken.
+    pub fn held(&self) -> Option<Held> {
+        match self {
+            CanvasCompare::Settling(held, _) | CanvasCompare::Waiting(held) => Some(*held),
+            CanvasCompare::Off | CanvasCompare::Showing(..) => None,
+        }
+    }
+}
+
+// Marks the still, so the canvas's own rebuild leaves it be.
+#[derive(Component)]
+pub struct CompareStill;
+
+// What the still is drawn with. It goes through a copy of the canvas's material, so the CRT look,
+// the dither, the palette and the rest land on both halves alike and the setting being compared is
+// all that differs. Everything that keeps the canvas's material up to date finds the copy too.
+#[derive(SystemParam)]
+struct StillAssets<'w, 's> {
+    canvas: Res<'w, CanvasImage>,
+    quads: Query<'w, 's, &'static Handle<CanvasMaterial>, Without<CompareStill>>,
+    images: ResMut<'w, Assets<Image>>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    canvas_materials: ResMut<'w, Assets<CanvasMaterial>>,
+}
+
+fn take_compare_still(
+    time: Res<Time>,
+    mut compare: ResMut<CanvasCompare>,
+    mut readbacks: ResMut<ReadbackRequests>,
+) {
+    let CanvasCompare::Settling(held, timer) = &mut *compare else {
+        return;
+    };
+    if timer.tick(time.delta()).just_finished() {
+        readbacks.request(ReadbackPurpose::Compare);
+        *compare = CanvasCompare::Waiting(*held);
+    }
+}
+
+// The left half of the still over the left half of the canvas, with a line where the live half
+// starts.
+fn show_compare_still(
+    mut commands: Commands,
+    mut compare: ResMut<CanvasCompare>,
+    mut assets: StillAssets,
+    mut frames: EventReader<CanvasFrame>,
+) {
+    for frame in frames.read() {
+        if !matches!(frame.purpose, ReadbackPurpose::Compare) {
+            continue;
+        }
+        // the comparison was put away, or moved on, before the copy came back
+        let CanvasCompare::Waiting(held) = *compare else {
+            continue;
+        };
+
+        let mut still = Image::new(
+            Extent3d {
+                width: frame.size.x,
+                height: frame.size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            frame.data.clone(),
+            frame.format,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        // scaled up the same way as the canvas
+        if let Some(canvas) = assets.images.get(&assets.canvas.0) {
+            still.sampler = canvas.sampler.clone();
+        }
+        let Some(mut material) = assets
+            .quads
+            .iter()
+            .find_map(|quad| assets.canvas_materials.get(quad))
+            .cloned()
+        else {
+            continue;
+        };
+        material.set_canvas(assets.images.add(still));
+
+        // the left half of the canvas quad, with the left half of the still on it
+        let size = frame.size.as_vec2();
+        let mut mesh = Mesh::from(Rectangle::new(size.x / 2.0, size.y))
+            .translated_by(Vec3::NEG_X * size.x / 4.0);
+        if let Some(VertexAttributeValues::Float32x2(uvs)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+        {
+            for uv in uvs {
+                uv[0] /= 2.0;
+            }
+        }
+
+        let still = commands
+            .spawn((
+                MaterialMesh2dBundle {
+                    mesh: assets.meshes.add(mesh).into(),
+                    material: assets.canvas_materials.add(material),
+                    transform: Transform::from_xyz(0.0, 0.0, COMPARE_Z),
+                    ..default()
+                },
+                RenderLayers::layer(CANVAS_LAYER),
+                CompareStill,
+            ))
+            .with_children(|still| {
+                still.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::WHITE,
+                            custom_size: Some(Vec2::new(1.0, size.y)),
+                            anchor: Anchor::CenterLeft,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                        ..default()
+                    },
+                    RenderLayers::layer(CANVAS_LAYER),
+                ));
+            })
+            .id();
+        *compare = CanvasCompare::Showing(held, still);
+    }
+}
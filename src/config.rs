@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use std::{env, fs};
+
+// A config file dropped next to the executable, if there is one and it parses.
+pub fn read_override<T: DeserializeOwned>(file_name: &str) -> Option<T> {
+    let path = env::current_exe().ok()?.parent()?.join(file_name);
+    if !path.exists() {
+        return None;
+    }
+
+    match fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|contents| ron::from_str(&contents).map_err(|error| error.to_string()))
+    {
+        Ok(config) => {
+            info!("using {path:?}");
+            Some(config)
+        }
+        Err(error) => {
+            error!("couldn't load {path:?}, using the built-in one: {error}");
+            None
+        }
+    }
+}
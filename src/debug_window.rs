@@ -0,0 +1,166 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    render::{camera::RenderTarget, view::RenderLayers},
+    window::{PrimaryWindow, WindowCloseRequested, WindowRef, WindowResolution},
+};
+
+use crate::{
+    canvas::CanvasSettings, input::InputState, keybindings::Action, low_power::LowPowerMode,
+    settings::Settings, CanvasImage, CanvasZoom,
+};
+
+// past the main window's layers, so neither window draws the other's canvas
+const DEBUG_LAYER: usize = 3;
+
+pub struct DebugWindowPlugin;
+
+impl Plugin for DebugWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_debug_window,
+                close_debug_window,
+                label_debug_window,
+                follow_canvas.run_if(resource_changed::<CanvasImage>),
+            ),
+        );
+    }
+}
+
+// The window, and the camera and sprite that only it shows, all go together.
+#[derive(Component)]
+struct DebugView;
+
+// F10 opens a second window with the canvas at one window pixel per canvas pixel, whatever the main
+// window is scaled to.
+fn toggle_debug_window(
+    mut commands: Commands,
+    input: Res<InputState>,
+    canvas: Res<CanvasImage>,
+    canvas_settings: Res<CanvasSettings>,
+    views: Query<Entity, With<DebugView>>,
+) {
+    if !input.just_did(Action::DebugWindow) {
+        return;
+    }
+    if !views.is_empty() {
+        despawn_views(&mut commands, &views);
+        return;
+    }
+
+    let window = commands
+        .spawn((
+            Window {
+                // filled in by label_debug_window
+                title: "McKenzie Bevy canvas".into(),
+                // physical pixels, so desktop scaling doesn't smear them
+                resolution: debug_resolution(&canvas_settings),
+                resizable: false,
+                ..default()
+            },
+            DebugView,
+        ))
+        .id();
+
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                ..default()
+            },
+            ..default()
+        },
+        RenderLayers::layer(DEBUG_LAYER),
+        DebugView,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            texture: canvas.0.clone(),
+            ..default()
+        },
+        RenderLayers::layer(DEBUG_LAYER),
+        DebugView,
+    ));
+}
+
+// Windows aren't closed for us, so the main one can go to the tray, this one just goes.
+fn close_debug_window(
+    mut commands: Commands,
+    mut close_events: EventReader<WindowCloseRequested>,
+    views: Query<Entity, With<DebugView>>,
+) {
+    if close_events
+        .read()
+        .any(|event| views.contains(event.window))
+    {
+        despawn_views(&mut commands, &views);
+    }
+}
+
+// The title reports how the main window is drawing the canvas, to check against the pixels here,
+// and how fast.
+fn label_debug_window(
+    zoom: Res<CanvasZoom>,
+    settings: Res<Settings>,
+    low_power: Res<LowPowerMode>,
+    diagnostics: Res<DiagnosticsStore>,
+    primary: Query<&Window, With<PrimaryWindow>>,
+    mut debug_windows: Query<&mut Window, (With<DebugView>, Without<PrimaryWindow>)>,
+) {
+    let Ok(primary) = primary.get_single() else {
+        return;
+    };
+    let scale_factor = primary.scale_factor();
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    let cap = low_power
+        .fps_cap(&settings)
+        .map_or(String::new(), |cap| format!(" capped at {cap}"));
+    let mode = if low_power.active() {
+        ", low power"
+    } else {
+        ""
+    };
+    let title = format!(
+        "McKenzie Bevy canvas, main window at {scale_factor}x scaling, {:.0} screen pixels per canvas pixel, {:?}, {fps:.0} fps{cap}{mode}",
+        scale_factor / zoom.scale(),
+        primary.present_mode,
+    );
+
+    for mut window in &mut debug_windows {
+        // only when it changes, every write goes out to the OS
+        if window.title != title {
+            window.title.clone_from(&title);
+        }
+    }
+}
+
+fn debug_resolution(canvas: &CanvasSettings) -> WindowResolution {
+    let size = canvas.size();
+    WindowResolution::new(size.x, size.y).with_scale_factor_override(1.0)
+}
+
+// A new canvas size swaps the image out, the window goes along with it.
+fn follow_canvas(
+    canvas: Res<CanvasImage>,
+    canvas_settings: Res<CanvasSettings>,
+    mut sprites: Query<&mut Handle<Image>, With<DebugView>>,
+    mut debug_windows: Query<&mut Window, With<DebugView>>,
+) {
+    for mut texture in &mut sprites {
+        *texture = canvas.0.clone();
+    }
+    for mut window in &mut debug_windows {
+        window.resolution = debug_resolution(&canvas_settings);
+    }
+}
+
+fn despawn_views(commands: &mut Commands, views: &Query<Entity, With<DebugView>>) {
+    for view in views {
+        commands.entity(view).despawn_recursive();
+    }
+}
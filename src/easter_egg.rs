@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use std::{collections::VecDeque, f32::consts::PI};
+
+use crate::{Cube, CubeSystems};
+
+const KONAMI_CODE: [KeyCode; 10] = [
+    KeyCode::ArrowUp,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::KeyB,
+    KeyCode::KeyA,
+];
+// a pause this long between presses starts the sequence over
+const SEQUENCE_TIMEOUT_SECS: f32 = 2.0;
+const PARTY_SECS: f32 = 10.0;
+// seconds per barrel roll
+const ROLL_SECS: f32 = 0.8;
+
+pub struct EasterEggPlugin;
+
+impl Plugin for EasterEggPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeySequence>().add_systems(
+            Update,
+            (
+                match_konami_code,
+                (party_lights, party_rolls)
+                    .run_if(resource_exists::<PartyMode>)
+                    .in_set(CubeSystems::Overlay),
+            )
+                .chain(),
+        );
+    }
+}
+
+// The last few keys pressed, for spotting the code.
+#[derive(Resource, Default)]
+struct KeySequence {
+    keys: VecDeque<KeyCode>,
+    last_press: f32,
+}
+
+// Ten seconds of strobing lights and barrel rolls, exists only while the party is on.
+#[derive(Resource)]
+struct PartyMode {
+    timer: Timer,
+    // what the lights were before, put back when it ends
+    light_colors: Vec<(Entity, Color)>,
+}
+
+fn match_konami_code(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut sequence: ResMut<KeySequence>,
+    party: Option<Res<PartyMode>>,
+    lights: Query<(Entity, &PointLight)>,
+) {
+    for key in keys.get_just_pressed() {
+        let now = time.elapsed_seconds();
+        if now - sequence.last_press > SEQUENCE_TIMEOUT_SECS {
+            sequence.keys.clear();
+        }
+        sequence.last_press = now;
+
+        if sequence.keys.len() == KONAMI_CODE.len() {
+            sequence.keys.pop_front();
+        }
+        sequence.keys.push_back(*key);
+
+        if sequence.keys.iter().eq(KONAMI_CODE.iter()) && party.is_none() {
+            info!("party time!");
+            sequence.keys.clear();
+            commands.insert_resource(PartyMode {
+                timer: Timer::from_seconds(PARTY_SECS, TimerMode::Once),
+                light_colors: lights
+                    .iter()
+                    .map(|(entity, light)| (entity, light.color))
+                    .collect(),
+            });
+        }
+    }
+}
+
+fn party_lights(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut party: ResMut<PartyMode>,
+    mut lights: Query<&mut PointLight>,
+) {
+    party.timer.tick(time.delta());
+
+    if party.timer.finished() {
+        for (entity, color) in &party.light_colors {
+            if let Ok(mut light) = lights.get_mut(*entity) {
+                light.color = *color;
+            }
+        }
+        commands.remove_resource::<PartyMode>();
+        return;
+    }
+
+    // every light a different hue, all cycling round twice a second
+    let elapsed = party.timer.elapsed_secs();
+    for (i, (entity, _)) in party.light_colors.iter().enumerate() {
+        if let Ok(mut light) = lights.get_mut(*entity) {
+            let hue = (elapsed * 720.0 + i as f32 * 120.0) % 360.0;
+            light.color = Color::hsl(hue, 1.0, 0.6);
+        }
+    }
+}
+
+// Rolls on top of whatever the behavior wants, so control is simply handed back when it stops.
+fn party_rolls(party: Res<PartyMode>, mut query_cube: Query<(&mut Transform, &mut Cube)>) {
+    if party.timer.finished() {
+        return;
+    }
+
+    let roll = Quat::from_rotation_z(party.timer.elapsed_secs() / ROLL_SECS * 2.0 * PI);
+    for (mut cube_transform, mut cube_prop) in &mut query_cube {
+        cube_transform.rotation *= roll;
+        cube_prop.look_offset *= roll;
+    }
+}
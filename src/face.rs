@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use serde::Deserialize;
+
+use crate::{personality::Personality, rim::CubeMaterial, skin::SkinLibrary, Cube, CubeState};
+
+pub struct FacePlugin;
+
+impl Plugin for FacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                find_cube_face.run_if(not(resource_exists::<CubeFace>)),
+                (apply_expression, blink_update, hold_eyes_closed)
+                    .chain()
+                    .run_if(resource_exists::<CubeFace>),
+            ),
+        );
+    }
+}
+
+// Material of the cube's face, found once the glb scene has been spawned.
+#[derive(Resource)]
+pub struct CubeFace {
+    pub material: Handle<CubeMaterial>,
+    // expression the face shows while its eyes are open
+    pub current: Handle<Image>,
+    // None for a skin without expressions, which keeps its eyes open
+    pub closed_eyes: Option<Handle<Image>>,
+}
+
+// The textures a skin's face swaps between, paths relative to the assets folder.
+#[derive(Deserialize)]
+pub struct ExpressionPaths {
+    happy: String,
+    sad: String,
+    sleepy: String,
+    surprised: String,
+    blink: String,
+}
+
+impl ExpressionPaths {
+    // the built-in cube's
+    pub fn mckenzie() -> Self {
+        ExpressionPaths {
+            happy: "mckenzie-cube.glb#Texture0".into(),
+            sad: "mckenzie-cube-sad.png".into(),
+            sleepy: "mckenzie-cube-sleepy.png".into(),
+            surprised: "mckenzie-cube-surprised.png".into(),
+            blink: "mckenzie-cube-blink.png".into(),
+        }
+    }
+}
+
+pub struct Expressions {
+    happy: Handle<Image>,
+    sad: Handle<Image>,
+    sleepy: Handle<Image>,
+    surprised: Handle<Image>,
+    closed_eyes: Handle<Image>,
+}
+
+impl Expressions {
+    pub fn load(asset_server: &AssetServer, paths: ExpressionPaths) -> Self {
+        Expressions {
+            happy: asset_server.load(paths.happy),
+            sad: asset_server.load(paths.sad),
+            sleepy: asset_server.load(paths.sleepy),
+            surprised: asset_server.load(paths.surprised),
+            closed_eyes: asset_server.load(paths.blink),
+        }
+    }
+
+    pub fn for_state(&self, state: CubeState) -> &Handle<Image> {
+        match state {
+            CubeState::Happy | CubeState::Excited => &self.happy,
+            CubeState::Sad | CubeState::Angry | CubeState::Hungry | CubeState::Sulking => &self.sad,
+            CubeState::Sleepy => &self.sleepy,
+            CubeState::Dizzy | CubeState::Scared => &self.surprised,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Blink {
+    timer: Timer,
+    closed: bool,
+    waiting_for_lerp: bool,
+}
+
+// Keeps the eyes shut for as long as it's on the cube, blinks wait until it's gone.
+#[derive(Component)]
+pub struct EyesClosed;
+
+impl Default for Blink {
+    fn default() -> Self {
+        Blink {
+            timer: Blink::open_timer(1.0),
+            closed: false,
+            waiting_for_lerp: false,
+        }
+    }
+}
+
+impl Blink {
+    fn open_timer(pace: f32) -> Timer {
+        Timer::from_seconds(
+            rand::thread_rng().gen_range(2.0..6.0) * pace,
+            TimerMode::Once,
+        )
+    }
+}
+
+// The scene spawns asynchronously, so keep looking until its material shows up.
+fn find_cube_face(
+    mut commands: Commands,
+    skins: Res<SkinLibrary>,
+    materials: Res<Assets<CubeMaterial>>,
+    query_cube: Query<Entity, With<Cube>>,
+    children: Query<&Children>,
+    query_material: Query<&Handle<CubeMaterial>>,
+) {
+    let cube = query_cube.single();
+
+    for entity in children.iter_descendants(cube) {
+        let Ok(handle) = query_material.get(entity) else {
+            continue;
+        };
+        let Some(material) = materials.get(handle) else {
+            continue;
+        };
+
+        commands.insert_resource(CubeFace {
+            material: handle.clone(),
+            current: material.base.base_color_texture.clone().unwrap_or_default(),
+            closed_eyes: skins
+                .current()
+                .expressions
+                .as_ref()
+                .map(|expressions| expressions.closed_eyes.clone()),
+        });
+        return;
+    }
+}
+
+// A skin that doesn't come with expressions keeps the face it was made with.
+fn apply_expression(
+    state: Res<State<CubeState>>,
+    skins: Res<SkinLibrary>,
+    mut face: ResMut<CubeFace>,
+    mut materials: ResMut<Assets<CubeMaterial>>,
+    query_blink: Query<(&Blink, Has<EyesClosed>)>,
+) {
+    let Some(expressions) = &skins.current().expressions else {
+        return;
+    };
+    let wanted = expressions.for_state(*state.get());
+    if face.current == *wanted {
+        return;
+    }
+
+    face.current = wanted.clone();
+
+    // closed eyes put the new expression on when they open
+    if query_blink
+        .iter()
+        .any(|(blink, eyes_closed)| blink.closed || eyes_closed)
+    {
+        return;
+    }
+    if let Some(material) = materials.get_mut(&face.material) {
+        material.base.base_color_texture = Some(face.current.clone());
+    }
+}
+
+fn blink_update(
+    time: Res<Time>,
+    personality: Res<Personality>,
+    state: Res<State<CubeState>>,
+    face: Res<CubeFace>,
+    mut materials: ResMut<Assets<CubeMaterial>>,
+    mut query_cube: Query<(&Cube, &mut Blink), Without<EyesClosed>>,
+) {
+    let Ok((cube_prop, mut blink)) = query_cube.get_single_mut() else {
+        return;
+    };
+    let Some(closed_eyes) = &face.closed_eyes else {
+        return;
+    };
+
+    // only start blinks while happy or sad, but always finish one that started
+    let can_blink = matches!(state.get(), CubeState::Happy | CubeState::Sad);
+    if !can_blink && !blink.closed {
+        return;
+    }
+
+    // don't start a blink while the cube is turning towards its new state
+    if state.is_changed() {
+        blink.waiting_for_lerp = true;
+    }
+    if cube_prop.rotate_timer.finished() {
+        blink.waiting_for_lerp = false;
+    }
+    if blink.waiting_for_lerp && !blink.closed {
+        return;
+    }
+
+    if !blink.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    blink.closed = !blink.closed;
+    blink.timer = if blink.closed {
+        Timer::from_seconds(0.15, TimerMode::Once)
+    } else {
+        Blink::open_timer(personality.blink_pace)
+    };
+
+    if let Some(material) = materials.get_mut(&face.material) {
+        material.base.base_color_texture = Some(if blink.closed {
+            closed_eyes.clone()
+        } else {
+            face.current.clone()
+        });
+    }
+}
+
+fn hold_eyes_closed(
+    face: Res<CubeFace>,
+    mut materials: ResMut<Assets<CubeMaterial>>,
+    mut opened: RemovedComponents<EyesClosed>,
+    query_closed: Query<(), Added<EyesClosed>>,
+) {
+    // read either way, so a later skin with eyes to close isn't handed stale ones
+    let opened = opened.read().count() > 0;
+    let Some(closed_eyes) = &face.closed_eyes else {
+        return;
+    };
+
+    let texture = if !query_closed.is_empty() {
+        closed_eyes
+    } else if opened {
+        &face.current
+    } else {
+        return;
+    };
+
+    if let Some(material) = materials.get_mut(&face.material) {
+        material.base.base_color_texture = Some(texture.clone());
+    }
+}
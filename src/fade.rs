@@ -0,0 +1,48 @@
+// Eased in and out, from 0 at the start to 1 at the end with no sudden change of speed at either.
+pub fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// From whatever was showing toward a target, over a fixed number of seconds. A new target partway
+// through starts over from there, so there's never a jump.
+pub struct Fade<T> {
+    from: T,
+    to: T,
+    elapsed: f32,
+    secs: f32,
+    mix: fn(T, T, f32) -> T,
+}
+
+impl<T: Copy + PartialEq> Fade<T> {
+    pub fn new(value: T, secs: f32, mix: fn(T, T, f32) -> T) -> Self {
+        Fade {
+            from: value,
+            to: value,
+            elapsed: 0.0,
+            secs,
+            mix,
+        }
+    }
+
+    pub fn value(&self) -> T {
+        (self.mix)(self.from, self.to, smoothstep(self.elapsed / self.secs))
+    }
+
+    pub fn retarget(&mut self, target: T) {
+        if self.to != target {
+            self.from = self.value();
+            self.to = target;
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub fn tick(&mut self, secs: f32) {
+        self.elapsed += secs;
+    }
+
+    // The last tick lands past the end, right on the target.
+    pub fn finished(&self) -> bool {
+        self.elapsed > self.secs
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::{
+    diagnostic::FrameTimeDiagnosticsPlugin,
+    prelude::*,
+    window::{PresentMode, PrimaryWindow},
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    idle::window_visible, input::InputState, keybindings::Action, low_power::LowPowerMode,
+    settings::Settings,
+};
+
+pub struct FramePacingPlugin;
+
+impl Plugin for FramePacingPlugin {
+    fn build(&self, app: &mut App) {
+        // the measured rate goes up on the debug window
+        app.add_plugins(FrameTimeDiagnosticsPlugin)
+            .add_systems(Startup, restore_present_mode)
+            .add_systems(Update, toggle_vsync)
+            // idle already slows the whole loop down further than any cap would
+            .add_systems(Last, limit_frame_rate.run_if(window_visible));
+    }
+}
+
+fn present_mode(vsync_off: bool) -> PresentMode {
+    if vsync_off {
+        PresentMode::AutoNoVsync
+    } else {
+        PresentMode::AutoVsync
+    }
+}
+
+fn restore_present_mode(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    window.present_mode = present_mode(settings.vsync_off);
+}
+
+fn toggle_vsync(
+    input: Res<InputState>,
+    mut settings: ResMut<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !input.just_did(Action::ToggleVsync) {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    settings.vsync_off = !settings.vsync_off;
+    window.present_mode = present_mode(settings.vsync_off);
+
+    if settings.vsync_off {
+        info!("vsync off");
+    } else {
+        info!("vsync on");
+    }
+}
+
+// Sleeps off whatever is left of the frame once everything else is done, so a fast machine doesn't
+// spend it drawing frames nobody needs.
+fn limit_frame_rate(
+    settings: Res<Settings>,
+    low_power: Res<LowPowerMode>,
+    mut last_frame: Local<Option<Instant>>,
+) {
+    if let (Some(fps_cap), Some(last_frame)) = (low_power.fps_cap(&settings), *last_frame) {
+        let frame = Duration::from_secs_f64(1.0 / f64::from(fps_cap));
+        if let Some(left) = frame.checked_sub(last_frame.elapsed()) {
+            thread::sleep(left);
+        }
+    }
+
+    *last_frame = Some(Instant::now());
+}
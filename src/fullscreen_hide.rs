@@ -0,0 +1,152 @@
+use bevy::{prelude::*, window::PrimaryWindow, winit::WinitWindows};
+
+// asking another process's window about itself isn't free, and games don't go fullscreen that often
+const FULLSCREEN_CHECK_SECS: f32 = 1.0;
+
+pub struct FullscreenHidePlugin;
+
+impl Plugin for FullscreenHidePlugin {
+    fn build(&self, app: &mut App) {
+        // opt in with the feature, and only windows can say what's in the foreground
+        if !cfg!(all(windows, feature = "fullscreen-hide")) {
+            return;
+        }
+
+        app.init_resource::<FullscreenWatch>()
+            .add_systems(Update, watch_fullscreen_apps);
+    }
+}
+
+#[derive(Resource)]
+struct FullscreenWatch {
+    timer: Timer,
+    // only ever shows the window again if this is what hid it, not the tray
+    hid_window: bool,
+    // shown again while still covered, so it stays until that app is done
+    shown_anyway: bool,
+}
+
+impl Default for FullscreenWatch {
+    fn default() -> Self {
+        FullscreenWatch {
+            timer: Timer::from_seconds(FULLSCREEN_CHECK_SECS, TimerMode::Repeating),
+            hid_window: false,
+            shown_anyway: false,
+        }
+    }
+}
+
+// Hides the window while a game or a video has the cube's monitor to itself. A hidden window is
+// idle, so the cube's systems stop with it. Shown from the tray in the meantime, the window is the
+// user's again, to keep or tuck away.
+fn watch_fullscreen_apps(
+    // virtual time stops while the window is hidden
+    time: Res<Time<Real>>,
+    winit_windows: NonSend<WinitWindows>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    mut watch: ResMut<FullscreenWatch>,
+) {
+    let Ok((entity, mut window)) = windows.get_single_mut() else {
+        return;
+    };
+    // every frame, it can be shown and hidden again between checks
+    if watch.hid_window && window.visible {
+        watch.hid_window = false;
+        watch.shown_anyway = true;
+    }
+    if !watch.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(monitor) = winit_windows
+        .get_window(entity)
+        .and_then(|winit_window| winit_window.current_monitor())
+    else {
+        return;
+    };
+    let origin = monitor.position();
+    let covered = fullscreen_foreground_on(IVec2::new(origin.x, origin.y));
+
+    if covered && window.visible && !watch.hid_window && !watch.shown_anyway {
+        info!("another app went fullscreen, hiding until it's done");
+        window.visible = false;
+        watch.hid_window = true;
+    } else if !covered {
+        if watch.hid_window {
+            window.visible = true;
+        }
+        watch.hid_window = false;
+        watch.shown_anyway = false;
+    }
+}
+
+#[cfg(not(all(windows, feature = "fullscreen-hide")))]
+fn fullscreen_foreground_on(_monitor_origin: IVec2) -> bool {
+    false
+}
+
+#[cfg(all(windows, feature = "fullscreen-hide"))]
+use windows_api::fullscreen_foreground_on;
+
+#[cfg(all(windows, feature = "fullscreen-hide"))]
+mod windows_api {
+    use bevy::math::IVec2;
+    use std::mem;
+    use windows_sys::Win32::{
+        Foundation::RECT,
+        Graphics::Gdi::{GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONULL},
+        System::Threading::GetCurrentProcessId,
+        UI::WindowsAndMessaging::{
+            GetClassNameW, GetForegroundWindow, GetWindowRect, GetWindowThreadProcessId,
+        },
+    };
+
+    // Whether the window in front belongs to someone else and covers the whole of the monitor whose
+    // top left corner is `monitor_origin`, in physical desktop pixels.
+    pub fn fullscreen_foreground_on(monitor_origin: IVec2) -> bool {
+        // SAFETY: only asks about a window handle the OS just gave out, into buffers sized for
+        // the answers, a window that's gone by then just makes the calls fail
+        unsafe {
+            let foreground = GetForegroundWindow();
+            if foreground == 0 {
+                return false;
+            }
+
+            let mut process_id = 0;
+            GetWindowThreadProcessId(foreground, &mut process_id);
+            if process_id == GetCurrentProcessId() {
+                return false;
+            }
+
+            // clicking the desktop brings its own window to the front, and that covers the monitor
+            let mut class = [0u16; 32];
+            let len = GetClassNameW(foreground, class.as_mut_ptr(), class.len() as i32);
+            let class = String::from_utf16_lossy(&class[..len.max(0) as usize]);
+            if class == "Progman" || class == "WorkerW" {
+                return false;
+            }
+
+            let mut window_rect: RECT = mem::zeroed();
+            if GetWindowRect(foreground, &mut window_rect) == 0 {
+                return false;
+            }
+            let monitor = MonitorFromWindow(foreground, MONITOR_DEFAULTTONULL);
+            if monitor == 0 {
+                return false;
+            }
+            let mut info = MONITORINFO {
+                cbSize: mem::size_of::<MONITORINFO>() as u32,
+                ..mem::zeroed()
+            };
+            if GetMonitorInfoW(monitor, &mut info) == 0 {
+                return false;
+            }
+
+            let screen = info.rcMonitor;
+            IVec2::new(screen.left, screen.top) == monitor_origin
+                && window_rect.left <= screen.left
+                && window_rect.top <= screen.top
+                && window_rect.right >= screen.right
+                && window_rect.bottom >= screen.bottom
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::input::InputState;
+
+// radial, as a fraction of full deflection
+const STICK_DEAD_ZONE: f32 = 0.15;
+
+pub struct GamepadLookPlugin;
+
+impl Plugin for GamepadLookPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LookInput>()
+            .add_systems(PreUpdate, read_right_stick);
+    }
+}
+
+// Where the cube should be looking this frame, whichever of the mouse and the right stick is steering.
+#[derive(Resource, Default)]
+pub struct LookInput {
+    // right stick deflection past the dead zone, rescaled back to 0..1
+    stick: Option<Vec2>,
+    // the stick took over or let go this frame
+    pub switched: bool,
+}
+
+impl LookInput {
+    pub fn stick_active(&self) -> bool {
+        self.stick.is_some()
+    }
+
+    // The stick acts as a cursor, full deflection reaching the window edge so the angles match the mouse.
+    pub fn position(&self, input: &InputState) -> Option<Vec2> {
+        let Some(stick) = self.stick else {
+            return input.pointer;
+        };
+        let half_size = input.window_size / 2.;
+
+        // window y grows downwards, stick y upwards
+        Some(half_size + Vec2::new(stick.x, -stick.y) * half_size)
+    }
+}
+
+fn read_right_stick(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut look_input: ResMut<LookInput>,
+) {
+    let stick = gamepads
+        .iter()
+        .filter_map(|gamepad| {
+            let x = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::RightStickX))?;
+            let y = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::RightStickY))?;
+            let deflection = Vec2::new(x, y);
+            let magnitude = deflection.length();
+
+            (magnitude > STICK_DEAD_ZONE).then(|| {
+                let rescaled = ((magnitude - STICK_DEAD_ZONE) / (1.0 - STICK_DEAD_ZONE)).min(1.0);
+                deflection / magnitude * rescaled
+            })
+        })
+        .next();
+
+    look_input.switched = stick.is_some() != look_input.stick.is_some();
+    look_input.stick = stick;
+}
@@ -0,0 +1,66 @@
+use bevy::{core_pipeline::bloom::BloomSettings, prelude::*};
+use std::f32::consts::TAU;
+
+use crate::{celebration::Celebration, settings::Settings, CubeState};
+
+// how fast the glow eases over to a new state's, per second
+const GLOW_EASE: f32 = 4.0;
+// beats per second while it pulses
+const PULSE_RATE: f32 = 1.5;
+
+pub struct GlowPlugin;
+
+impl Plugin for GlowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, glow_with_state);
+    }
+}
+
+// The bloom for the canvas camera, none when it's turned off in the settings. Bloom needs the
+// camera drawing in HDR, which only changes the textures it draws through: the canvas it lands on
+// keeps its format, alpha and all.
+pub fn camera_bloom(settings: &Settings) -> Option<BloomSettings> {
+    (!settings.bloom_off).then(|| BloomSettings {
+        intensity: glow(CubeState::default(), false).0,
+        ..BloomSettings::NATURAL
+    })
+}
+
+// Resting intensity and how far it swings either side of it.
+fn glow(state: CubeState, celebrating: bool) -> (f32, f32) {
+    if celebrating {
+        return (0.25, 0.12);
+    }
+
+    match state {
+        CubeState::Happy => (0.08, 0.0),
+        CubeState::Sad => (0.005, 0.0),
+        CubeState::Sleepy => (0.02, 0.0),
+        CubeState::Angry => (0.15, 0.0),
+        CubeState::Dizzy => (0.08, 0.0),
+        CubeState::Excited => (0.2, 0.1),
+        CubeState::Scared => (0.04, 0.0),
+        CubeState::Hungry => (0.04, 0.0),
+        CubeState::Sulking => (0.01, 0.0),
+    }
+}
+
+fn glow_with_state(
+    time: Res<Time>,
+    state: Res<State<CubeState>>,
+    celebration: Option<Res<Celebration>>,
+    mut blooms: Query<&mut BloomSettings>,
+    mut eased: Local<Option<Vec2>>,
+) {
+    let (resting, swing) = glow(*state.get(), celebration.is_some());
+    let target = Vec2::new(resting, swing);
+    let glow = eased.map_or(target, |eased| {
+        eased.lerp(target, (GLOW_EASE * time.delta_seconds()).min(1.0))
+    });
+    *eased = Some(glow);
+
+    let pulse = glow.y * (time.elapsed_seconds() * PULSE_RATE * TAU).sin();
+    for mut bloom in &mut blooms {
+        bloom.intensity = (glow.x + pulse).max(0.0);
+    }
+}
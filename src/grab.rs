@@ -0,0 +1,78 @@
+use bevy::{input::mouse::MouseMotion, prelude::*};
+
+use crate::{
+    happy_cube_update, input::PressTarget, menu::MenuState, CanvasPointer, Cube, CubeState,
+    CubeSystems,
+};
+
+pub struct GrabPlugin;
+
+impl Plugin for GrabPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DragRotate>().add_systems(
+            Update,
+            (
+                grab_cube.run_if(in_state(MenuState::Closed)),
+                drag_rotate
+                    .run_if(in_state(CubeState::Happy))
+                    .after(happy_cube_update),
+            )
+                .chain()
+                .in_set(CubeSystems::Behavior),
+        );
+    }
+}
+
+#[derive(Resource)]
+pub struct DragRotate {
+    // radians per pixel of mouse travel
+    pub sensitivity: f32,
+}
+
+impl Default for DragRotate {
+    fn default() -> Self {
+        DragRotate { sensitivity: 0.01 }
+    }
+}
+
+// Held by the mouse, the cube turns with the drag instead of tracking the cursor.
+#[derive(Component)]
+pub struct Grabbed;
+
+fn grab_cube(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    state: Res<State<CubeState>>,
+    pointer: CanvasPointer,
+    mut query_cube: Query<(Entity, &mut Cube, Has<Grabbed>)>,
+) {
+    let (entity, mut cube_prop, grabbed) = query_cube.single_mut();
+
+    if grabbed && !mouse.pressed(MouseButton::Left) {
+        commands.entity(entity).remove::<Grabbed>();
+        // eases back round to the cursor from wherever it was left
+        cube_prop.rotate_timer = Timer::from_seconds(0.5, TimerMode::Once);
+    } else if !grabbed
+        && pointer.input.pressed_on(PressTarget::Cube)
+        && *state.get() == CubeState::Happy
+    {
+        commands.entity(entity).insert(Grabbed);
+    }
+}
+
+fn drag_rotate(
+    drag: Res<DragRotate>,
+    mut motion_events: EventReader<MouseMotion>,
+    mut query_cube: Query<&mut Transform, With<Grabbed>>,
+) {
+    let delta: Vec2 = motion_events.read().map(|motion| motion.delta).sum();
+
+    let Ok(mut cube_transform) = query_cube.get_single_mut() else {
+        return;
+    };
+
+    // like orbiting a model viewer, dragging sideways turns it about the vertical axis
+    cube_transform.rotation = Quat::from_rotation_y(delta.x * drag.sensitivity)
+        * Quat::from_rotation_x(delta.y * drag.sensitivity)
+        * cube_transform.rotation;
+}
@@ -0,0 +1,88 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::f32::consts::TAU;
+
+use crate::{mood::Mood, rim::CubeMaterial, skin::CubeParts, CubeState};
+
+// one beat, from no glow up to the most and back
+const HEARTBEAT_SECS: f32 = 1.2;
+// how long the beat takes to swell on Happy and fade out again after
+const HEARTBEAT_RAMP_SECS: f32 = 1.0;
+// soft and warm, in linear light like the rest of the emissive
+const HEARTBEAT_GLOW: LinearRgba = LinearRgba::new(0.35, 0.16, 0.04, 0.0);
+
+pub struct HeartbeatPlugin;
+
+impl Plugin for HeartbeatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, beat_heart);
+    }
+}
+
+#[derive(Default)]
+struct Heartbeat {
+    // 0 without any beat, 1 with all of it
+    ramp: f32,
+    // seconds into the beat, from when it started swelling
+    phase: f32,
+    // the glb's own emissive for each of the cube's materials, put back once the beat is gone
+    originals: HashMap<AssetId<CubeMaterial>, LinearRgba>,
+}
+
+// A happy cube glows gently in and out, more strongly the better its mood, on whatever it's drawn
+// with right now, skin and all.
+fn beat_heart(
+    time: Res<Time>,
+    state: Res<State<CubeState>>,
+    mood: Res<Mood>,
+    parts: CubeParts<&Handle<CubeMaterial>>,
+    mut materials: ResMut<Assets<CubeMaterial>>,
+    mut beat: Local<Heartbeat>,
+) {
+    let target = if *state.get() == CubeState::Happy {
+        1.0
+    } else {
+        0.0
+    };
+    // nothing moves while paused, and nothing's left to put back once it's faded out
+    if time.delta_seconds() == 0.0 || (beat.ramp == 0.0 && target == 0.0) {
+        return;
+    }
+
+    let step = time.delta_seconds() / HEARTBEAT_RAMP_SECS;
+    beat.ramp = if target > beat.ramp {
+        (beat.ramp + step).min(target)
+    } else {
+        (beat.ramp - step).max(target)
+    };
+    beat.phase += time.delta_seconds();
+    let pulse = 0.5 - 0.5 * (TAU * beat.phase / HEARTBEAT_SECS).cos();
+    let glow = HEARTBEAT_GLOW * (pulse * beat.ramp * mood.0);
+
+    let mut seen = HashSet::new();
+    for (_, handle) in parts.iter() {
+        if !seen.insert(handle.id()) {
+            continue;
+        }
+        let Some(current) = materials.get(handle).map(|material| material.base.emissive) else {
+            continue;
+        };
+
+        let original = *beat.originals.entry(handle.id()).or_insert(current);
+        let emissive = original + glow;
+        // writing at all rebuilds the material's bind group
+        if emissive != current {
+            if let Some(material) = materials.get_mut(handle) {
+                material.base.emissive = emissive;
+            }
+        }
+    }
+
+    // faded right out, the originals just went back on
+    if beat.ramp == 0.0 {
+        beat.phase = 0.0;
+        beat.originals.clear();
+    }
+}
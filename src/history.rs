@@ -0,0 +1,268 @@
+use bevy::{app::AppExit, prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, fs, path::PathBuf, time::Duration};
+
+use crate::{input::InputState, keybindings::Action, shutdown::SaveOnExit, CubeState};
+
+// transitions kept around for the F3 dump
+const HISTORY_LEN: usize = 32;
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StateHistory>()
+            .add_systems(Update, (record_state_history, dump_state_history).chain())
+            .add_systems(
+                Last,
+                save_lifetime_stats
+                    .in_set(SaveOnExit)
+                    .run_if(on_event::<AppExit>()),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateChange {
+    pub from: Option<CubeState>,
+    pub to: CubeState,
+    // time since startup
+    pub at: Duration,
+}
+
+// The last few state changes, plus how long the cube has spent in each state since launch.
+#[derive(Resource, Default)]
+pub struct StateHistory {
+    changes: VecDeque<StateChange>,
+    // totals for finished stretches, the current one is added on top when asked
+    totals: HashMap<CubeState, Duration>,
+    current: Option<(CubeState, Duration)>,
+    now: Duration,
+}
+
+impl StateHistory {
+    pub fn record(&mut self, from: Option<CubeState>, to: CubeState, at: Duration) {
+        if let Some((state, since)) = self.current {
+            *self.totals.entry(state).or_default() += at.saturating_sub(since);
+        }
+
+        if self.changes.len() == HISTORY_LEN {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(StateChange { from, to, at });
+
+        self.current = Some((to, at));
+        self.now = self.now.max(at);
+    }
+
+    pub fn advance_to(&mut self, now: Duration) {
+        self.now = now;
+    }
+
+    pub fn changes(&self) -> impl Iterator<Item = &StateChange> {
+        self.changes.iter()
+    }
+
+    // Every state the cube has been in since launch, longest first.
+    pub fn visited(&self) -> Vec<CubeState> {
+        let mut states: Vec<CubeState> = self.totals.keys().copied().collect();
+        if let Some((current, _)) = self.current {
+            if !states.contains(&current) {
+                states.push(current);
+            }
+        }
+        states.sort_by_key(|state| std::cmp::Reverse(self.time_in_state(*state)));
+        states
+    }
+
+    // Everything spent in `state` since launch, including the stretch it's in right now.
+    pub fn time_in_state(&self, state: CubeState) -> Duration {
+        let finished = self.totals.get(&state).copied().unwrap_or_default();
+
+        match self.current {
+            Some((current, since)) if current == state => finished + self.now.saturating_sub(since),
+            _ => finished,
+        }
+    }
+}
+
+fn record_state_history(
+    time: Res<Time>,
+    mut transitions: EventReader<StateTransitionEvent<CubeState>>,
+    mut history: ResMut<StateHistory>,
+) {
+    for transition in transitions.read() {
+        let Some(to) = transition.entered else {
+            continue;
+        };
+        if transition.exited == Some(to) {
+            continue;
+        }
+
+        history.record(transition.exited, to, time.elapsed());
+    }
+
+    history.advance_to(time.elapsed());
+}
+
+fn dump_state_history(input: Res<InputState>, history: Res<StateHistory>) {
+    if !input.just_did(Action::DumpHistory) {
+        return;
+    }
+
+    let mut lines = Vec::new();
+    let mut changes = history.changes().peekable();
+
+    while let Some(change) = changes.next() {
+        let stayed = match changes.peek() {
+            Some(next) => next.at - change.at,
+            None => history.now - change.at,
+        };
+        let from = change
+            .from
+            .map_or("start".to_string(), |from| format!("{from:?}"));
+
+        lines.push(format!(
+            "{:>8.1}s  {from} -> {:?}, for {:.1}s",
+            change.at.as_secs_f32(),
+            change.to,
+            stayed.as_secs_f32()
+        ));
+    }
+
+    let totals: Vec<String> = history
+        .visited()
+        .iter()
+        .map(|state| {
+            format!(
+                "{state:?} {:.1}s",
+                history.time_in_state(*state).as_secs_f32()
+            )
+        })
+        .collect();
+
+    info!(
+        "last {} state changes:\n{}\ntime in each state: {}",
+        lines.len(),
+        lines.join("\n"),
+        totals.join(", ")
+    );
+}
+
+// Time in each state over every session so far, topped up with this one's on the way out.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct LifetimeStats {
+    sessions: u32,
+    seconds_in_state: std::collections::HashMap<CubeState, f32>,
+}
+
+impl LifetimeStats {
+    fn path() -> Option<PathBuf> {
+        Some(dirs::data_dir()?.join("mckenzie-bevy").join("stats.ron"))
+    }
+}
+
+fn save_lifetime_stats(history: Res<StateHistory>) {
+    let Some(path) = LifetimeStats::path() else {
+        return;
+    };
+
+    // an unreadable file starts the count over rather than losing this session too
+    let mut stats = fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| ron::from_str::<LifetimeStats>(&contents).ok())
+        .unwrap_or_default();
+    stats.sessions += 1;
+    for state in history.visited() {
+        *stats.seconds_in_state.entry(state).or_default() +=
+            history.time_in_state(state).as_secs_f32();
+    }
+
+    let saved = ron::ser::to_string_pretty(&stats, Default::default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| {
+            path.parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&path, contents))
+                .map_err(|error| error.to_string())
+        });
+
+    if let Err(error) = saved {
+        warn!("couldn't save the stats to {path:?}: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn keeps_only_the_newest_changes() {
+        let mut history = StateHistory::default();
+        history.record(None, CubeState::Happy, secs(0));
+
+        for i in 1..=HISTORY_LEN as u64 + 5 {
+            let (from, to) = if i % 2 == 1 {
+                (CubeState::Happy, CubeState::Sad)
+            } else {
+                (CubeState::Sad, CubeState::Happy)
+            };
+            history.record(Some(from), to, secs(i));
+        }
+
+        assert_eq!(history.changes().count(), HISTORY_LEN);
+        assert_eq!(history.changes().next().unwrap().at, secs(6));
+        assert_eq!(
+            history.changes().last().unwrap().at,
+            secs(HISTORY_LEN as u64 + 5)
+        );
+    }
+
+    #[test]
+    fn totals_survive_the_buffer_wrapping() {
+        let mut history = StateHistory::default();
+        history.record(None, CubeState::Happy, secs(0));
+
+        // 3s happy, 1s sad, over and over, well past the buffer length
+        let mut at = 0;
+        for _ in 0..HISTORY_LEN {
+            at += 3;
+            history.record(Some(CubeState::Happy), CubeState::Sad, secs(at));
+            at += 1;
+            history.record(Some(CubeState::Sad), CubeState::Happy, secs(at));
+        }
+        history.advance_to(secs(at));
+
+        assert_eq!(
+            history.time_in_state(CubeState::Happy),
+            secs(3 * HISTORY_LEN as u64)
+        );
+        assert_eq!(
+            history.time_in_state(CubeState::Sad),
+            secs(HISTORY_LEN as u64)
+        );
+    }
+
+    #[test]
+    fn counts_the_state_in_progress() {
+        let mut history = StateHistory::default();
+        history.record(None, CubeState::Happy, secs(0));
+        history.record(Some(CubeState::Happy), CubeState::Sleepy, secs(10));
+        history.advance_to(secs(25));
+
+        assert_eq!(history.time_in_state(CubeState::Happy), secs(10));
+        assert_eq!(history.time_in_state(CubeState::Sleepy), secs(15));
+        assert_eq!(history.time_in_state(CubeState::Angry), Duration::ZERO);
+
+        history.record(Some(CubeState::Sleepy), CubeState::Happy, secs(30));
+        history.advance_to(secs(32));
+
+        assert_eq!(history.time_in_state(CubeState::Happy), secs(12));
+        assert_eq!(history.time_in_state(CubeState::Sleepy), secs(20));
+    }
+}
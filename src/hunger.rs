@@ -0,0 +1,253 @@
+use bevy::{ecs::system::SystemParam, prelude::*, render::view::RenderLayers};
+use std::f32::consts::PI;
+
+use crate::{
+    canvas_to_window, canvas_to_world, cursor_look_angles, happy_cube_update,
+    manual_override::{no_manual_override, ManualOverride},
+    mood::Mood,
+    reactions::{Gesture, GestureKind},
+    world_to_canvas, CanvasPointer, Cube, CubeState, CubeSystems, LookPriority, CANVAS_LAYER,
+};
+
+// real-time seconds it takes an empty stomach to fill back up with hunger
+const HUNGER_SECS: f32 = 15.0 * 60.0;
+// canvas pixels per second food falls at
+const FOOD_SPEED: f32 = 120.0;
+
+pub struct HungerPlugin;
+
+impl Plugin for HungerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hunger>()
+            .add_systems(OnEnter(CubeState::Hungry), enter_hungry)
+            .add_systems(
+                Update,
+                (
+                    hunger_update,
+                    get_hungry
+                        .run_if(in_state(CubeState::Happy))
+                        .run_if(no_manual_override)
+                        .in_set(CubeSystems::Behavior),
+                    // ahead of whichever state drains the looks, so it watches food from the
+                    // frame it shows up, the menu's included
+                    (drop_food, food_update)
+                        .chain()
+                        .before(hungry_cube_update)
+                        .before(happy_cube_update)
+                        .in_set(CubeSystems::Behavior),
+                    hungry_cube_update
+                        .run_if(in_state(CubeState::Hungry))
+                        .in_set(CubeSystems::Behavior),
+                    chomp_update.in_set(CubeSystems::Overlay),
+                ),
+            );
+    }
+}
+
+// How empty the cube's stomach is, from 0.0 (just fed) to 1.0 (starving).
+#[derive(Resource, Default)]
+pub struct Hunger(pub f32);
+
+// A dropped file on its way down to the cube, in canvas world coordinates.
+#[derive(Component)]
+struct Food;
+
+#[derive(Component)]
+struct Chomp {
+    timer: Timer,
+}
+
+fn hunger_update(time: Res<Time<Real>>, mut hunger: ResMut<Hunger>) {
+    hunger.0 = (hunger.0 + time.delta_seconds() / HUNGER_SECS).min(1.0);
+}
+
+fn get_hungry(hunger: Res<Hunger>, mut next_state: ResMut<NextState<CubeState>>) {
+    if hunger.0 >= 1.0 {
+        next_state.set(CubeState::Hungry);
+    }
+}
+
+fn enter_hungry(mut query_cube: Query<&mut Cube>) {
+    let mut cube_prop = query_cube.single_mut();
+
+    cube_prop.state_timer = Timer::from_seconds(4.0, TimerMode::Repeating);
+}
+
+// Droops, and every few seconds glances down at the bottom of the canvas hoping for food. Food
+// that's actually coming gets watched instead.
+fn hungry_cube_update(
+    time: Res<Time>,
+    hunger: Res<Hunger>,
+    manual_override: Option<Res<ManualOverride>>,
+    mut query_cube: Query<(&mut Transform, &mut Cube)>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
+
+    if hunger.0 < 1.0 && manual_override.is_none() {
+        // fed, back to normal
+        next_state.set(CubeState::Happy);
+        return;
+    }
+
+    let (cube_rot_y, cube_rot_x, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
+
+    cube_prop.state_timer.tick(time.delta());
+
+    let glance = match cube_prop.state_timer.elapsed_secs() {
+        secs if secs < 1.0 => (secs * PI).sin() * 0.35,
+        _ => 0.0,
+    };
+    let look = cube_prop
+        .look_requests
+        .drain(..)
+        .max_by_key(|(priority, _)| *priority)
+        .map_or(Vec2::new(0.3 + glance, 0.0), |(_, look)| look);
+    let ease = 1.0 - (-4.0 * time.delta_seconds()).exp();
+
+    cube_transform.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        cube_rot_y.lerp(look.y, ease),
+        cube_rot_x.lerp(look.x, ease),
+        0.0,
+    );
+}
+
+fn drop_food(
+    mut commands: Commands,
+    mut drops: EventReader<FileDragAndDrop>,
+    state: Res<State<CubeState>>,
+    pointer: CanvasPointer,
+    mut query_cube: Query<(Entity, Option<&mut Gesture>), With<Cube>>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+
+        if *state.get() != CubeState::Hungry {
+            // not hungry, but thanks anyway
+            let (entity, gesture) = query_cube.single_mut();
+            match gesture {
+                Some(mut gesture) => gesture.queue(GestureKind::Nod),
+                None => {
+                    commands
+                        .entity(entity)
+                        .insert(Gesture::new(GestureKind::Nod));
+                }
+            }
+            continue;
+        }
+
+        debug!("feeding the cube {path_buf:?}");
+
+        // falls from where the file was let go, or from the top if the cursor isn't known
+        let canvas_size = pointer.canvas.size();
+        let from = pointer
+            .cursor()
+            .map(|cursor| canvas_to_world(cursor, canvas_size))
+            .unwrap_or(Vec2::new(0.0, canvas_size.y / 2.0));
+
+        spawn_food(&mut commands, from);
+    }
+}
+
+// Drops a snack at `from`, in canvas world coordinates, that makes its way to the cube.
+pub fn spawn_food(commands: &mut Commands, from: Vec2) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.95, 0.75, 0.3),
+                custom_size: Some(Vec2::splat(6.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(from.extend(1.0)),
+            ..default()
+        },
+        RenderLayers::layer(CANVAS_LAYER),
+        Food,
+    ));
+}
+
+// How keenly the cube watches food come.
+#[derive(SystemParam)]
+struct FoodWatch<'w> {
+    state: Res<'w, State<CubeState>>,
+    mood: Res<'w, Mood>,
+}
+
+impl FoodWatch<'_> {
+    // None in the states that don't take looks, anywhere else they'd only pile up
+    fn look_divisor(&self) -> Option<f32> {
+        matches!(self.state.get(), CubeState::Happy | CubeState::Hungry)
+            .then(|| self.mood.look_divisor())
+    }
+}
+
+fn food_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    watch: FoodWatch,
+    pointer: CanvasPointer,
+    mut hunger: ResMut<Hunger>,
+    mut query_food: Query<(Entity, &mut Transform), With<Food>>,
+    mut query_cube: Query<(Entity, &GlobalTransform, &mut Cube)>,
+) {
+    let (cube, cube_global, mut cube_prop) = query_cube.single_mut();
+    let Some((center, radius)) = pointer.cube(cube_global) else {
+        return;
+    };
+    let canvas_size = pointer.canvas.size();
+    let center = canvas_to_world(center, canvas_size);
+
+    for (entity, mut transform) in &mut query_food {
+        let to_cube = center - transform.translation.truncate();
+
+        // watches it come
+        if let Some(look_divisor) = watch.look_divisor() {
+            let window_size = pointer.input.window_size;
+            let food_on_window = canvas_to_window(
+                window_size,
+                world_to_canvas(transform.translation.truncate(), canvas_size),
+                pointer.projections.single(),
+                canvas_size,
+            );
+            let look = cursor_look_angles(window_size, food_on_window, look_divisor);
+            cube_prop.request_look(LookPriority::Gesture, look.into());
+        }
+
+        if to_cube.length() > radius * 0.5 {
+            let step = to_cube.normalize_or_zero() * FOOD_SPEED * time.delta_seconds();
+            transform.translation += step.clamp_length_max(to_cube.length()).extend(0.0);
+            transform.rotate_z(4.0 * time.delta_seconds());
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        commands.entity(cube).insert(Chomp {
+            timer: Timer::from_seconds(0.45, TimerMode::Once),
+        });
+        hunger.0 = 0.0;
+    }
+}
+
+// Three quick bites, squashing down and springing back.
+fn chomp_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Transform, &mut Chomp)>,
+) {
+    for (entity, mut cube_transform, mut chomp) in &mut query_cube {
+        chomp.timer.tick(time.delta());
+
+        let t = chomp.timer.elapsed_secs() / chomp.timer.duration().as_secs_f32();
+        let bite = (t * 3.0 * PI).sin().abs() * (1.0 - t);
+
+        cube_transform.scale = Vec3::new(1.0 + 0.15 * bite, 1.0 - 0.2 * bite, 1.0 + 0.15 * bite);
+
+        if chomp.timer.finished() {
+            cube_transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<Chomp>();
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    window::{PrimaryWindow, WindowOccluded},
+    winit::{UpdateMode, WinitSettings, WinitWindows},
+};
+use std::time::Duration;
+
+use crate::Cube;
+
+// how often the app wakes up while nobody can see it, enough to notice the tray or a restore
+const IDLE_WAKE_MILLIS: u64 = 250;
+
+pub struct IdlePlugin;
+
+impl Plugin for IdlePlugin {
+    fn build(&self, app: &mut App) {
+        // decided before Update so nothing gated on it runs a frame late
+        app.init_resource::<Idle>()
+            .add_systems(PreUpdate, track_idle);
+    }
+}
+
+// Whether the main window can be seen at all: hidden in the tray, minimized and covered over all
+// count as not.
+#[derive(Resource, Default)]
+pub struct Idle {
+    occluded: bool,
+    // what to put back on waking, kept only while idle
+    resume: Option<Resume>,
+}
+
+struct Resume {
+    winit_settings: WinitSettings,
+    // the pause overlay may have stopped the clock already, it stays stopped then
+    time_was_paused: bool,
+}
+
+// The cube's systems, the canvas fit and the cameras all stop while this is false.
+pub fn window_visible(idle: Res<Idle>) -> bool {
+    idle.resume.is_none()
+}
+
+// Everything that costs anything per frame, turned down while idle.
+#[derive(SystemParam)]
+struct Workload<'w, 's> {
+    winit_settings: ResMut<'w, WinitSettings>,
+    time: ResMut<'w, Time<Virtual>>,
+    cameras: Query<'w, 's, &'static mut Camera>,
+    query_cube: Query<'w, 's, &'static mut Cube>,
+}
+
+impl Workload<'_, '_> {
+    fn sleep(&mut self) -> Resume {
+        let resume = Resume {
+            winit_settings: self.winit_settings.clone(),
+            time_was_paused: self.time.is_paused(),
+        };
+
+        let wake = UpdateMode::reactive_low_power(Duration::from_millis(IDLE_WAKE_MILLIS));
+        self.winit_settings.focused_mode = wake;
+        self.winit_settings.unfocused_mode = wake;
+        // timers pick up where they left off instead of catching up on all of it at once
+        self.time.pause();
+        for mut camera in &mut self.cameras {
+            camera.is_active = false;
+        }
+        resume
+    }
+
+    fn wake(&mut self, resume: Resume) {
+        *self.winit_settings = resume.winit_settings;
+        if !resume.time_was_paused {
+            self.time.unpause();
+        }
+        for mut camera in &mut self.cameras {
+            camera.is_active = true;
+        }
+        for mut cube_prop in &mut self.query_cube {
+            // the cursor is somewhere else by now, ease over to it
+            cube_prop.rotate_timer = Timer::from_seconds(0.5, TimerMode::Once);
+        }
+    }
+}
+
+fn track_idle(
+    mut occluded_events: EventReader<WindowOccluded>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut idle: ResMut<Idle>,
+    mut workload: Workload,
+) {
+    let Ok((entity, window)) = windows.get_single() else {
+        return;
+    };
+    if let Some(event) = occluded_events
+        .read()
+        .filter(|event| event.window == entity)
+        .last()
+    {
+        idle.occluded = event.occluded;
+    }
+
+    // not every platform reports a minimized window as occluded
+    let minimized = winit_windows
+        .get_window(entity)
+        .and_then(|winit_window| winit_window.is_minimized())
+        .unwrap_or(false);
+    let hidden = !window.visible || minimized || idle.occluded;
+
+    match (hidden, idle.resume.take()) {
+        (true, None) => idle.resume = Some(workload.sleep()),
+        (false, Some(resume)) => workload.wake(resume),
+        (_, resume) => idle.resume = resume,
+    }
+}
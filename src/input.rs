@@ -0,0 +1,381 @@
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        mouse::MouseMotion,
+        touch::{ForceTouch, Touch},
+        InputSystem,
+    },
+    prelude::*,
+    ui::UiSystem,
+    utils::HashSet,
+    window::{CursorEntered, CursorGrabMode, CursorLeft, PrimaryWindow, WindowFocused},
+};
+#[cfg(feature = "global-cursor")]
+use {bevy::time::Stopwatch, mouse_position::mouse_position::Mouse};
+
+use crate::{
+    canvas::CanvasSettings,
+    canvas_to_window, cube_on_canvas,
+    keybindings::{Action, Keybindings},
+    menu::MenuState,
+    pause::AppMode,
+    window_to_canvas, Cube, MainCamera,
+};
+
+// how far pen pressure can scale the cube's reactions either way
+const MIN_PRESSURE: f32 = 0.5;
+const MAX_PRESSURE: f32 = 1.5;
+// the pointer has to stay out of the window this long before it counts as gone
+const POINTER_GRACE_SECS: f32 = 0.5;
+// with the global cursor the pointer only counts as gone once it's been left alone this long
+#[cfg(feature = "global-cursor")]
+const GLOBAL_CURSOR_IDLE_SECS: f32 = 120.0;
+
+pub struct InputStatePlugin;
+
+impl Plugin for InputStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputState>().add_systems(
+            PreUpdate,
+            (
+                gather_input.after(InputSystem),
+                // UI focus decides whether a press landed on a button first
+                resolve_press.after(gather_input).after(UiSystem::Focus),
+                track_pointer_presence.after(gather_input),
+            ),
+        );
+
+        #[cfg(feature = "global-cursor")]
+        app.init_resource::<GlobalCursor>();
+    }
+}
+
+// What a left press or a tap landed on. Only that one gets it, so dragging the window from the
+// background never pokes or grabs the cube as well.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PressTarget {
+    // a menu, the pause overlay, anything with buttons
+    Ui,
+    Cube,
+    // empty canvas around the cube
+    Background,
+}
+
+// Everything the cube reacts to this frame, gathered once so the rest of the app never has to ask
+// the window, the mouse, the touchscreen and the keyboard separately.
+#[derive(Resource, Default)]
+pub struct InputState {
+    // the mouse cursor or the finger on the screen, in window coordinates like
+    // `Window::cursor_position`
+    pub pointer: Option<Vec2>,
+    // the same spot in low-res canvas pixels
+    pub canvas_pointer: Option<Vec2>,
+    // window pixels per second, zero when the pointer has only just appeared
+    pub pointer_velocity: Vec2,
+    // how hard the pen or finger presses, MIN_PRESSURE to MAX_PRESSURE, and 1.0 where there's no
+    // pressure to read, like with a mouse
+    pub pressure: f32,
+    // logical size, kept from the last frame there was a window
+    pub window_size: Vec2,
+    // actions that started this frame
+    actions: HashSet<Action>,
+    // actions held down right now
+    held: HashSet<Action>,
+    // where the made-up cursor is while the mouse steers by relative motion
+    virtual_cursor: Option<Vec2>,
+    // set on the frame of a press
+    press: Option<PressTarget>,
+    // between the window's CursorEntered and CursorLeft
+    cursor_inside: bool,
+    // how long there's been neither a cursor in the window nor a pointer
+    away_secs: f32,
+}
+
+impl InputState {
+    pub fn pointer_present(&self) -> bool {
+        self.pointer.is_some()
+    }
+
+    // Gone for good rather than for a frame, `pointer` can blink out while the cursor is still over
+    // the window.
+    pub fn pointer_gone(&self) -> bool {
+        self.away_secs >= POINTER_GRACE_SECS
+    }
+
+    pub fn just_did(&self, action: Action) -> bool {
+        self.actions.contains(&action)
+    }
+
+    pub fn holding(&self, action: Action) -> bool {
+        self.held.contains(&action)
+    }
+
+    pub fn pressed_on(&self, target: PressTarget) -> bool {
+        self.press == Some(target)
+    }
+}
+
+// How the canvas sits in the window, to take the pointer over onto it.
+#[derive(SystemParam)]
+pub struct CanvasView<'w, 's> {
+    projections: Query<'w, 's, &'static OrthographicProjection, With<MainCamera>>,
+    canvas: Res<'w, CanvasSettings>,
+}
+
+#[derive(SystemParam)]
+pub struct Devices<'w, 's> {
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    touches: Res<'w, Touches>,
+    motion_events: EventReader<'w, 's, MouseMotion>,
+    focus_events: EventReader<'w, 's, WindowFocused>,
+}
+
+pub fn gather_input(
+    time: Res<Time<Real>>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    view: CanvasView,
+    mut devices: Devices,
+    bindings: Res<Keybindings>,
+    #[cfg(feature = "global-cursor")] mut global_cursor: ResMut<GlobalCursor>,
+    mut input: ResMut<InputState>,
+) {
+    input.actions = bindings
+        .actions()
+        .filter(|(_, key)| devices.keys.just_pressed(*key))
+        .map(|(action, _)| action)
+        .collect();
+    input.held = bindings
+        .actions()
+        .filter(|(_, key)| devices.keys.pressed(*key))
+        .map(|(action, _)| action)
+        .collect();
+    // a tap pokes the same as a click
+    if devices.mouse.just_pressed(MouseButton::Left) || devices.touches.any_just_pressed() {
+        input.actions.insert(Action::Poke);
+    }
+
+    let (primary, mut window) = windows.get_single_mut().ok().unzip();
+    if let Some(window) = &window {
+        input.window_size = window.size();
+    }
+
+    let lost_focus = devices
+        .focus_events
+        .read()
+        .any(|event| Some(event.window) == primary && !event.focused);
+    let grab = window
+        .as_ref()
+        .and_then(|_| switch_relative_mode(&mut input, lost_focus));
+    if let (Some(grab), Some(window)) = (grab, &mut window) {
+        // only written to when it changes, a changed window gets synced back to the OS
+        if window.cursor.grab_mode != grab {
+            window.cursor.grab_mode = grab;
+        }
+    }
+    let switched_mode = grab.is_some();
+    let window = window.as_deref();
+
+    // the finger that's been down the longest
+    let touch = devices.touches.iter().min_by_key(|touch| touch.id());
+    // a window clicks go through doesn't hear about the cursor either, only the global one can tell
+    let pointer = touch.map(|touch| touch.position()).or_else(|| {
+        window
+            .filter(|window| window.cursor.hit_test)
+            .and_then(Window::cursor_position)
+    });
+    #[cfg(feature = "global-cursor")]
+    let pointer = match (touch, window) {
+        (None, Some(window)) if input.virtual_cursor.is_none() => {
+            track_global_cursor(&time, window, &mut global_cursor).unwrap_or(pointer)
+        }
+        _ => pointer,
+    };
+
+    let motion: Vec2 = devices
+        .motion_events
+        .read()
+        .map(|motion| motion.delta)
+        .sum();
+    let canvas_size = view.canvas.size();
+    let pointer = match (input.virtual_cursor, window, view.projections.get_single()) {
+        (Some(virtual_cursor), Some(window), Ok(projection)) if touch.is_none() => {
+            // the deltas are physical pixels, same as the global cursor
+            let moved = virtual_cursor + motion / window.resolution.scale_factor();
+            let top_left = canvas_to_window(input.window_size, Vec2::ZERO, projection, canvas_size);
+            let bottom_right =
+                canvas_to_window(input.window_size, canvas_size, projection, canvas_size);
+            let clamped = moved.clamp(top_left, bottom_right);
+            input.virtual_cursor = Some(clamped);
+            Some(clamped)
+        }
+        _ => pointer,
+    };
+
+    input.pointer_velocity = match (input.pointer, pointer) {
+        // jumping between the real and the made-up cursor isn't a flick
+        _ if switched_mode => Vec2::ZERO,
+        (Some(previous), Some(current)) if time.delta_seconds() > 0.0 => {
+            (current - previous) / time.delta_seconds()
+        }
+        _ => Vec2::ZERO,
+    };
+    input.pointer = pointer;
+    input.pressure = touch
+        .and_then(Touch::force)
+        .map_or(1.0, pressure_multiplier);
+    input.canvas_pointer = match (pointer, view.projections.get_single()) {
+        (Some(pointer), Ok(projection)) => Some(window_to_canvas(
+            input.window_size,
+            pointer,
+            projection,
+            canvas_size,
+        )),
+        _ => None,
+    };
+}
+
+// The window's own enter and leave events say whether the cursor is over it, `pointer` alone would
+// flicker. Touches, the made-up cursor and the global one still count whenever there's a pointer.
+pub fn track_pointer_presence(
+    time: Res<Time<Real>>,
+    virtual_time: Res<Time<Virtual>>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut entered_events: EventReader<CursorEntered>,
+    mut left_events: EventReader<CursorLeft>,
+    mut input: ResMut<InputState>,
+) {
+    let Ok((primary, window)) = windows.get_single() else {
+        return;
+    };
+    let entered = entered_events.read().any(|event| event.window == primary);
+    let left = left_events.read().any(|event| event.window == primary);
+
+    input.cursor_inside = match (entered, left) {
+        // in and out within a frame, the window's cursor knows which came last
+        (true, true) => window.cursor_position().is_some(),
+        (true, false) => true,
+        (false, true) => false,
+        (false, false) => input.cursor_inside,
+    };
+
+    // a window clicks go through stops hearing about the cursor, whatever it last said is stale
+    let present = (input.cursor_inside && window.cursor.hit_test) || input.pointer.is_some();
+    // by the real clock so slow motion doesn't look like leaving, stopped along with the virtual
+    // one while paused or hidden
+    input.away_secs = if present {
+        0.0
+    } else if virtual_time.is_paused() {
+        input.away_secs
+    } else {
+        input.away_secs + time.delta_seconds()
+    };
+}
+
+fn resolve_press(
+    menu: Res<State<MenuState>>,
+    mode: Res<State<AppMode>>,
+    interactions: Query<&Interaction>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    query_cube: Query<&GlobalTransform, With<Cube>>,
+    mut input: ResMut<InputState>,
+) {
+    input.press = None;
+    if !input.just_did(Action::Poke) {
+        return;
+    }
+
+    // with a menu or the overlay up every press is theirs, even the ones that close them
+    let on_ui = *menu.get() != MenuState::Closed
+        || *mode.get() == AppMode::Paused
+        || interactions
+            .iter()
+            .any(|interaction| *interaction != Interaction::None);
+    let on_cube = match (
+        input.canvas_pointer,
+        cameras.get_single(),
+        query_cube.get_single(),
+    ) {
+        (Some(cursor), Ok((camera, camera_transform)), Ok(cube_transform)) => {
+            cube_on_canvas(camera, camera_transform, cube_transform)
+                .is_some_and(|(center, radius)| cursor.distance(center) <= radius)
+        }
+        _ => false,
+    };
+
+    input.press = Some(if on_ui {
+        PressTarget::Ui
+    } else if on_cube {
+        PressTarget::Cube
+    } else {
+        PressTarget::Background
+    });
+}
+
+// The hotkey confines the cursor to the window and has the mouse steer a made-up cursor by its
+// motion, for when the real one is grabbed or hidden. Switching away from the window lets go, and
+// going back to absolute just picks the real cursor up again. Returns the grab to switch to when
+// the mode changed.
+fn switch_relative_mode(input: &mut InputState, lost_focus: bool) -> Option<CursorGrabMode> {
+    let toggled = input.just_did(Action::RelativeMouse);
+
+    if input.virtual_cursor.is_some() && (toggled || lost_focus) {
+        input.virtual_cursor = None;
+        Some(CursorGrabMode::None)
+    } else if input.virtual_cursor.is_none() && toggled {
+        // starts from wherever the cube is already looking
+        input.virtual_cursor = Some(input.pointer.unwrap_or(input.window_size / 2.));
+        Some(CursorGrabMode::Confined)
+    } else {
+        None
+    }
+}
+
+fn pressure_multiplier(force: ForceTouch) -> f32 {
+    let multiplier = match force {
+        // 1.0 is an average touch, which is where the scaling should sit too
+        ForceTouch::Calibrated { force, .. } => 0.5 + 0.5 * force as f32,
+        // the device's full range, lightest to hardest
+        ForceTouch::Normalized(force) => MIN_PRESSURE + force as f32,
+    };
+
+    multiplier.clamp(MIN_PRESSURE, MAX_PRESSURE)
+}
+
+#[cfg(feature = "global-cursor")]
+#[derive(Resource, Default)]
+pub struct GlobalCursor {
+    last: Option<IVec2>,
+    still: Stopwatch,
+}
+
+// Follows the OS cursor outside the window too, in the same window coordinates as the in-window one.
+// `None` while the window position isn't known yet, then the in-window cursor will do.
+#[cfg(feature = "global-cursor")]
+fn track_global_cursor(
+    time: &Time<Real>,
+    window: &Window,
+    global_cursor: &mut GlobalCursor,
+) -> Option<Option<Vec2>> {
+    // the window position is only known once it has moved
+    let (Mouse::Position { x, y }, WindowPosition::At(window_position)) =
+        (Mouse::get_mouse_position(), window.position)
+    else {
+        return None;
+    };
+    let screen = IVec2::new(x, y);
+
+    if global_cursor.last != Some(screen) {
+        global_cursor.last = Some(screen);
+        global_cursor.still.reset();
+    }
+    global_cursor.still.tick(time.delta());
+
+    // both are physical pixels, and the window position is its outer corner, so this is off by the
+    // title bar, which is small next to the desktop
+    let physical = (screen - window_position).as_vec2();
+    Some(
+        (global_cursor.still.elapsed_secs() < GLOBAL_CURSOR_IDLE_SECS)
+            .then(|| physical / window.resolution.scale_factor()),
+    )
+}
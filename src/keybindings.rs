@@ -0,0 +1,131 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::{config::read_override, input::InputState};
+
+const EMBEDDED_BINDINGS: &str = include_str!("../assets/keybindings.ron");
+const BINDINGS_FILE_NAME: &str = "keybindings.ron";
+
+pub struct KeybindingsPlugin;
+
+impl Plugin for KeybindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Keybindings::load())
+            .add_systems(Update, print_bindings);
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    // backs out of whatever is going on: menus, overrides, celebrations
+    Cancel,
+    // keep the window above all the others
+    AlwaysOnTop,
+    // whole multiples, fit or stretch to the window
+    CanvasScaling,
+    // step through the canvas resolutions
+    CanvasSize,
+    // pass every click through the window to whatever is under it
+    ClickThrough,
+    Coffee,
+    // half the canvas without multisampling next to half with it
+    CompareMsaa,
+    // another tonemapper on half the canvas, the next one each press
+    CompareTonemapping,
+    // step through the palettes the canvas can be limited to, then off
+    CyclePalette,
+    // a second window with the canvas unscaled
+    DebugWindow,
+    DumpHistory,
+    // held, not toggled
+    LookAtViewer,
+    PrintBindings,
+    // reads the canvas, outline and rim shaders off the disk again, shader-reload builds only
+    ReloadShaders,
+    // steer by mouse motion with the cursor confined to the window
+    RelativeMouse,
+    // saves the canvas as it was drawn, before it's scaled up
+    Screenshot,
+    // the old tube screen look
+    ToggleCrt,
+    ToggleFullscreen,
+    // the smaller canvas, single light and lower frame rate, for saving battery
+    ToggleLowPower,
+    // starts a clip of the canvas, and saves it on the second press
+    ToggleRecording,
+    // switch between waiting on the display and drawing as fast as possible
+    ToggleVsync,
+    ToggleSulk,
+    // the cube's triangles over it, debug-tools builds only
+    ToggleWireframe,
+    ForceHappy,
+    ForceSad,
+    ForceSleepy,
+    ForceAngry,
+    ForceDizzy,
+    ForceExcited,
+    ForceScared,
+    ForceHungry,
+    ForceSulking,
+    // a left click or a tap, on top of whatever key it's bound to
+    Poke,
+}
+
+#[derive(Deserialize)]
+struct BindingsFile {
+    bindings: HashMap<Action, KeyCode>,
+}
+
+#[derive(Resource, Debug)]
+pub struct Keybindings {
+    bindings: HashMap<Action, KeyCode>,
+}
+
+impl Keybindings {
+    // Starts from the embedded bindings and lays the ones next to the executable on top.
+    fn load() -> Self {
+        let mut bindings = ron::from_str::<BindingsFile>(EMBEDDED_BINDINGS)
+            .expect("embedded keybindings.ron is valid")
+            .bindings;
+        if let Some(file) = read_override::<BindingsFile>(BINDINGS_FILE_NAME) {
+            bindings.extend(file.bindings);
+        }
+
+        let keybindings = Keybindings { bindings };
+        keybindings.warn_about_conflicts();
+        keybindings
+    }
+
+    fn warn_about_conflicts(&self) {
+        let mut by_key: HashMap<KeyCode, Vec<Action>> = HashMap::default();
+        for (action, key) in &self.bindings {
+            by_key.entry(*key).or_default().push(*action);
+        }
+
+        for (key, mut actions) in by_key {
+            if actions.len() > 1 {
+                actions.sort();
+                warn!("{key:?} is bound to more than one action: {actions:?}");
+            }
+        }
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = (Action, KeyCode)> + '_ {
+        self.bindings.iter().map(|(action, key)| (*action, *key))
+    }
+}
+
+fn print_bindings(bindings: Res<Keybindings>, input: Res<InputState>) {
+    if !input.just_did(Action::PrintBindings) {
+        return;
+    }
+
+    let mut lines: Vec<_> = bindings.bindings.iter().collect();
+    lines.sort_by_key(|(action, _)| **action);
+    let lines: Vec<String> = lines
+        .into_iter()
+        .map(|(action, key)| format!("{action:?}: {key:?}"))
+        .collect();
+
+    info!("key bindings:\n{}", lines.join("\n"));
+}
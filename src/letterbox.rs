@@ -0,0 +1,231 @@
+use bevy::{
+    asset::load_internal_binary_asset,
+    prelude::*,
+    render::{
+        camera::CameraUpdateSystem,
+        render_asset::RenderAssetUsages,
+        texture::{CompressedImageFormats, ImageSampler, ImageType},
+        view::RenderLayers,
+    },
+    sprite::{Anchor, ImageScaleMode},
+    transform::TransformSystem,
+};
+
+use crate::{
+    canvas::CanvasSettings, pet_mode::PetMode, settings::Settings, setup_camera, MainCamera,
+    LETTERBOX_LAYER,
+};
+
+const FRAME_IMAGE: Handle<Image> =
+    Handle::weak_from_u128(0x6c65_7474_6572_626f_785f_6672_616d_6501);
+// how far past the window's edges the bars reach, so none of the clear color shows at the seams
+const OVERSCAN: f32 = 1.0;
+
+pub struct LetterboxPlugin;
+
+impl Plugin for LetterboxPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_binary_asset!(
+            app,
+            FRAME_IMAGE,
+            "../assets/letterbox-frame.png",
+            |bytes, _: String| Image::from_buffer(
+                bytes,
+                ImageType::Extension("png"),
+                CompressedImageFormats::NONE,
+                true,
+                ImageSampler::nearest(),
+                RenderAssetUsages::RENDER_WORLD,
+            )
+            .expect("embedded letterbox-frame.png is valid")
+        );
+
+        app.add_systems(Startup, spawn_letterbox.after(setup_camera))
+            .add_systems(
+                Update,
+                style_letterbox
+                    .run_if(resource_changed::<Settings>.or_else(resource_changed::<ClearColor>)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    // both cameras work out the same area from the same projection
+                    follow_main_camera.before(CameraUpdateSystem),
+                    // the projection's area is only worked out for the new window size in
+                    // CameraUpdateSystem, and the bars have to move before their transforms are
+                    // propagated to be drawn in the same frame
+                    fit_letterbox
+                        .after(CameraUpdateSystem)
+                        .before(TransformSystem::TransformPropagate),
+                ),
+            );
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+// One of the four bars filling the window around the canvas when its shape doesn't match.
+#[derive(Component)]
+struct LetterboxBar(Side);
+
+// Draws the bars before the main camera draws the canvas over the middle. It rides along under the
+// main camera, so it sees what that sees.
+#[derive(Component)]
+struct LetterboxCamera;
+
+// What the main camera clears the window to. The letterbox camera has cleared it already, the pet
+// window has no letterbox and clears for itself.
+pub fn main_clear_color(pet_mode: PetMode) -> ClearColorConfig {
+    if pet_mode.enabled {
+        pet_mode.clear_color()
+    } else {
+        ClearColorConfig::None
+    }
+}
+
+// The pet window is cropped around the cube and see-through, it has no bars to fill.
+fn spawn_letterbox(
+    mut commands: Commands,
+    pet_mode: Res<PetMode>,
+    main_cameras: Query<Entity, With<MainCamera>>,
+) {
+    if pet_mode.enabled {
+        return;
+    }
+    let Ok(main_camera) = main_cameras.get_single() else {
+        return;
+    };
+
+    commands.entity(main_camera).with_children(|main_camera| {
+        main_camera.spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    order: -1,
+                    ..default()
+                },
+                // right where the main camera is, rather than the bundle's own distance back
+                transform: Transform::IDENTITY,
+                ..default()
+            },
+            RenderLayers::layer(LETTERBOX_LAYER),
+            LetterboxCamera,
+        ));
+    });
+    for side in [Side::Left, Side::Right, Side::Top, Side::Bottom] {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::ZERO),
+                    anchor: Anchor::BottomLeft,
+                    ..default()
+                },
+                ..default()
+            },
+            RenderLayers::layer(LETTERBOX_LAYER),
+            LetterboxBar(side),
+        ));
+    }
+}
+
+// The color from the settings, or the window's clear color so the bars look just like the plain
+// window did. The frame is tiled over the bars a canvas pixel per texel, tinted by that color.
+fn style_letterbox(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    clear_color: Res<ClearColor>,
+    mut bars: Query<(Entity, &mut Sprite, &mut Handle<Image>), With<LetterboxBar>>,
+    mut warned: Local<Option<String>>,
+) {
+    let fallback = if settings.letterbox_frame {
+        Color::WHITE
+    } else {
+        clear_color.0
+    };
+    let color = match settings.letterbox_color.as_deref() {
+        Some(hex) => Srgba::hex(hex).map(Color::from).unwrap_or_else(|error| {
+            if warned.as_deref() != Some(hex) {
+                warn!("letterbox color {hex:?} isn't a hex color: {error}");
+            }
+            fallback
+        }),
+        None => fallback,
+    };
+    warned.clone_from(&settings.letterbox_color);
+
+    for (entity, mut sprite, mut image) in &mut bars {
+        sprite.color = color;
+        if settings.letterbox_frame {
+            *image = FRAME_IMAGE;
+            commands.entity(entity).insert(ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: true,
+                stretch_value: 1.0,
+            });
+        } else {
+            *image = Handle::default();
+            commands.entity(entity).remove::<ImageScaleMode>();
+        }
+    }
+}
+
+fn follow_main_camera(
+    main: Query<Ref<OrthographicProjection>, With<MainCamera>>,
+    mut letterbox: Query<&mut OrthographicProjection, (With<LetterboxCamera>, Without<MainCamera>)>,
+) {
+    let (Ok(main_projection), Ok(mut projection)) = (main.get_single(), letterbox.get_single_mut())
+    else {
+        return;
+    };
+
+    if main_projection.is_changed() {
+        *projection = main_projection.clone();
+    }
+}
+
+// Spans from the canvas's edges out past whatever the main camera sees of the window, by the same
+// fit `fit_canvas` gave the projection. Zoomed in far enough for the canvas to fill the window, the
+// bars shrink away to nothing.
+fn fit_letterbox(
+    canvas: Res<CanvasSettings>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    mut bars: Query<(&LetterboxBar, &mut Sprite, &mut Transform), Without<Camera>>,
+) {
+    let Ok((camera, projection)) = cameras.get_single() else {
+        return;
+    };
+
+    let half_canvas = canvas.size() / 2.0;
+    let seen = camera.translation.truncate();
+    let outer_min = (seen + projection.area.min - OVERSCAN).min(-half_canvas);
+    let outer_max = (seen + projection.area.max + OVERSCAN).max(half_canvas);
+
+    for (bar, mut sprite, mut transform) in &mut bars {
+        let (min, max) = match bar.0 {
+            Side::Left => (outer_min, Vec2::new(-half_canvas.x, outer_max.y)),
+            Side::Right => (Vec2::new(half_canvas.x, outer_min.y), outer_max),
+            Side::Top => (
+                Vec2::new(-half_canvas.x, half_canvas.y),
+                Vec2::new(half_canvas.x, outer_max.y),
+            ),
+            Side::Bottom => (
+                Vec2::new(-half_canvas.x, outer_min.y),
+                Vec2::new(half_canvas.x, -half_canvas.y),
+            ),
+        };
+
+        let size = Some(max - min);
+        if sprite.custom_size != size {
+            sprite.custom_size = size;
+        }
+        let translation = min.extend(0.0);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
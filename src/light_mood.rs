@@ -0,0 +1,237 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::{
+    accessibility::{ColorPreset, ColorTables},
+    config::read_override,
+    fade::Fade,
+    settings::Settings,
+    skin::SkinLibrary,
+    time_of_day::lighting_update,
+    CubeState,
+};
+
+const EMBEDDED_LIGHTS: &str = include_str!("../assets/lights.ron");
+const LIGHTS_FILE_NAME: &str = "lights.ron";
+// how long the lights take to come round to a new state
+const LIGHT_MOOD_SECS: f32 = 2.0;
+
+pub struct LightMoodPlugin;
+
+impl Plugin for LightMoodPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LightThemes::load())
+            .init_resource::<LightMood>()
+            .add_systems(PreUpdate, light_mood.before(lighting_update));
+    }
+}
+
+// The main light, the one casting the shadow.
+#[derive(Component)]
+pub struct KeyLight;
+
+// The dimmer one opposite, lifting the shadowed side.
+#[derive(Component)]
+pub struct FillLight;
+
+#[derive(Deserialize, Clone)]
+pub struct LightEntry {
+    color: String,
+    intensity: f32,
+    #[serde(default)]
+    flicker: f32,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct StateLights {
+    key: LightEntry,
+    fill: LightEntry,
+}
+
+#[derive(Deserialize)]
+struct LightsFile {
+    states: HashMap<CubeState, StateLights>,
+    // states that look different with one of the color presets, on top of the ones above and
+    // colors.ron's
+    #[serde(default)]
+    presets: HashMap<ColorPreset, HashMap<CubeState, StateLights>>,
+}
+
+// How one light is tinted and scaled, on top of the mood and the time of day.
+#[derive(Clone, Copy, PartialEq)]
+pub struct LightLook {
+    // sRGB, multiplied into the light's color
+    pub color: Vec3,
+    pub intensity: f32,
+    flicker: f32,
+}
+
+impl LightLook {
+    const NEUTRAL: LightLook = LightLook {
+        color: Vec3::ONE,
+        intensity: 1.0,
+        flicker: 0.0,
+    };
+
+    fn from_entry(entry: &LightEntry, state: CubeState) -> Self {
+        let color = Srgba::hex(&entry.color).unwrap_or_else(|error| {
+            warn!(
+                "light color {:?} for {state:?} isn't a hex color: {error}",
+                entry.color
+            );
+            Srgba::WHITE
+        });
+        LightLook {
+            color: Vec3::new(color.red, color.green, color.blue),
+            intensity: entry.intensity.max(0.0),
+            flicker: entry.flicker.clamp(0.0, 1.0),
+        }
+    }
+
+    fn lerp(self, other: LightLook, t: f32) -> LightLook {
+        LightLook {
+            color: self.color.lerp(other.color, t),
+            intensity: self.intensity + (other.intensity - self.intensity) * t,
+            flicker: self.flicker + (other.flicker - self.flicker) * t,
+        }
+    }
+
+    // Wavering on a few sines that never quite line up, so it doesn't read as a pulse.
+    fn flickered(self, seconds: f32) -> LightLook {
+        let waver = (seconds * 13.0).sin() * 0.5
+            + (seconds * 31.0).sin() * 0.3
+            + (seconds * 7.0).sin() * 0.2;
+        LightLook {
+            intensity: self.intensity * (1.0 - self.flicker * (0.5 + 0.5 * waver)),
+            ..self
+        }
+    }
+}
+
+type StateLooks = HashMap<CubeState, (LightLook, LightLook)>;
+
+fn state_looks(states: HashMap<CubeState, StateLights>) -> StateLooks {
+    states
+        .into_iter()
+        .map(|(state, lights)| {
+            (
+                state,
+                (
+                    LightLook::from_entry(&lights.key, state),
+                    LightLook::from_entry(&lights.fill, state),
+                ),
+            )
+        })
+        .collect()
+}
+
+// The states one skin's entry in skins.ron has its own lights for.
+pub struct SkinLights(StateLooks);
+
+impl SkinLights {
+    pub fn new(states: HashMap<CubeState, StateLights>) -> Self {
+        SkinLights(state_looks(states))
+    }
+}
+
+// The key and fill looks for each state, the built-in ones with any from lights.ron on top, and
+// what each color preset changes. The current skin's own go between the two.
+#[derive(Resource)]
+struct LightThemes {
+    states: StateLooks,
+    presets: HashMap<ColorPreset, StateLooks>,
+}
+
+impl LightThemes {
+    fn load() -> Self {
+        let LightsFile { mut states, .. } =
+            ron::from_str::<LightsFile>(EMBEDDED_LIGHTS).expect("embedded lights.ron is valid");
+        let tables = ColorTables::load();
+        let mut presets: HashMap<_, _> = ColorPreset::ALL
+            .into_iter()
+            .map(|preset| (preset, tables.each(preset, |look| look.lights.as_ref())))
+            .collect();
+        if let Some(file) = read_override::<LightsFile>(LIGHTS_FILE_NAME) {
+            states.extend(file.states);
+            for (preset, preset_states) in file.presets {
+                presets.entry(preset).or_default().extend(preset_states);
+            }
+        }
+
+        LightThemes {
+            states: state_looks(states),
+            presets: presets
+                .into_iter()
+                .map(|(preset, states)| (preset, state_looks(states)))
+                .collect(),
+        }
+    }
+
+    fn get(
+        &self,
+        state: CubeState,
+        preset: ColorPreset,
+        skin: Option<&SkinLights>,
+    ) -> (LightLook, LightLook) {
+        self.presets
+            .get(&preset)
+            .and_then(|states| states.get(&state))
+            .or_else(|| skin.and_then(|skin| skin.0.get(&state)))
+            .or_else(|| self.states.get(&state))
+            .or_else(|| self.states.get(&CubeState::Happy))
+            .copied()
+            .unwrap_or((LightLook::NEUTRAL, LightLook::NEUTRAL))
+    }
+}
+
+// Where the lights are on their way from one state's look to the next.
+#[derive(Resource)]
+pub struct LightMood {
+    fade: Fade<(LightLook, LightLook)>,
+    // this frame's, flicker and all
+    key: LightLook,
+    fill: LightLook,
+}
+
+impl Default for LightMood {
+    fn default() -> Self {
+        let neutral = (LightLook::NEUTRAL, LightLook::NEUTRAL);
+        LightMood {
+            fade: Fade::new(neutral, LIGHT_MOOD_SECS, |from, to, t| {
+                (from.0.lerp(to.0, t), from.1.lerp(to.1, t))
+            }),
+            key: LightLook::NEUTRAL,
+            fill: LightLook::NEUTRAL,
+        }
+    }
+}
+
+impl LightMood {
+    pub fn key(&self) -> LightLook {
+        self.key
+    }
+
+    pub fn fill(&self) -> LightLook {
+        self.fill
+    }
+}
+
+fn light_mood(
+    time: Res<Time>,
+    state: Res<State<CubeState>>,
+    settings: Res<Settings>,
+    themes: Res<LightThemes>,
+    skins: Res<SkinLibrary>,
+    mut mood: ResMut<LightMood>,
+) {
+    // switching skins eases over to the new one's lights like any other change
+    let skin = skins.current().lights.as_ref();
+    mood.fade
+        .retarget(themes.get(*state.get(), settings.colors, skin));
+    mood.fade.tick(time.delta_seconds());
+
+    let (key, fill) = mood.fade.value();
+    let seconds = time.elapsed_seconds();
+    mood.key = key.flickered(seconds);
+    mood.fill = fill.flickered(seconds);
+}
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    canvas::{CanvasSettings, MIN_CANVAS_SIZE},
+    input::InputState,
+    keybindings::Action,
+    light_mood::FillLight,
+    pet_mode::PetMode,
+    settings::Settings,
+};
+
+// the most frames per second that get drawn in low power, lower caps from the settings still win
+const LOW_POWER_FPS: u32 = 30;
+// real seconds between asking the OS whether it's still on battery
+const BATTERY_POLL_SECS: f32 = 10.0;
+
+pub struct LowPowerPlugin;
+
+impl Plugin for LowPowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LowPowerMode>()
+            .add_systems(Update, (toggle_low_power, poll_battery))
+            // the canvas size it changes is only picked up from the next frame on, which also keeps
+            // the first frame's change from being mistaken for the canvas just being made
+            .add_systems(PostUpdate, apply_low_power);
+    }
+}
+
+// Costs less to draw, for laptops: half the canvas in each direction, which the fit to the window
+// makes up for with twice the upscale, only the key light, and a frame rate cap.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LowPower {
+    #[default]
+    Off,
+    On,
+    // whenever the OS says it's running off the battery
+    OnBattery,
+}
+
+impl LowPower {
+    pub fn label(self) -> &'static str {
+        match self {
+            LowPower::Off => "Off",
+            LowPower::On => "On",
+            LowPower::OnBattery => "On battery",
+        }
+    }
+
+    pub fn next(self) -> LowPower {
+        match self {
+            LowPower::Off => LowPower::On,
+            LowPower::On => LowPower::OnBattery,
+            LowPower::OnBattery => LowPower::Off,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct LowPowerMode {
+    active: bool,
+    // flipped by hand until the next launch, along with the setting it was flipped against, so
+    // picking another one in the settings panel takes back over
+    manual: Option<(bool, LowPower)>,
+    // unknown where the OS can't be asked
+    on_battery: Option<bool>,
+    poll: Timer,
+    // the two canvas sizes it switched between, to only switch back if nothing else changed it
+    halved: Option<(UVec2, UVec2)>,
+}
+
+impl Default for LowPowerMode {
+    fn default() -> Self {
+        LowPowerMode {
+            active: false,
+            manual: None,
+            on_battery: on_battery(),
+            poll: Timer::from_seconds(BATTERY_POLL_SECS, TimerMode::Repeating),
+            halved: None,
+        }
+    }
+}
+
+impl LowPowerMode {
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    // The cap from the settings, brought down to the low power one while that's on.
+    pub fn fps_cap(&self, settings: &Settings) -> Option<u32> {
+        let cap = settings.fps_cap.filter(|cap| *cap > 0);
+        if self.active {
+            Some(cap.map_or(LOW_POWER_FPS, |cap| cap.min(LOW_POWER_FPS)))
+        } else {
+            cap
+        }
+    }
+
+    fn wanted(&self, setting: LowPower) -> bool {
+        if let Some((active, _)) = self.manual {
+            return active;
+        }
+        match setting {
+            LowPower::Off => false,
+            LowPower::On => true,
+            LowPower::OnBattery => self.on_battery.unwrap_or(false),
+        }
+    }
+}
+
+// Flips it on or off by hand, even if it's on because of the battery. Only for this run, the saved
+// setting is left for the settings panel.
+fn toggle_low_power(
+    input: Res<InputState>,
+    settings: Res<Settings>,
+    mut mode: ResMut<LowPowerMode>,
+) {
+    if !input.just_did(Action::ToggleLowPower) {
+        return;
+    }
+
+    let active = !mode.active;
+    mode.manual = Some((active, settings.low_power));
+    info!("low power {} by hand", if active { "on" } else { "off" });
+}
+
+fn poll_battery(time: Res<Time<Real>>, mut mode: ResMut<LowPowerMode>) {
+    if !mode.poll.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let on_battery = on_battery();
+    if mode.on_battery != on_battery {
+        mode.on_battery = on_battery;
+    }
+}
+
+// The pet window crops a fixed number of canvas pixels around the cube at a fixed scale, so it
+// keeps its canvas and only drops the light and the frame rate.
+fn apply_low_power(
+    settings: Res<Settings>,
+    pet_mode: Res<PetMode>,
+    mut mode: ResMut<LowPowerMode>,
+    mut canvas: ResMut<CanvasSettings>,
+    mut fill_lights: Query<&mut Visibility, With<FillLight>>,
+    mut warned: Local<bool>,
+) {
+    if settings.low_power == LowPower::OnBattery && mode.on_battery.is_none() && !*warned {
+        warn!("there's no telling whether this is on battery here, leaving low power off");
+        *warned = true;
+    }
+
+    if mode
+        .manual
+        .is_some_and(|(_, against)| against != settings.low_power)
+    {
+        mode.manual = None;
+    }
+    let wanted = mode.wanted(settings.low_power);
+    if mode.active == wanted {
+        return;
+    }
+    mode.active = wanted;
+
+    if wanted && !pet_mode.enabled {
+        let full = canvas.resolution;
+        let half = (full / 2).max(MIN_CANVAS_SIZE);
+        canvas.resolution = half;
+        mode.halved = Some((full, half));
+    } else if let Some((full, half)) = mode.halved.take() {
+        // a size picked by hand in the meantime stays
+        if canvas.resolution == half {
+            canvas.resolution = full;
+        }
+    }
+
+    let visibility = if wanted {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut fill_light in &mut fill_lights {
+        *fill_light = visibility;
+    }
+
+    info!(
+        "{} low power, drawing at {}x{}",
+        if wanted { "into" } else { "out of" },
+        canvas.resolution.x,
+        canvas.resolution.y
+    );
+}
+
+// Whether a battery is running down, a desktop without any is on the mains.
+#[cfg(target_os = "linux")]
+fn on_battery() -> Option<bool> {
+    let supplies = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut batteries = supplies.filter_map(Result::ok).filter(|supply| {
+        std::fs::read_to_string(supply.path().join("type"))
+            .is_ok_and(|kind| kind.trim() == "Battery")
+    });
+
+    Some(batteries.any(|battery| {
+        std::fs::read_to_string(battery.path().join("status"))
+            .is_ok_and(|status| status.trim() == "Discharging")
+    }))
+}
+
+#[cfg(all(windows, feature = "battery"))]
+fn on_battery() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    // SAFETY: only fills in the struct it's handed
+    let status = unsafe {
+        let mut status: SYSTEM_POWER_STATUS = std::mem::zeroed();
+        if GetSystemPowerStatus(&mut status) == 0 {
+            return None;
+        }
+        status
+    };
+    // 0 is off the mains, 1 on them, 255 unknown
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", all(windows, feature = "battery"))))]
+fn on_battery() -> Option<bool> {
+    None
+}
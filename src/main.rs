@@ -1,252 +1,1835 @@
 #![windows_subsystem = "windows"]
 
+mod accessibility;
+mod ambient_occlusion;
+mod antialiasing;
+mod background;
+mod behavior;
+mod canvas;
+mod canvas_material;
+mod celebration;
+mod coffee;
+mod compare;
+mod config;
+mod debug_window;
+mod easter_egg;
+mod face;
+mod fade;
+mod frame_pacing;
+mod fullscreen_hide;
+mod gamepad;
+mod glow;
+mod grab;
+mod heartbeat;
+mod history;
+mod hunger;
+mod idle;
+mod input;
+mod keybindings;
+mod letterbox;
+mod light_mood;
+mod low_power;
+mod manual_override;
+mod menu;
+mod mood;
+mod msaa;
+mod outline;
+mod palette;
+mod particles;
+mod pause;
+mod personality;
+mod pet_mode;
+mod petting;
+mod reactions;
+mod readback;
+mod recording;
+mod reposition;
+mod rim;
+mod screen_effects;
+mod screenshot;
+mod session;
+mod settings;
+mod settings_menu;
+mod shader_reload;
+mod shadows;
+mod shutdown;
+mod skin;
+mod sway;
+mod time_dilation;
+mod time_of_day;
+mod tonemapping;
+mod tray;
+mod typing;
+mod window_controls;
+mod window_icon;
+mod window_placement;
+mod window_shake;
+mod wireframe;
+mod yawn;
+
+use accessibility::{AccessibilityPlugin, ColorPreset};
+use ambient_occlusion::camera_ambient_occlusion;
+use antialiasing::{camera_fxaa, AntialiasingPlugin};
+use background::BackgroundPlugin;
+use behavior::BehaviorPlugin;
 use bevy::{
+    ecs::system::SystemParam,
+    input::mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
     render::{
-        camera::RenderTarget,
-        render_resource::{
-            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
-        },
+        camera::{CameraProjection, CameraUpdateSystem, RenderTarget, ScalingMode},
+        settings::WgpuSettings,
+        view::RenderLayers,
+        RenderPlugin,
     },
-    window::{WindowMode, WindowResized},
+    sprite::MaterialMesh2dBundle,
+    time::Stopwatch,
+    transform::TransformSystem,
+    window::{PrimaryWindow, WindowResized, WindowScaleFactorChanged},
 };
+use canvas::{canvas_image, CanvasPlugin, CanvasScaling, CanvasSettings};
+use canvas_material::{CanvasMaterial, CanvasMaterialPlugin};
+use celebration::CelebrationPlugin;
+use coffee::{Caffeinated, CoffeePlugin};
+use compare::ComparePlugin;
+use debug_window::DebugWindowPlugin;
+use easter_egg::EasterEggPlugin;
+use face::{Blink, FacePlugin};
+use frame_pacing::FramePacingPlugin;
+use fullscreen_hide::FullscreenHidePlugin;
+use gamepad::{GamepadLookPlugin, LookInput};
+use glow::{camera_bloom, GlowPlugin};
+use grab::{GrabPlugin, Grabbed};
+use heartbeat::HeartbeatPlugin;
+use history::HistoryPlugin;
+use hunger::HungerPlugin;
+use idle::{window_visible, IdlePlugin};
+use input::{InputState, InputStatePlugin, PressTarget};
+use keybindings::{Action, KeybindingsPlugin};
+use letterbox::{main_clear_color, LetterboxPlugin};
+use light_mood::{FillLight, KeyLight, LightMoodPlugin};
+use low_power::LowPowerPlugin;
+use manual_override::{ManualOverride, ManualOverridePlugin};
+use menu::{MenuPlugin, MenuState};
+use mood::{Mood, MoodLight, MoodPlugin};
+use msaa::{allowed_msaa, MsaaPlugin};
+use outline::{camera_outline, OutlinePlugin};
+use palette::PalettePlugin;
+use particles::{ParticlesPlugin, SleepParticleSpawner};
+use pause::{AppMode, PausePlugin};
+use personality::{Personality, PersonalityPlugin};
+use pet_mode::{PetMode, PET_CANVAS_SCALE};
+use petting::PettingPlugin;
 use rand::prelude::*;
-use std::f32::consts::PI;
+use reactions::{Gesture, GestureKind, ReactionsPlugin, Sneezing};
+use readback::ReadbackPlugin;
+use recording::RecordingPlugin;
+use reposition::{MiddleDrag, RepositionPlugin};
+use rim::RimPlugin;
+use screen_effects::ScreenEffectsPlugin;
+use screenshot::ScreenshotPlugin;
+use serde::{Deserialize, Serialize};
+use session::{LastSession, SessionPlugin};
+use settings::{Settings, SettingsPlugin};
+use settings_menu::SettingsMenuPlugin;
+use shader_reload::{asset_plugin, ShaderReloadPlugin};
+use shadows::ShadowsPlugin;
+use shutdown::ShutdownPlugin;
+use skin::{SkinLibrary, SkinPlugin};
+use std::{collections::VecDeque, f32::consts::PI};
+use sway::SwayPlugin;
+use time_dilation::TimeDilationPlugin;
+use time_of_day::TimeOfDayPlugin;
+use tonemapping::{camera_tonemapping, TonemappingPlugin};
+use tray::TrayPlugin;
+use typing::{TypingActivity, TypingPlugin};
+use window_controls::WindowControlsPlugin;
+use window_icon::WindowIconPlugin;
+use window_placement::{restore_placement, MonitorChoice, WindowPlacementPlugin};
+use window_shake::WindowShakePlugin;
+use wireframe::{wireframe_features, WireframeViewPlugin};
+use yawn::YawnPlugin;
+
+const CANVAS_MAX_ZOOM: u32 = 4;
+// touchpads scroll in pixels, this much counts as one wheel notch
+const CANVAS_ZOOM_PIXELS_PER_STEP: f32 = 50.0;
+
+// Which camera draws what. Anything spawned without RenderLayers lands on the scene's layer, so
+// sprites and text have to say which of the others they're on.
+// the cube, its lights and the backdrop, drawn into the canvas by the 3D camera
+const SCENE_LAYER: usize = 0;
+// the canvas and anything in canvas pixels on top of it, particles, confetti and the like, scaled
+// up with it by the main camera
+const CANVAS_LAYER: usize = 1;
+// text and indicators at the window's own resolution, drawn over everything and never snapped to
+// canvas pixels
+const UI_LAYER: usize = 2;
+// the bars around the canvas, drawn into the window before anything else
+const LETTERBOX_LAYER: usize = 3;
 
-const RES_WIDTH: u32 = 640;
-const RES_HEIGHT: u32 = 360;
+// what makes the cube change states lives in assets/behavior.ron, these are how the states play out
+
+// seconds the cursor has to sit still before the cube gets bored and starts glancing around
+const BORED_IDLE_SECS: f32 = 10.0;
+// cursor movement (in window pixels) below this doesn't count as moving
+const CURSOR_MOVE_THRESHOLD: f32 = 4.0;
+// how far back clicks and swirls are remembered for the transition table to look at
+const CURSOR_HISTORY_SECS: f32 = 10.0;
+const ANGRY_SECS: f32 = 3.0;
+const DIZZY_SECS: f32 = 2.5;
+// rushing at the window center again faster than this (window pixels per second) keeps it excited
+const EXCITED_SPEED: f32 = 2500.0;
+const EXCITED_SECS: f32 = 1.5;
+const SCARED_SECS: f32 = 2.0;
+// after calming down from Angry it sulks this long, or until the cursor sits still for a bit
+const SULK_SECS: f32 = 10.0;
+const SULK_FORGIVE_SECS: f32 = 3.0;
+// how far (in radians) it turns away while sulking
+const SULK_MAX_ANGLE: f32 = 0.6;
+// furthest the cube turns its head towards the cursor, in radians
+const MAX_LOOK_ANGLE: f32 = 1.0;
+// time constant of the low-pass on the tracked look, so single pixel steps don't jitter, unless
+// the settings pick another
+const LOOK_SMOOTHING_SECS: f32 = 0.08;
+// canvas pixels the cursor has to move before the tracked look changes at all
+const LOOK_DEAD_ZONE: f32 = 2.0;
+// how far (in radians) the gaze dips towards the keyboard while someone types flat out
+const TYPING_DIP: f32 = 0.35;
+// where the cube rests in the 3D scene
+const CUBE_POSITION: Vec3 = Vec3::new(0.0, 0.0, -13.0);
+// radius (in world units) around the cube's center that counts as a hit
+const CUBE_HIT_RADIUS: f32 = 1.1;
 
 #[derive(Component)]
 struct Cube {
     rotate_timer: Timer,
+    // how long the cursor has been sitting still
+    idle: Stopwatch,
+    state_timer: Timer,
     random_look_x: f32,
     random_look_y: f32,
+    last_cursor_pos: Vec2,
+    recent_clicks: Vec<f32>,
+    prev_cursor_pos: Option<Vec2>,
+    // in window pixels per second, zero while the cursor is still
+    cursor_velocity: Vec2,
+    last_cursor_move: f32,
+    // (timestamp, signed angle) the cursor swept around the window center each frame
+    swirl: Vec<(f32, f32)>,
+    spin_speed: f32,
+    bored: bool,
+    hover_timer: Timer,
+    tilt: f32,
+    // an authored clip owns the pose while this is set
+    playing_clip: bool,
+    // extra rotation overlays put on top of the behavior's pose this frame
+    look_offset: Quat,
+    // sad wander targets picked since it last checked back on the cursor's spot
+    wanders_since_glance: u32,
+    // (x, y) looks to take, 0.7 seconds each, before sad_cube_update goes back to `random_look`
+    search_queue: VecDeque<(f32, f32)>,
+    // low-pass state for the angles happy_cube_update tracks, None until the first sample
+    look_filter: Option<LookFilter>,
+    // (x, y) looks other systems asked for this frame instead of the cursor, see `request_look`
+    look_requests: Vec<(LookPriority, Vec2)>,
+    // whose look happy_cube_update went with last frame, None for the cursor
+    active_look: Option<LookPriority>,
+    // where it rests, CUBE_POSITION until it's dragged somewhere else
+    home: Vec3,
+}
+
+// Who gets to decide where the cube looks when more than one wants to, highest wins. Anything
+// without a request falls back to following the cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum LookPriority {
+    Gesture,
+    Manual,
+}
+
+#[derive(Clone, Copy)]
+struct LookFilter {
+    // canvas position the target was last taken at
+    cursor: Vec2,
+    // (x, y) angles the cursor asks for, and the eased ones actually used
+    target: Vec2,
+    smoothed: Vec2,
 }
 
 impl Default for Cube {
     fn default() -> Self {
         Cube {
             rotate_timer: Timer::from_seconds(0.5, TimerMode::Once),
+            idle: Stopwatch::new(),
+            state_timer: Timer::from_seconds(ANGRY_SECS, TimerMode::Once),
             random_look_x: 0.0,
             random_look_y: 0.0,
+            last_cursor_pos: Vec2::ZERO,
+            recent_clicks: Vec::new(),
+            prev_cursor_pos: None,
+            cursor_velocity: Vec2::ZERO,
+            last_cursor_move: 0.0,
+            swirl: Vec::new(),
+            spin_speed: 0.0,
+            bored: false,
+            hover_timer: Timer::from_seconds(2.0, TimerMode::Once),
+            tilt: 0.0,
+            playing_clip: false,
+            look_offset: Quat::IDENTITY,
+            wanders_since_glance: 0,
+            search_queue: VecDeque::new(),
+            look_filter: None,
+            look_requests: Vec::new(),
+            active_look: None,
+            home: CUBE_POSITION,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+impl Cube {
+    // Rotation without the overlays, which are still on until the next `remove_look_offset`.
+    fn base_rotation(&self, cube_transform: &Transform) -> Quat {
+        cube_transform.rotation * self.look_offset.inverse()
+    }
+
+    // Asks happy_cube_update to look at (x, y) instead of the cursor, for this frame only.
+    fn request_look(&mut self, priority: LookPriority, look: Vec2) {
+        self.look_requests.push((priority, look));
+    }
+
+    // Eases the tracked angles towards `target`, ignoring cursor moves under LOOK_DEAD_ZONE.
+    fn smooth_look(
+        &mut self,
+        cursor: Vec2,
+        target: Vec2,
+        smoothing_secs: f32,
+        delta_secs: f32,
+    ) -> Vec2 {
+        let filter = self.look_filter.get_or_insert(LookFilter {
+            cursor,
+            target,
+            smoothed: target,
+        });
+
+        if cursor.distance(filter.cursor) >= LOOK_DEAD_ZONE {
+            filter.cursor = cursor;
+            filter.target = target;
+        }
+        filter.smoothed = filter
+            .smoothed
+            .lerp(filter.target, 1.0 - (-delta_secs / smoothing_secs).exp());
+
+        filter.smoothed
+    }
+}
+
+// The 2D camera showing the canvas in the main window, as opposed to the debug window's.
+#[derive(Component)]
+struct MainCamera;
+
+// Draws `UI_LAYER` over the main window, one world unit per logical pixel with the origin in the
+// middle.
+#[derive(Component)]
+struct UiCamera;
+
+// What the 3D camera renders into, shared by every sprite that shows the canvas.
+#[derive(Resource)]
+struct CanvasImage(Handle<Image>);
+
+// How many logical window pixels each canvas pixel takes up.
+#[derive(Resource)]
+struct CanvasZoom {
+    // how much of the window the canvas fills, kept up to date by `fit_canvas`, a whole multiple
+    // of physical pixels unless the scaling setting allows fractions
+    fit: f32,
+    // extra multiple the user zoomed in by, 1 is the plain fit
+    zoom: u32,
+    // wheel travel that hasn't added up to a whole step yet
+    scrolled: f32,
+    // the canvas size to spread over the whole window on stretched scaling, squashing it as needed
+    stretch: Option<Vec2>,
+}
+
+impl Default for CanvasZoom {
+    fn default() -> Self {
+        CanvasZoom {
+            fit: 1.0,
+            zoom: 1,
+            scrolled: 0.0,
+            stretch: None,
+        }
+    }
+}
+
+impl CanvasZoom {
+    // Canvas pixels per logical window pixel, along the tighter axis when stretched.
+    fn scale(&self) -> f32 {
+        1. / (self.fit * self.zoom as f32)
+    }
+
+    // One canvas pixel in the logical window pixels UI is laid out in, for sizing the menus and
+    // overlays in canvas pixels so they match the art.
+    fn ui_pixel(&self) -> f32 {
+        1. / self.scale()
+    }
+
+    fn apply(&self, projection: &mut OrthographicProjection) {
+        match self.stretch {
+            Some(canvas_size) => {
+                projection.scaling_mode = ScalingMode::Fixed {
+                    width: canvas_size.x,
+                    height: canvas_size.y,
+                };
+                projection.scale = 1. / self.zoom as f32;
+            }
+            None => {
+                projection.scaling_mode = ScalingMode::WindowSize(1.0);
+                projection.scale = self.scale();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States, Serialize, Deserialize)]
 enum CubeState {
     #[default]
     Happy,
     Sad,
+    Sleepy,
+    Angry,
+    Dizzy,
+    Excited,
+    Scared,
+    Hungry,
+    Sulking,
+}
+
+#[derive(SubStates, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[source(CubeState = CubeState::Sad)]
+enum SadPhase {
+    // turning away right after the cursor left
+    #[default]
+    Settling,
+    // randomly glancing around
+    Wandering,
+}
+
+// Eases the cube from its sad pose back to the cursor the short way around.
+#[derive(Component)]
+struct WakeUp {
+    timer: Timer,
+    from_x: f32,
+    from_y: f32,
+}
+
+// Sent whenever the cube switches states, so other systems don't have to poll `State<CubeState>`.
+#[derive(Event, Debug, Clone, Copy)]
+struct CubeStateChanged {
+    cube: Entity,
+    from: CubeState,
+    to: CubeState,
+}
+
+// State behaviors write the cube's base pose, overlays layer short reactions on top of it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum CubeSystems {
+    Behavior,
+    Overlay,
 }
 
 fn main() {
+    let pet_mode = PetMode::from_args();
+    let monitor_choice = MonitorChoice::from_args();
+    let mut settings = Settings::load();
+    if let Some(Ok(preset)) = ColorPreset::from_args() {
+        settings.colors = preset;
+    }
+    let canvas = CanvasSettings::load(&settings);
+
     App::new()
         .add_plugins(
             DefaultPlugins
                 .set(ImagePlugin::default_nearest())
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "McKenzie Bevy".into(),
-                        mode: WindowMode::Windowed,
-                        position: WindowPosition::Centered(MonitorSelection::Primary),
+                .set(asset_plugin())
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        features: wireframe_features(),
                         ..default()
-                    }),
+                    }
+                    .into(),
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    primary_window: Some(monitor_choice.window(restore_placement(
+                        pet_mode.window(canvas.size()),
+                        pet_mode,
+                        settings.window.as_ref(),
+                    ))),
+                    // closing hides to the tray, TrayPlugin decides when to actually quit
+                    close_when_requested: false,
                     ..default()
                 })
                 .build(),
         )
+        .add_plugins((
+            BehaviorPlugin,
+            CelebrationPlugin,
+            CoffeePlugin,
+            EasterEggPlugin,
+            FacePlugin,
+            HistoryPlugin,
+            HungerPlugin,
+            MoodPlugin,
+            ParticlesPlugin,
+            PausePlugin,
+            PersonalityPlugin,
+            ReactionsPlugin,
+            SessionPlugin,
+            SkinPlugin,
+            YawnPlugin,
+        ))
+        // ways of handling the cube directly
+        .add_plugins((
+            GamepadLookPlugin,
+            GrabPlugin,
+            InputStatePlugin,
+            KeybindingsPlugin,
+            ManualOverridePlugin,
+            MenuPlugin,
+            PettingPlugin,
+            RepositionPlugin,
+            SettingsMenuPlugin,
+            TypingPlugin,
+        ))
+        // the window itself
+        .add_plugins((
+            DebugWindowPlugin,
+            FramePacingPlugin,
+            FullscreenHidePlugin,
+            IdlePlugin,
+            LowPowerPlugin,
+            SettingsPlugin,
+            ShutdownPlugin,
+            SwayPlugin,
+            TimeDilationPlugin,
+            TrayPlugin,
+            WindowControlsPlugin,
+            WindowIconPlugin,
+            WindowPlacementPlugin,
+            WindowShakePlugin,
+            WireframeViewPlugin,
+        ))
+        // how the scene is lit
+        .add_plugins((
+            BackgroundPlugin,
+            GlowPlugin,
+            HeartbeatPlugin,
+            LightMoodPlugin,
+            RimPlugin,
+            ShadowsPlugin,
+            TimeOfDayPlugin,
+        ))
+        // how the canvas ends up on screen
+        .add_plugins((
+            AccessibilityPlugin,
+            AntialiasingPlugin,
+            CanvasPlugin,
+            CanvasMaterialPlugin,
+            ComparePlugin,
+            LetterboxPlugin,
+            MsaaPlugin,
+            OutlinePlugin,
+            PalettePlugin,
+            ReadbackPlugin,
+            RecordingPlugin,
+            ScreenEffectsPlugin,
+            ScreenshotPlugin,
+            ShaderReloadPlugin,
+            TonemappingPlugin,
+        ))
         .init_state::<CubeState>()
-        .insert_resource(Msaa::Off)
+        .add_sub_state::<SadPhase>()
+        .add_event::<CubeStateChanged>()
+        // only what the settings allow, see msaa.rs
+        .insert_resource(allowed_msaa(&settings).0)
+        .insert_resource(pet_mode)
+        .insert_resource(monitor_choice)
+        .insert_resource(settings)
+        .insert_resource(canvas)
+        .init_resource::<CanvasZoom>()
+        .configure_sets(
+            Update,
+            (
+                CubeSystems::Behavior
+                    .before(CubeSystems::Overlay)
+                    // a sneeze takes over whatever the cube was doing
+                    .run_if(not(any_with_component::<Sneezing>)),
+                // happy_cube_update, sad_cube_update and the rest hold still while paused
+                (CubeSystems::Behavior, CubeSystems::Overlay).run_if(in_state(AppMode::Running)),
+                // and don't run at all while nobody can see the window
+                (CubeSystems::Behavior, CubeSystems::Overlay).run_if(window_visible),
+            ),
+        )
         //systems
         .add_systems(Startup, (setup, setup_camera))
         .add_systems(
             Update,
             (
-                fit_canvas,
+                fit_canvas.run_if(window_visible),
+                zoom_canvas.after(reposition::drag_cube_home),
+            )
+                .chain(),
+        )
+        // once the projection has its new area, before the camera's moved into place
+        .add_systems(
+            PostUpdate,
+            snap_canvas
+                .after(CameraUpdateSystem)
+                .before(TransformSystem::TransformPropagate),
+        )
+        .add_systems(Update, toggle_sulk.run_if(debug_keys_enabled))
+        .add_systems(
+            Update,
+            (
+                (send_cube_state_changed, log_cube_state_changed).chain(),
+                remove_look_offset,
+            )
+                .before(CubeSystems::Behavior),
+        )
+        .add_systems(OnEnter(CubeState::Happy), enter_happy)
+        .add_systems(OnExit(CubeState::Happy), exit_happy)
+        .add_systems(OnEnter(SadPhase::Settling), enter_sad_settling)
+        .add_systems(OnExit(CubeState::Sad), exit_sad)
+        .add_systems(OnEnter(CubeState::Sleepy), enter_sleepy)
+        .add_systems(OnExit(CubeState::Sleepy), exit_sleepy)
+        .add_systems(OnEnter(CubeState::Angry), enter_angry)
+        .add_systems(OnEnter(CubeState::Dizzy), enter_dizzy)
+        .add_systems(OnEnter(CubeState::Excited), enter_excited)
+        .add_systems(OnEnter(CubeState::Scared), enter_scared)
+        .add_systems(OnEnter(CubeState::Sulking), enter_sulking)
+        .add_systems(
+            OnTransition {
+                exited: CubeState::Sad,
+                entered: CubeState::Happy,
+            },
+            start_wake_up,
+        )
+        .add_systems(OnExit(CubeState::Happy), cancel_wake_up)
+        .add_systems(
+            Update,
+            (
+                look_at_viewer
+                    .before(happy_cube_update)
+                    .run_if(in_state(CubeState::Happy)),
                 happy_cube_update.run_if(in_state(CubeState::Happy)),
+                wake_up_update
+                    .after(happy_cube_update)
+                    .run_if(in_state(CubeState::Happy)),
                 sad_cube_update.run_if(in_state(CubeState::Sad)),
-            ),
+                sad_settling_update.run_if(in_state(SadPhase::Settling)),
+                sad_wandering_update.run_if(in_state(SadPhase::Wandering)),
+                sleepy_cube_update.run_if(in_state(CubeState::Sleepy)),
+                angry_cube_update.run_if(in_state(CubeState::Angry)),
+                dizzy_cube_update.run_if(in_state(CubeState::Dizzy)),
+                excited_cube_update.run_if(in_state(CubeState::Excited)),
+                scared_cube_update.run_if(in_state(CubeState::Scared)),
+                sulking_cube_update.run_if(in_state(CubeState::Sulking)),
+                cube_click_update
+                    .run_if(in_state(CubeState::Happy))
+                    .run_if(in_state(MenuState::Closed)),
+            )
+                .in_set(CubeSystems::Behavior),
         )
         .run();
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(
+    mut commands: Commands,
+    skins: Res<SkinLibrary>,
+    settings: Res<Settings>,
+    last_session: Res<LastSession>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    // sulks for a while after being left alone for hours
+    if last_session.missed_for_long() {
+        next_state.set(CubeState::Sad);
+    }
+
     //cube
     commands
         .spawn((
             SceneBundle {
-                scene: asset_server.load("mckenzie-cube.glb#Scene0"),
-                transform: Transform::from_xyz(0.0, 0.0, -13.0),
+                scene: skins.current().scene.clone(),
+                transform: Transform::from_translation(CUBE_POSITION),
                 ..default()
             },
             Name::new("Cube"),
         ))
-        .insert(Cube::default());
+        .insert((
+            Cube::default(),
+            Blink::default(),
+            SleepParticleSpawner::default(),
+        ));
 
     //point light
-    commands.spawn(PointLightBundle {
-        point_light: PointLight {
-            intensity: 3_000_000.,
-            range: 100.,
+    commands.spawn((
+        PointLightBundle {
+            point_light: PointLight {
+                intensity: 3_000_000.,
+                range: 100.,
+                ..default()
+            },
+            transform: Transform::from_xyz(5.0, 8.0, -7.0),
             ..default()
         },
-        transform: Transform::from_xyz(5.0, 8.0, -7.0),
-        ..default()
-    });
+        MoodLight {
+            base_intensity: 3_000_000.,
+        },
+        FillLight,
+    ));
 
-    //point light
-    commands.spawn(PointLightBundle {
-        point_light: PointLight {
-            intensity: 30_000_000.,
-            range: 100.,
+    //point light, the main one and the only one casting a shadow
+    commands.spawn((
+        PointLightBundle {
+            point_light: PointLight {
+                intensity: 30_000_000.,
+                range: 100.,
+                shadows_enabled: settings.shadows.enabled(),
+                ..default()
+            },
+            transform: Transform::from_xyz(-5.0, -8.0, 7.0),
             ..default()
         },
-        transform: Transform::from_xyz(-5.0, -8.0, 7.0),
-        ..default()
-    });
+        MoodLight {
+            base_intensity: 30_000_000.,
+        },
+        KeyLight,
+    ));
 }
 
 // ! Camera setup
-fn setup_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
-    let canvas_size = Extent3d {
-        width: RES_WIDTH,
-        height: RES_HEIGHT,
-        ..default()
-    };
-
+fn setup_camera(
+    mut commands: Commands,
+    pet_mode: Res<PetMode>,
+    settings: Res<Settings>,
+    canvas: Res<CanvasSettings>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut canvas_materials: ResMut<Assets<CanvasMaterial>>,
+) {
     // this Image serves as a canvas representing the low-resolution game screen
-    let mut canvas = Image {
-        texture_descriptor: TextureDescriptor {
-            label: None,
-            size: canvas_size,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Bgra8UnormSrgb,
-            mip_level_count: 1,
-            sample_count: 1,
-            usage: TextureUsages::TEXTURE_BINDING
-                | TextureUsages::COPY_DST
-                | TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        },
-        ..default()
-    };
-
-    // fill image.data with zeroes
-    canvas.resize(canvas_size);
+    let image_handle = images.add(canvas_image(canvas.resolution));
 
-    let image_handle = images.add(canvas);
+    // this camera renders whatever is on `SCENE_LAYER` to the canvas
+    let mut camera = commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                // render before the "main pass" camera
+                target: RenderTarget::Image(image_handle.clone()),
+                clear_color: pet_mode.clear_color(),
+                // the light only goes through HDR textures on its way, it's tonemapped down to the
+                // canvas's own format before it lands there, so the upscale is untouched
+                hdr: true,
+                ..default()
+            },
+            tonemapping: camera_tonemapping(&settings),
+            ..default()
+        },
+        RenderLayers::layer(SCENE_LAYER),
+        camera_fxaa(&settings),
+    ));
+    if let Some(bloom) = camera_bloom(&settings) {
+        camera.insert(bloom);
+    }
+    if let Some(outline) = camera_outline(&settings) {
+        camera.insert(outline);
+    }
+    if let Some(ambient_occlusion) = camera_ambient_occlusion(&settings) {
+        camera.insert(ambient_occlusion);
+    }
 
-    // this camera renders whatever is on `PIXEL_PERFECT_LAYERS` to the canvas
-    commands.spawn(Camera3dBundle {
-        camera: Camera {
-            // render before the "main pass" camera
-            target: RenderTarget::Image(image_handle.clone()),
+    // spawn the canvas, a quad the size of the canvas like a sprite would be, so the camera's
+    // integer zoom still lands every canvas pixel on whole screen pixels
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: meshes.add(Rectangle::from_size(canvas.size())).into(),
+            material: canvas_materials.add(CanvasMaterial::new(image_handle.clone(), &settings)),
             ..default()
         },
-        ..default()
-    });
+        RenderLayers::layer(CANVAS_LAYER),
+    ));
+    commands.insert_resource(CanvasImage(image_handle));
 
-    // spawn the canvas
-    commands.spawn(SpriteBundle {
-        texture: image_handle,
-        ..default()
-    });
+    // the canvas and whatever sits on it, scaled up to the window by fit_canvas
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                clear_color: main_clear_color(*pet_mode),
+                ..default()
+            },
+            ..default()
+        },
+        RenderLayers::layer(CANVAS_LAYER),
+        MainCamera,
+    ));
 
-    // here, the canvas and one of the sample sprites will be rendered by this camera
-    commands.spawn(Camera2dBundle::default());
+    // after the main camera, straight over what it drew
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            ..default()
+        },
+        RenderLayers::layer(UI_LAYER),
+        UiCamera,
+    ));
 }
 
 // Scales camera projection to fit the window (integer multiples only).
 fn fit_canvas(
     mut resize_events: EventReader<WindowResized>,
-    mut projections: Query<&mut OrthographicProjection, With<Camera2d>>,
+    mut scale_factor_events: EventReader<WindowScaleFactorChanged>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut fit: CanvasFit,
 ) {
-    for event in resize_events.read() {
-        let h_scale = event.width / RES_WIDTH as f32;
-        let v_scale = event.height / RES_HEIGHT as f32;
-        let mut projection = projections.single_mut();
-        projection.scale = 1. / h_scale.min(v_scale).round();
+    let Ok((entity, window)) = windows.get_single() else {
+        return;
+    };
+    // the debug window shows the canvas as it is, only the main one is fit to
+    let resized = resize_events.read().any(|event| event.window == entity);
+    let rescaled = scale_factor_events
+        .read()
+        .any(|event| event.window == entity);
+    if !resized && !rescaled && !fit.canvas.is_changed() && !fit.settings.is_changed() {
+        return;
     }
+
+    fit.refit(
+        Vec2::new(
+            window.resolution.physical_width() as f32,
+            window.resolution.physical_height() as f32,
+        ),
+        window.scale_factor(),
+    );
+}
+
+// Everything that goes into how big the canvas shows up in the main window.
+#[derive(SystemParam)]
+struct CanvasFit<'w, 's> {
+    canvas: Res<'w, CanvasSettings>,
+    settings: Res<'w, Settings>,
+    pet_mode: Res<'w, PetMode>,
+    zoom: ResMut<'w, CanvasZoom>,
+    projections: Query<'w, 's, &'static mut OrthographicProjection, With<MainCamera>>,
+}
+
+impl CanvasFit<'_, '_> {
+    // The whole multiple is taken in physical pixels, so with desktop scaling a canvas pixel still
+    // lands on an exact block of screen pixels even when that's a fraction of a logical one. The pet
+    // window is cropped around the cube rather than fitting the whole canvas, so it keeps a fixed
+    // scale.
+    fn refit(&mut self, physical_size: Vec2, scale_factor: f32) {
+        let scale = physical_size / self.canvas.size();
+        let physical_fit = match self.settings.scaling {
+            _ if self.pet_mode.enabled => PET_CANVAS_SCALE * scale_factor,
+            CanvasScaling::Integer => scale.min_element().round().max(1.0),
+            CanvasScaling::Fit | CanvasScaling::Stretch => scale.min_element(),
+        };
+        let stretch = !self.pet_mode.enabled && self.settings.scaling == CanvasScaling::Stretch;
+
+        // the projection works in logical pixels
+        self.zoom.fit = physical_fit / scale_factor;
+        self.zoom.stretch = stretch.then(|| self.canvas.size());
+        self.zoom.apply(&mut self.projections.single_mut());
+    }
+}
+
+// Nudges the main camera by less than a screen pixel so the canvas's edges land on whole physical
+// pixels. Centered in a window an odd number of pixels across, every edge would fall halfway across
+// one and the whole upscaled image would come out soft. The canvas itself stays on whole canvas
+// pixels for the same reason.
+fn snap_canvas(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    canvas: Res<CanvasSettings>,
+    settings: Res<Settings>,
+    mut cameras: Query<(&mut Transform, &OrthographicProjection), With<MainCamera>>,
+    mut quads: Query<&mut Transform, (With<Handle<CanvasMaterial>>, Without<MainCamera>)>,
+) {
+    let (Ok(window), Ok((mut camera, projection))) =
+        (windows.get_single(), cameras.get_single_mut())
+    else {
+        return;
+    };
+
+    for mut quad in &mut quads {
+        let snapped = quad.translation.truncate().round();
+        if quad.translation.truncate() != snapped {
+            quad.translation = snapped.extend(quad.translation.z);
+        }
+    }
+
+    let offset = if settings.smooth_canvas {
+        Vec2::ZERO
+    } else {
+        // physical pixels per canvas pixel, along each axis for stretched scaling
+        let physical_size = Vec2::new(
+            window.resolution.physical_width() as f32,
+            window.resolution.physical_height() as f32,
+        );
+        let area = projected_area(projection, Vec2::new(window.width(), window.height()));
+        let per_canvas_pixel = physical_size / area.size().max(Vec2::ONE);
+        // how far the canvas's top left corner falls past the last whole pixel with the camera
+        // centered, world y runs up where the window's runs down
+        let corner = (physical_size / 2.0 - canvas.size() / 2.0 * per_canvas_pixel).fract_gl();
+        Vec2::new(corner.x, -corner.y) / per_canvas_pixel
+    };
+    if camera.translation.truncate() != offset {
+        camera.translation = offset.extend(camera.translation.z);
+    }
+}
+
+// Wheel steps the canvas pixels up or down a whole multiple at a time, middle click goes back to the fit.
+fn zoom_canvas(
+    mut wheel_events: EventReader<MouseWheel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    drag: Res<MiddleDrag>,
+    mut zoom: ResMut<CanvasZoom>,
+    mut projections: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    // with Ctrl held the wheel changes skins instead
+    let changing_skin = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    for event in wheel_events.read().filter(|_| !changing_skin) {
+        zoom.scrolled += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / CANVAS_ZOOM_PIXELS_PER_STEP,
+        };
+    }
+
+    let steps = zoom.scrolled.trunc();
+    zoom.scrolled -= steps;
+    let zoom_level = (zoom.zoom as f32 + steps).clamp(1.0, CANVAS_MAX_ZOOM as f32) as u32;
+
+    // a middle drag moves the cube instead
+    if mouse.just_released(MouseButton::Middle) && drag.moved < CURSOR_MOVE_THRESHOLD {
+        zoom.zoom = 1;
+        zoom.scrolled = 0.0;
+    } else if zoom_level != zoom.zoom {
+        zoom.zoom = zoom_level;
+    } else {
+        return;
+    }
+
+    zoom.apply(&mut projections.single_mut());
+}
+
+fn send_cube_state_changed(
+    mut transitions: EventReader<StateTransitionEvent<CubeState>>,
+    query_cube: Query<Entity, With<Cube>>,
+    mut state_changed: EventWriter<CubeStateChanged>,
+) {
+    for transition in transitions.read() {
+        let (Some(from), Some(to)) = (transition.exited, transition.entered) else {
+            continue;
+        };
+        if from == to {
+            continue;
+        }
+
+        for cube in &query_cube {
+            state_changed.send(CubeStateChanged { cube, from, to });
+        }
+    }
+}
+
+fn log_cube_state_changed(mut state_changed: EventReader<CubeStateChanged>) {
+    for event in state_changed.read() {
+        debug!("{:?}: {:?} -> {:?}", event.cube, event.from, event.to);
+    }
+}
+
+// Takes last frame's overlays back off, so behaviors only ever see their own pose.
+fn remove_look_offset(mut query_cube: Query<(&mut Transform, &mut Cube)>) {
+    for (mut cube_transform, mut cube_prop) in &mut query_cube {
+        if cube_prop.look_offset == Quat::IDENTITY {
+            continue;
+        }
+
+        cube_transform.rotation *= cube_prop.look_offset.inverse();
+        cube_prop.look_offset = Quat::IDENTITY;
+    }
+}
+
+// What the projection covers in a window this size. Its own area is only worked out again by
+// CameraUpdateSystem in PostUpdate, a frame behind a change to the scaling or the canvas.
+fn projected_area(projection: &OrthographicProjection, window_size: Vec2) -> Rect {
+    let mut projection = projection.clone();
+    projection.update(window_size.x, window_size.y);
+    projection.area
+}
+
+// Along each axis, since stretched scaling squashes one more than the other.
+fn canvas_per_window_pixel(window_size: Vec2, projection: &OrthographicProjection) -> Vec2 {
+    projected_area(projection, window_size).size() / window_size.max(Vec2::ONE)
+}
+
+// Converts a window cursor position into pixel coordinates on the low-res canvas.
+fn window_to_canvas(
+    window_size: Vec2,
+    position: Vec2,
+    projection: &OrthographicProjection,
+    canvas_size: Vec2,
+) -> Vec2 {
+    let window_center = window_size / 2.;
+    let canvas_center = canvas_size / 2.;
+
+    (position - window_center) * canvas_per_window_pixel(window_size, projection) + canvas_center
+}
+
+// The other way round, from canvas pixels back to where they show up in the window.
+fn canvas_to_window(
+    window_size: Vec2,
+    position: Vec2,
+    projection: &OrthographicProjection,
+    canvas_size: Vec2,
+) -> Vec2 {
+    let window_center = window_size / 2.;
+    let canvas_center = canvas_size / 2.;
+
+    (position - canvas_center) / canvas_per_window_pixel(window_size, projection) + window_center
+}
+
+// Turns a canvas pixel (top-left origin, y down) into the 2D world the canvas sprite sits in.
+fn canvas_to_world(position: Vec2, canvas_size: Vec2) -> Vec2 {
+    Vec2::new(
+        position.x - canvas_size.x / 2.0,
+        canvas_size.y / 2.0 - position.y,
+    )
+}
+
+fn world_to_canvas(position: Vec2, canvas_size: Vec2) -> Vec2 {
+    Vec2::new(
+        position.x + canvas_size.x / 2.0,
+        canvas_size.y / 2.0 - position.y,
+    )
+}
+
+// Projects the cube through the 3D camera, returning its center and radius in canvas pixels.
+fn cube_on_canvas(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    cube_transform: &GlobalTransform,
+) -> Option<(Vec2, f32)> {
+    let center = cube_transform.translation();
+    let edge = center + camera_transform.right() * CUBE_HIT_RADIUS;
+
+    let center = camera.world_to_viewport(camera_transform, center)?;
+    let edge = camera.world_to_viewport(camera_transform, edge)?;
+
+    Some((center, center.distance(edge)))
+}
+
+// The cursor as seen on the low-res canvas, and where the cube sits on it.
+#[derive(SystemParam)]
+struct CanvasPointer<'w, 's> {
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<Camera3d>>,
+    projections: Query<'w, 's, &'static OrthographicProjection, With<MainCamera>>,
+    input: Res<'w, InputState>,
+    canvas: Res<'w, CanvasSettings>,
+}
+
+impl CanvasPointer<'_, '_> {
+    fn cursor(&self) -> Option<Vec2> {
+        self.input.canvas_pointer
+    }
+
+    fn cube(&self, cube_transform: &GlobalTransform) -> Option<(Vec2, f32)> {
+        let (camera, camera_transform) = self.cameras.single();
+
+        cube_on_canvas(camera, camera_transform, cube_transform)
+    }
+
+    fn over_cube(&self, cube_transform: &GlobalTransform) -> bool {
+        let Some(cursor) = self.cursor() else {
+            return false;
+        };
+
+        self.cube(cube_transform)
+            .is_some_and(|(center, radius)| cursor.distance(center) <= radius)
+    }
+}
+
+// Whether the cursor is moving fast enough, and straight enough, at the window center.
+fn rushing_at_center(position: Vec2, velocity: Vec2, speed: f32) -> bool {
+    let to_center = -position.normalize_or_zero();
+
+    velocity.length() > speed && velocity.normalize_or_zero().dot(to_center) > 0.8
+}
+
+// Pitch and yaw, in radians, that point the cube at a window cursor position. A cursor far outside
+// the window (see the global-cursor feature) still can't turn it further than MAX_LOOK_ANGLE.
+fn cursor_look_angles(window_size: Vec2, position: Vec2, look_divisor: f32) -> (f32, f32) {
+    let mousepos_x = position.x - window_size.x / 2.;
+    let mousepos_y = position.y - window_size.y / 2.;
+
+    (
+        (mousepos_y / look_divisor)
+            .to_radians()
+            .clamp(-MAX_LOOK_ANGLE, MAX_LOOK_ANGLE),
+        (mousepos_x / look_divisor)
+            .to_radians()
+            .clamp(-MAX_LOOK_ANGLE, MAX_LOOK_ANGLE),
+    )
+}
+
+// Signed difference between two angles, wrapped into [-PI, PI].
+fn shortest_angle(from: f32, to: f32) -> f32 {
+    (to - from + PI).rem_euclid(2. * PI) - PI
+}
+
+// Everything besides the cursor that sways where the happy cube looks.
+#[derive(SystemParam)]
+struct LookInfluences<'w> {
+    mood: Res<'w, Mood>,
+    personality: Res<'w, Personality>,
+    typing: Res<'w, TypingActivity>,
+    settings: Res<'w, Settings>,
 }
 
 //MARK: Main Code
 fn happy_cube_update(
     time: Res<Time>,
-    windows: Query<&Window>,
+    // the cursor sitting still is the user's time, not the cube's
+    real: Res<Time<Real>>,
+    influences: LookInfluences,
+    pointer: CanvasPointer,
+    look_input: Res<LookInput>,
+    mut query_cube: Query<(
+        &mut Transform,
+        &GlobalTransform,
+        &mut Cube,
+        Option<&Caffeinated>,
+    )>,
+    query_grabbed: Query<(), (With<Cube>, With<Grabbed>)>,
+) {
+    let window_size = pointer.input.window_size;
+    let mouse_pos = look_input.position(&pointer.input);
+    let (mut cube_transform, cube_global, mut cube_prop, caffeinated) = query_cube.single_mut();
+    // coffee makes it track snappier and follow the cursor further
+    let caffeine = Caffeinated::strength(caffeinated);
+
+    if cube_prop.playing_clip {
+        return;
+    }
+
+    let (mut cube_rot_y, mut cube_rot_x, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
+
+    // settle back down from hops, bobs and shrinking
+    cube_transform.translation = cube_transform
+        .translation
+        .lerp(cube_prop.home, (8.0 * time.delta_seconds()).min(1.0));
+    cube_transform.scale = cube_transform
+        .scale
+        .lerp(Vec3::ONE, (8.0 * time.delta_seconds()).min(1.0));
+
+    let Some(position) = mouse_pos else {
+        // the transition table notices the cursor is gone
+        return;
+    };
+
+    if look_input.switched {
+        // eases over to whichever of the stick and the mouse just took over
+        cube_prop.rotate_timer = Timer::from_seconds(0.5.lerp(0.15, caffeine), TimerMode::Once);
+    }
+
+    let mousepos_x = position.x - window_size.x / 2.;
+    let mousepos_y = position.y - window_size.y / 2.;
+    // pressing harder with a pen makes it follow more keenly
+    let look_divisor = influences.mood.look_divisor().lerp(12.0, caffeine)
+        / (influences.personality.look_sensitivity * pointer.input.pressure);
+    let cursor = window_to_canvas(
+        window_size,
+        position,
+        pointer.projections.single(),
+        pointer.canvas.size(),
+    );
+    let target = Vec2::from(cursor_look_angles(window_size, position, look_divisor));
+    let smoothing_secs = influences
+        .settings
+        .look_smoothing_secs
+        .unwrap_or(LOOK_SMOOTHING_SECS)
+        .max(0.001);
+    let look = cube_prop.smooth_look(cursor, target, smoothing_secs, time.delta_seconds());
+
+    let requested = cube_prop
+        .look_requests
+        .drain(..)
+        .max_by_key(|(priority, _)| *priority);
+    let requested_by = requested.map(|(priority, _)| priority);
+    if requested_by != cube_prop.active_look {
+        // snaps to attention quickly, drifts back to the cursor at the usual pace
+        let secs = match requested_by {
+            Some(_) => 0.2,
+            None => 0.5.lerp(0.15, caffeine),
+        };
+        cube_prop.rotate_timer = Timer::from_seconds(secs, TimerMode::Once);
+        cube_prop.active_look = requested_by;
+    }
+    let (look_x, look_y) = match requested {
+        Some((_, requested)) => (requested.x, requested.y),
+        // reads along with the typing, gaze lowered towards the keyboard
+        None => (look.x + TYPING_DIP * influences.typing.dip(), look.y),
+    };
+
+    if position.distance(cube_prop.last_cursor_pos) > CURSOR_MOVE_THRESHOLD {
+        cube_prop.last_cursor_pos = position;
+        cube_prop.idle.reset();
+
+        if cube_prop.bored {
+            cube_prop.bored = false;
+            cube_prop.rotate_timer = Timer::from_seconds(0.5.lerp(0.15, caffeine), TimerMode::Once);
+        }
+    } else if cube_prop.idle.tick(real.delta()).elapsed_secs() > BORED_IDLE_SECS {
+        cube_prop.bored = true;
+    }
+
+    if cube_prop.bored && requested.is_none() {
+        // glancing around near the cursor, slower and smaller than the sad wander
+        if !cube_prop.rotate_timer.finished() {
+            cube_prop.rotate_timer.tick(time.delta());
+
+            let t = cube_prop.rotate_timer.elapsed_secs()
+                / cube_prop.rotate_timer.duration().as_secs_f32();
+
+            cube_rot_x = cube_rot_x.lerp(cube_prop.random_look_x, t);
+            cube_rot_y = cube_rot_y.lerp(cube_prop.random_look_y, t);
+        } else {
+            let mut rng = rand::thread_rng();
+            let fidget = influences.personality.fidget;
+            cube_prop.random_look_y = look_y + rng.gen_range(-0.4..0.4) * fidget;
+            cube_prop.random_look_x = look_x + rng.gen_range(-0.15..0.15) * fidget;
+            cube_prop.rotate_timer = Timer::from_seconds(rng.gen_range(1.5..4.0), TimerMode::Once);
+        }
+    } else if !cube_prop.rotate_timer.finished() {
+        cube_prop.rotate_timer.tick(time.delta());
+
+        let t =
+            cube_prop.rotate_timer.elapsed_secs() / cube_prop.rotate_timer.duration().as_secs_f32();
+
+        cube_rot_x = cube_rot_x.lerp(look_x, t);
+        cube_rot_y = cube_rot_y.lerp(look_y, t);
+    } else {
+        cube_rot_x = look_x;
+        cube_rot_y = look_y;
+    }
+
+    // curiously tilts its head when the cursor lingers right next to it
+    let hovering = pointer
+        .cube(cube_global)
+        .is_some_and(|(center, radius)| cursor.distance(center) < radius * 1.5);
+
+    if hovering {
+        cube_prop.hover_timer.tick(time.delta());
+    } else {
+        cube_prop.hover_timer.reset();
+    }
+
+    let tilt_target = if cube_prop.hover_timer.finished() {
+        -mousepos_x.signum() * 12_f32.to_radians()
+    } else {
+        0.0
+    };
+    cube_prop.tilt = cube_prop
+        .tilt
+        .lerp(tilt_target, 1.0 - (-6.0 * time.delta_seconds()).exp());
+
+    // drag_rotate turns it while it's held
+    if query_grabbed.is_empty() {
+        cube_transform.rotation =
+            Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, cube_prop.tilt);
+    }
+
+    let now = time.elapsed_seconds();
+    let mousepos = Vec2::new(mousepos_x, mousepos_y);
+
+    cube_prop.cursor_velocity = Vec2::ZERO;
+
+    // flicking the stick isn't a fast or swirling cursor
+    if look_input.stick_active() {
+        cube_prop.prev_cursor_pos = None;
+        cube_prop.swirl.clear();
+        return;
+    }
+
+    match cube_prop.prev_cursor_pos {
+        Some(prev) if prev != mousepos => {
+            cube_prop.last_cursor_move = now;
+            cube_prop.cursor_velocity = pointer.input.pointer_velocity;
+            // too close to the center for a meaningful angle
+            if prev.length() > 10.0 && mousepos.length() > 10.0 {
+                cube_prop.swirl.push((now, prev.angle_between(mousepos)));
+            }
+        }
+        // cursor stopped, slow circles shouldn't add up
+        _ if now - cube_prop.last_cursor_move > 0.15 => cube_prop.swirl.clear(),
+        _ => {}
+    }
+    cube_prop.prev_cursor_pos = Some(mousepos);
+    cube_prop
+        .swirl
+        .retain(|(sample_time, _)| now - sample_time <= CURSOR_HISTORY_SECS);
+}
+
+// Holding the key has it look straight out of the screen, chin up a little towards the viewer.
+fn look_at_viewer(input: Res<InputState>, mut query_cube: Query<&mut Cube>) {
+    if !input.holding(Action::LookAtViewer) {
+        return;
+    }
+
+    query_cube
+        .single_mut()
+        .request_look(LookPriority::Manual, Vec2::new(-0.12, 0.0));
+}
+
+fn enter_happy(
+    mut transitions: EventReader<StateTransitionEvent<CubeState>>,
+    input: Res<InputState>,
+    mut query_cube: Query<(&mut Cube, Option<&Caffeinated>)>,
+) {
+    let from = transitions
+        .read()
+        .last()
+        .and_then(|transition| transition.exited);
+    // the initial transition can run before the cube is spawned
+    let Ok((mut cube_prop, caffeinated)) = query_cube.get_single_mut() else {
+        return;
+    };
+
+    cube_prop.rotate_timer = match from {
+        // cautiously turns back to the cursor
+        Some(CubeState::Scared) => Timer::from_seconds(1.0, TimerMode::Once),
+        _ => Timer::from_seconds(
+            0.5.lerp(0.15, Caffeinated::strength(caffeinated)),
+            TimerMode::Once,
+        ),
+    };
+    if from == Some(CubeState::Sad) {
+        // the wake up animation does the easing, happy_cube_update can track right away
+        let duration = cube_prop.rotate_timer.duration();
+        cube_prop.rotate_timer.tick(duration);
+    }
+
+    cube_prop.idle.reset();
+    cube_prop.swirl.clear();
+    // no smoothing left over from whatever the cube was doing
+    cube_prop.look_filter = None;
+    cube_prop.look_requests.clear();
+    cube_prop.active_look = None;
+    if let Some(position) = input.pointer {
+        cube_prop.last_cursor_pos = position;
+    }
+}
+
+fn exit_happy(mut query_cube: Query<&mut Cube>) {
+    let mut cube_prop = query_cube.single_mut();
+
+    cube_prop.bored = false;
+    cube_prop.tilt = 0.0;
+    cube_prop.hover_timer.reset();
+    cube_prop.prev_cursor_pos = None;
+}
+
+fn start_wake_up(mut commands: Commands, query_cube: Query<(Entity, &Transform, &Cube)>) {
+    let (entity, cube_transform, cube_prop) = query_cube.single();
+    let (cube_rot_y, cube_rot_x, _) = cube_prop
+        .base_rotation(cube_transform)
+        .to_euler(EulerRot::YXZ);
+
+    commands.entity(entity).insert(WakeUp {
+        timer: Timer::from_seconds(0.6, TimerMode::Once),
+        from_x: cube_rot_x,
+        from_y: cube_rot_y,
+    });
+}
+
+fn cancel_wake_up(mut commands: Commands, query_cube: Query<Entity, With<WakeUp>>) {
+    for entity in &query_cube {
+        commands.entity(entity).remove::<WakeUp>();
+    }
+}
+
+fn wake_up_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mood: Res<Mood>,
+    personality: Res<Personality>,
+    input: Res<InputState>,
+    mut query_cube: Query<(
+        Entity,
+        &mut Transform,
+        &Cube,
+        &mut WakeUp,
+        Option<&Caffeinated>,
+    )>,
+) {
+    let Ok((entity, mut cube_transform, cube_prop, mut wake_up, caffeinated)) =
+        query_cube.get_single_mut()
+    else {
+        return;
+    };
+    let Some(position) = input.pointer else {
+        commands.entity(entity).remove::<WakeUp>();
+        return;
+    };
+
+    let look_divisor = mood
+        .look_divisor()
+        .lerp(12.0, Caffeinated::strength(caffeinated))
+        / (personality.look_sensitivity * input.pressure);
+    let (target_x, target_y) = cursor_look_angles(input.window_size, position, look_divisor);
+
+    wake_up.timer.tick(time.delta());
+
+    let t = wake_up.timer.elapsed_secs() / wake_up.timer.duration().as_secs_f32();
+    let ease = 1.0 - (1.0 - t).powi(3);
+
+    let cube_rot_x = wake_up.from_x.lerp(target_x, ease);
+    let cube_rot_y = wake_up.from_y + shortest_angle(wake_up.from_y, target_y) * ease;
+
+    cube_transform.rotation =
+        Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, cube_prop.tilt);
+
+    if wake_up.timer.finished() {
+        commands.entity(entity).remove::<WakeUp>();
+    }
+}
+
+fn sad_cube_update(time: Res<Time>, mut query_cube: Query<(&mut Transform, &mut Cube)>) {
+    let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
+
+    if cube_prop.playing_clip {
+        return;
+    }
+
+    let (mut cube_rot_y, mut cube_rot_x, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
+
+    if cube_prop.rotate_timer.finished() {
+        if let Some((look_x, look_y)) = cube_prop.search_queue.pop_front() {
+            cube_prop.random_look_x = look_x;
+            cube_prop.random_look_y = look_y;
+            cube_prop.rotate_timer = Timer::from_seconds(0.7, TimerMode::Once);
+        }
+    }
+
+    if !cube_prop.rotate_timer.finished() {
+        cube_prop.rotate_timer.tick(time.delta());
+
+        let t =
+            cube_prop.rotate_timer.elapsed_secs() / cube_prop.rotate_timer.duration().as_secs_f32();
+
+        cube_rot_x = cube_rot_x.lerp(cube_prop.random_look_x, t);
+        cube_rot_y += shortest_angle(cube_rot_y, cube_prop.random_look_y) * t;
+    }
+
+    cube_transform.rotation = Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, 0.0);
+}
+
+// Searches the left edge, the right edge and below for the cursor, then turns away from where
+// it was when it left.
+fn enter_sad_settling(mut query_cube: Query<(&Transform, &mut Cube)>) {
+    let (cube_transform, mut cube_prop) = query_cube.single_mut();
+    let (cube_rot_y, cube_rot_x, _) = cube_prop
+        .base_rotation(cube_transform)
+        .to_euler(EulerRot::YXZ);
+
+    cube_prop.search_queue = VecDeque::from([
+        (0.0, -0.6),
+        (0.0, 0.6),
+        (0.45, 0.0),
+        (-cube_rot_x, PI + cube_rot_y),
+    ]);
+    // start on the first sweep right away
+    let duration = cube_prop.rotate_timer.duration();
+    cube_prop.rotate_timer.tick(duration);
+    cube_prop.wanders_since_glance = 0;
+}
+
+fn exit_sad(mut query_cube: Query<&mut Cube>) {
+    query_cube.single_mut().search_queue.clear();
+}
+
+fn sad_settling_update(query_cube: Query<&Cube>, mut next_phase: ResMut<NextState<SadPhase>>) {
+    let cube_prop = query_cube.single();
+
+    if cube_prop.search_queue.is_empty() && cube_prop.rotate_timer.finished() {
+        next_phase.set(SadPhase::Wandering);
+    }
+}
+
+fn sad_wandering_update(
+    mood: Res<Mood>,
+    personality: Res<Personality>,
+    mut query_cube: Query<(&mut Cube, Option<&Caffeinated>)>,
+) {
+    let mut rng = rand::thread_rng();
+    let (mut cube_prop, caffeinated) = query_cube.single_mut();
+
+    if cube_prop.rotate_timer.finished() {
+        // the gloomier it gets, the slower and more downcast the glances
+        let gloom = 1.0 - mood.0;
+        // ...and coffee makes it fidget between them
+        let pace = (1.0 + gloom) * 1.0.lerp(0.4, Caffeinated::strength(caffeinated));
+
+        // every third or fourth look, it checks back where its person used to be
+        let glance_back = cube_prop.wanders_since_glance >= 4
+            || (cube_prop.wanders_since_glance == 3 && rng.gen_bool(0.5));
+
+        if glance_back {
+            cube_prop.wanders_since_glance = 0;
+            cube_prop.random_look_y = 0.0;
+            cube_prop.random_look_x = gloom * 0.25;
+            // held a little longer than any wander so it reads as on purpose
+            cube_prop.rotate_timer = Timer::from_seconds(2.8 * pace, TimerMode::Once);
+            return;
+        }
+
+        cube_prop.wanders_since_glance += 1;
+        cube_prop.random_look_y = rng.gen_range(personality.wander_yaw.clone());
+        cube_prop.random_look_x = rng.gen_range(-0.3..0.3) + gloom * 0.25;
+        cube_prop.rotate_timer =
+            Timer::from_seconds(rng.gen_range(0.3..2.5) * pace, TimerMode::Once);
+    }
+}
+
+fn enter_sleepy(mut query_cube: Query<&mut Cube>) {
+    // nod off slowly
+    query_cube.single_mut().rotate_timer = Timer::from_seconds(3.0, TimerMode::Once);
+}
+
+fn exit_sleepy(mut query_cube: Query<(&mut Transform, &Cube)>) {
+    let (mut cube_transform, cube_prop) = query_cube.single_mut();
+    cube_transform.translation.y = cube_prop.home.y;
+}
+
+fn sleepy_cube_update(time: Res<Time>, mut query_cube: Query<(&mut Transform, &mut Cube)>) {
+    let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
+    let (cube_rot_y, mut cube_rot_x, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
+
+    cube_prop.rotate_timer.tick(time.delta());
+
+    let t = cube_prop.rotate_timer.elapsed_secs() / cube_prop.rotate_timer.duration().as_secs_f32();
+
+    // head droops down and gently bobs while breathing
+    cube_rot_x = cube_rot_x.lerp(-0.5, t);
+    cube_transform.translation.y =
+        cube_prop.home.y + (time.elapsed_seconds() * 1.5).sin() * 0.08 * t;
+
+    cube_transform.rotation = Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, 0.0);
+}
+
+// Keeps glaring wherever it was looking when it got fed up.
+fn enter_angry(mut query_cube: Query<(&Transform, &mut Cube)>) {
+    let (cube_transform, mut cube_prop) = query_cube.single_mut();
+    let (cube_rot_y, cube_rot_x, _) = cube_prop
+        .base_rotation(cube_transform)
+        .to_euler(EulerRot::YXZ);
+
+    cube_prop.recent_clicks.clear();
+    cube_prop.state_timer = Timer::from_seconds(ANGRY_SECS, TimerMode::Once);
+    cube_prop.random_look_y = cube_rot_y;
+    cube_prop.random_look_x = cube_rot_x;
+}
+
+fn angry_cube_update(
+    time: Res<Time>,
     mut query_cube: Query<(&mut Transform, &mut Cube)>,
+    manual_override: Option<Res<ManualOverride>>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    let mut rng = rand::thread_rng();
+    let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
+
+    if cube_prop.state_timer.tick(time.delta()).finished() && manual_override.is_none() {
+        next_state.set(CubeState::Sulking);
+    }
+
+    // stubbornly keeps looking where it was and shakes in place
+    cube_transform.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        cube_prop.random_look_y,
+        cube_prop.random_look_x,
+        rng.gen_range(-0.08..0.08),
+    );
+}
+
+fn enter_sulking(mut query_cube: Query<&mut Cube>) {
+    let mut cube_prop = query_cube.single_mut();
+
+    cube_prop.state_timer = Timer::from_seconds(SULK_SECS, TimerMode::Once);
+    cube_prop.rotate_timer = Timer::from_seconds(0.5, TimerMode::Once);
+    cube_prop.idle.reset();
+}
+
+// Pointedly looks away from wherever the cursor is, and forgives you if you leave it alone.
+fn sulking_cube_update(
+    time: Res<Time>,
+    real: Res<Time<Real>>,
+    mood: Res<Mood>,
+    input: Res<InputState>,
+    mut query_cube: Query<(&mut Transform, &mut Cube)>,
+    manual_override: Option<Res<ManualOverride>>,
     mut next_state: ResMut<NextState<CubeState>>,
 ) {
-    let mouse_pos = windows.single().cursor_position();
     let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
     let (mut cube_rot_y, mut cube_rot_x, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
 
-    match mouse_pos {
-        Some(position) => {
-            let mousepos_x = position.x - windows.single().resolution.width() / 2.;
-            let mousepos_y = position.y - windows.single().resolution.height() / 2.;
+    cube_prop.state_timer.tick(time.delta());
 
-            if !cube_prop.rotate_timer.finished() {
-                cube_prop.rotate_timer.tick(time.delta());
+    if let Some(position) = input.pointer {
+        if position != cube_prop.last_cursor_pos {
+            cube_prop.last_cursor_pos = position;
+            cube_prop.idle.reset();
+        }
 
-                let t = cube_prop.rotate_timer.elapsed_secs()
-                    / cube_prop.rotate_timer.duration().as_secs_f32();
+        let (look_x, look_y) = cursor_look_angles(input.window_size, position, mood.look_divisor());
+        let away_x = (-look_x).clamp(-SULK_MAX_ANGLE, SULK_MAX_ANGLE);
+        let away_y = (-look_y).clamp(-SULK_MAX_ANGLE, SULK_MAX_ANGLE);
 
-                cube_rot_x = cube_rot_x.lerp((mousepos_y / 20.0).to_radians(), t);
-                cube_rot_y = cube_rot_y.lerp((mousepos_x / 20.0).to_radians(), t);
-            } else {
-                cube_rot_x = (mousepos_y / 20.0).to_radians();
-                cube_rot_y = (mousepos_x / 20.0).to_radians();
-            }
+        if !cube_prop.rotate_timer.finished() {
+            cube_prop.rotate_timer.tick(time.delta());
+
+            let t = cube_prop.rotate_timer.elapsed_secs()
+                / cube_prop.rotate_timer.duration().as_secs_f32();
 
-            cube_transform.rotation = Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, 0.0);
+            cube_rot_x = cube_rot_x.lerp(away_x, t);
+            cube_rot_y = cube_rot_y.lerp(away_y, t);
+        } else {
+            cube_rot_x = away_x;
+            cube_rot_y = away_y;
         }
+
+        cube_transform.rotation = Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, 0.0);
+    }
+
+    let forgiven = cube_prop.idle.tick(real.delta()).elapsed_secs() >= SULK_FORGIVE_SECS;
+    if (forgiven || cube_prop.state_timer.finished()) && manual_override.is_none() {
+        next_state.set(CubeState::Happy);
+    }
+}
+
+fn debug_keys_enabled() -> bool {
+    cfg!(debug_assertions)
+}
+
+// Debug builds can start or stop a sulk with S, without getting the cube angry first.
+fn toggle_sulk(
+    input: Res<InputState>,
+    state: Res<State<CubeState>>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    if !input.just_did(Action::ToggleSulk) {
+        return;
+    }
+
+    next_state.set(match state.get() {
+        CubeState::Sulking => CubeState::Happy,
+        _ => CubeState::Sulking,
+    });
+}
+
+fn cube_click_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    pointer: CanvasPointer,
+    mut query_cube: Query<(Entity, &GlobalTransform, &mut Cube, Option<&mut Gesture>)>,
+) {
+    let (entity, cube_global, mut cube_prop, current_gesture) = query_cube.single_mut();
+
+    let gesture = if pointer.input.pressed_on(PressTarget::Cube) {
+        GestureKind::Nod
+    } else if mouse.just_pressed(MouseButton::Right) && pointer.over_cube(cube_global) {
+        GestureKind::Shake
+    } else {
+        return;
+    };
+
+    match current_gesture {
+        Some(mut current_gesture) => current_gesture.queue(gesture),
         None => {
-            next_state.set(CubeState::Sad);
-            cube_prop.rotate_timer.reset();
-            cube_prop.random_look_y = PI + cube_rot_y;
-            cube_prop.random_look_x = -cube_rot_x;
+            commands.entity(entity).insert(Gesture::new(gesture));
         }
     }
+
+    if gesture != GestureKind::Nod {
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    cube_prop
+        .recent_clicks
+        .retain(|click| now - click <= CURSOR_HISTORY_SECS);
+    cube_prop.recent_clicks.push(now);
 }
 
-fn sad_cube_update(
+fn enter_dizzy(mut query_cube: Query<&mut Cube>) {
+    let mut cube_prop = query_cube.single_mut();
+    let swept: f32 = cube_prop.swirl.iter().map(|(_, angle)| angle).sum();
+
+    cube_prop.state_timer = Timer::from_seconds(DIZZY_SECS, TimerMode::Once);
+    // keeps spinning the way the cursor swirled
+    cube_prop.spin_speed = 4.0 * PI * swept.signum();
+}
+
+fn dizzy_cube_update(
     time: Res<Time>,
-    windows: Query<&Window>,
     mut query_cube: Query<(&mut Transform, &mut Cube)>,
+    manual_override: Option<Res<ManualOverride>>,
     mut next_state: ResMut<NextState<CubeState>>,
 ) {
-    let mouse_pos = windows.single().cursor_position();
-    let mut rng = rand::thread_rng();
+    let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
+    let (mut cube_rot_y, _, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
+
+    cube_prop.state_timer.tick(time.delta());
+
+    // keeps spinning the way the cursor swirled, slowing down until it settles
+    cube_prop.spin_speed *= (-2.0 * time.delta_seconds()).exp();
+    cube_rot_y += cube_prop.spin_speed * time.delta_seconds();
+
+    let remaining =
+        1.0 - cube_prop.state_timer.elapsed_secs() / cube_prop.state_timer.duration().as_secs_f32();
+    let cube_rot_x = (time.elapsed_seconds() * 8.0).sin() * 0.35 * remaining;
+
+    cube_transform.rotation = Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, 0.0);
+
+    if cube_prop.state_timer.finished() && manual_override.is_none() {
+        next_state.set(CubeState::Happy);
+    }
+}
+
+fn enter_excited(mut query_cube: Query<&mut Cube>) {
+    let mut cube_prop = query_cube.single_mut();
+
+    cube_prop.state_timer = Timer::from_seconds(EXCITED_SECS, TimerMode::Once);
+    cube_prop.rotate_timer = Timer::from_seconds(0.2, TimerMode::Once);
+}
+
+fn excited_cube_update(
+    time: Res<Time>,
+    mood: Res<Mood>,
+    input: Res<InputState>,
+    mut query_cube: Query<(&mut Transform, &mut Cube)>,
+    manual_override: Option<Res<ManualOverride>>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    let mouse_pos = input.pointer;
     let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
     let (mut cube_rot_y, mut cube_rot_x, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
 
-    match mouse_pos {
-        None => {
-            if !cube_prop.rotate_timer.finished() {
-                cube_prop.rotate_timer.tick(time.delta());
-
-                let t = cube_prop.rotate_timer.elapsed_secs()
-                    / cube_prop.rotate_timer.duration().as_secs_f32();
-
-                if cube_rot_y < 0. {
-                    cube_rot_y += 2. * PI;
-                }
-
-                cube_rot_x = cube_rot_x.lerp(cube_prop.random_look_x, t);
-                cube_rot_y = cube_rot_y.lerp(cube_prop.random_look_y, t);
-            } else {
-                cube_prop.random_look_y = rng.gen_range(2.6..3.6);
-                cube_prop.random_look_x = rng.gen_range(-0.3..0.3);
-                cube_prop.rotate_timer =
-                    Timer::from_seconds(rng.gen_range(0.3..2.5), TimerMode::Once);
-            }
+    cube_prop.state_timer.tick(time.delta());
+
+    let elapsed = cube_prop.state_timer.elapsed_secs();
+    let remaining = 1.0 - elapsed / cube_prop.state_timer.duration().as_secs_f32();
+
+    // bouncy hops that die down, always measured from the resting spot so they never stack
+    let hop = (elapsed * PI / 0.5).sin().abs() * 0.6 * remaining;
+    cube_transform.translation = cube_prop.home + Vec3::Y * hop;
+
+    if let Some(position) = mouse_pos {
+        let mousepos = position - input.window_size / 2.;
+
+        // looks at the cursor a bit too eagerly
+        let (look_x, look_y) =
+            cursor_look_angles(input.window_size, position, mood.look_divisor() / 1.5);
 
-            cube_transform.rotation = Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, 0.0);
+        if !cube_prop.rotate_timer.finished() {
+            cube_prop.rotate_timer.tick(time.delta());
+
+            let t = cube_prop.rotate_timer.elapsed_secs()
+                / cube_prop.rotate_timer.duration().as_secs_f32();
+
+            cube_rot_x = cube_rot_x.lerp(look_x, t);
+            cube_rot_y = cube_rot_y.lerp(look_y, t);
+        } else {
+            cube_rot_x = look_x;
+            cube_rot_y = look_y;
+        }
+
+        cube_transform.rotation = Quat::from_euler(EulerRot::YXZ, cube_rot_y, cube_rot_x, 0.0);
+
+        if let Some(prev) = cube_prop.prev_cursor_pos {
+            let velocity = (mousepos - prev) / time.delta_seconds().max(f32::EPSILON);
+            if prev != mousepos && rushing_at_center(mousepos, velocity, EXCITED_SPEED) {
+                cube_prop.state_timer.reset();
+            }
         }
-        Some(_) => {
-            cube_prop.rotate_timer.reset();
-            next_state.set(CubeState::Happy);
+        cube_prop.prev_cursor_pos = Some(mousepos);
+    }
+
+    if cube_prop.state_timer.finished() && manual_override.is_none() {
+        next_state.set(CubeState::Happy);
+    }
+}
+
+// Flinches away from wherever the cursor is.
+fn enter_scared(mood: Res<Mood>, input: Res<InputState>, mut query_cube: Query<&mut Cube>) {
+    let mut cube_prop = query_cube.single_mut();
+
+    cube_prop.state_timer = Timer::from_seconds(SCARED_SECS, TimerMode::Once);
+    cube_prop.rotate_timer = Timer::from_seconds(0.15, TimerMode::Once);
+
+    let Some(position) = input.pointer else {
+        return;
+    };
+    let (look_x, look_y) = cursor_look_angles(input.window_size, position, mood.look_divisor());
+
+    cube_prop.random_look_y = PI - look_y;
+    cube_prop.random_look_x = -look_x;
+}
+
+fn scared_cube_update(
+    time: Res<Time>,
+    mut query_cube: Query<(&mut Transform, &mut Cube)>,
+    manual_override: Option<Res<ManualOverride>>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    let mut rng = rand::thread_rng();
+    let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
+    let (mut cube_rot_y, mut cube_rot_x, _) = cube_transform.rotation.to_euler(EulerRot::YXZ);
+
+    cube_prop.state_timer.tick(time.delta());
+
+    if !cube_prop.rotate_timer.finished() {
+        cube_prop.rotate_timer.tick(time.delta());
+
+        let t =
+            cube_prop.rotate_timer.elapsed_secs() / cube_prop.rotate_timer.duration().as_secs_f32();
+
+        if cube_rot_y < 0. {
+            cube_rot_y += 2. * PI;
         }
+
+        cube_rot_x = cube_rot_x.lerp(cube_prop.random_look_x, t);
+        cube_rot_y = cube_rot_y.lerp(cube_prop.random_look_y, t);
+    } else {
+        cube_rot_x = cube_prop.random_look_x;
+        cube_rot_y = cube_prop.random_look_y;
+    }
+
+    // cowers a little and trembles
+    cube_transform.scale = cube_transform
+        .scale
+        .lerp(Vec3::splat(0.9), (10.0 * time.delta_seconds()).min(1.0));
+    cube_transform.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        cube_rot_y + rng.gen_range(-0.02..0.02),
+        cube_rot_x + rng.gen_range(-0.02..0.02),
+        rng.gen_range(-0.03..0.03),
+    );
+
+    if cube_prop.state_timer.finished() && manual_override.is_none() {
+        next_state.set(CubeState::Happy);
     }
 }
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::{input::InputState, keybindings::Action, CubeState, CubeSystems};
+
+const OVERRIDE_SECS: f32 = 10.0;
+
+// number keys from 1 up, in the order the states were added
+const FORCED_STATES: [(Action, CubeState); 9] = [
+    (Action::ForceHappy, CubeState::Happy),
+    (Action::ForceSad, CubeState::Sad),
+    (Action::ForceSleepy, CubeState::Sleepy),
+    (Action::ForceAngry, CubeState::Angry),
+    (Action::ForceDizzy, CubeState::Dizzy),
+    (Action::ForceExcited, CubeState::Excited),
+    (Action::ForceScared, CubeState::Scared),
+    (Action::ForceHungry, CubeState::Hungry),
+    (Action::ForceSulking, CubeState::Sulking),
+];
+
+pub struct ManualOverridePlugin;
+
+impl Plugin for ManualOverridePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                force_state,
+                manual_override_update.run_if(resource_exists::<ManualOverride>),
+            )
+                .chain()
+                // so the behaviors already see the override on the frame the key goes down
+                .before(CubeSystems::Behavior),
+        );
+    }
+}
+
+// While this exists the cube stays in the state it was forced into, no matter what the cursor does.
+#[derive(Resource)]
+pub struct ManualOverride {
+    timer: Timer,
+    state: CubeState,
+}
+
+pub fn no_manual_override(manual_override: Option<Res<ManualOverride>>) -> bool {
+    manual_override.is_none()
+}
+
+fn force_state(
+    mut commands: Commands,
+    input: Res<InputState>,
+    manual_override: Option<Res<ManualOverride>>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    if manual_override.is_some() && input.just_did(Action::Cancel) {
+        commands.remove_resource::<ManualOverride>();
+        return;
+    }
+
+    let Some(&(_, state)) = FORCED_STATES
+        .iter()
+        .find(|(action, _)| input.just_did(*action))
+    else {
+        return;
+    };
+
+    // the same key again hands control back
+    if manual_override.is_some_and(|manual_override| manual_override.state == state) {
+        commands.remove_resource::<ManualOverride>();
+        return;
+    }
+
+    next_state.set(state);
+    commands.insert_resource(ManualOverride {
+        timer: Timer::from_seconds(OVERRIDE_SECS, TimerMode::Once),
+        state,
+    });
+}
+
+fn manual_override_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut manual_override: ResMut<ManualOverride>,
+) {
+    if manual_override.timer.tick(time.delta()).finished() {
+        commands.remove_resource::<ManualOverride>();
+    }
+}
@@ -0,0 +1,208 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    canvas_to_world, hunger::spawn_food, input::InputState, keybindings::Action, mood::Mood,
+    pause::AppMode, petting::pet, CanvasPointer, CanvasZoom, Cube, CubeSystems, MainCamera,
+};
+
+const MENU_COLOR: Color = Color::srgb(0.12, 0.12, 0.15);
+const ENTRY_HOVER_COLOR: Color = Color::srgb(0.25, 0.25, 0.32);
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<MenuState>()
+            .add_systems(
+                Update,
+                open_menu
+                    .run_if(in_state(MenuState::Closed))
+                    .run_if(in_state(AppMode::Running)),
+            )
+            .add_systems(
+                Update,
+                (highlight_entries, pick_entry, close_menu)
+                    .chain()
+                    .run_if(in_state(MenuState::Open))
+                    // so whatever an entry does is already there for the cube to react to
+                    .before(CubeSystems::Behavior),
+            )
+            .add_systems(OnExit(MenuState::Open), despawn_menu);
+    }
+}
+
+// While open the cube ignores clicks, they're meant for the menu.
+#[derive(States, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum MenuState {
+    #[default]
+    Closed,
+    Open,
+    // the panel the Settings entry opens, see settings_menu.rs
+    Settings,
+}
+
+#[derive(Component)]
+struct ContextMenu;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+enum MenuEntry {
+    Pet,
+    Feed,
+    Settings,
+    Quit,
+}
+
+impl MenuEntry {
+    const ALL: [MenuEntry; 4] = [
+        MenuEntry::Pet,
+        MenuEntry::Feed,
+        MenuEntry::Settings,
+        MenuEntry::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuEntry::Pet => "Pet",
+            MenuEntry::Feed => "Feed",
+            MenuEntry::Settings => "Settings",
+            MenuEntry::Quit => "Quit",
+        }
+    }
+}
+
+fn open_menu(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    input: Res<InputState>,
+    zoom: Res<CanvasZoom>,
+    cameras: Query<Entity, With<MainCamera>>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Some(position) = input.pointer else {
+        return;
+    };
+
+    let pixel = zoom.ui_pixel();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(position.x),
+                    top: Val::Px(position.y),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(2.0 * pixel)),
+                    ..default()
+                },
+                background_color: MENU_COLOR.into(),
+                ..default()
+            },
+            TargetCamera(cameras.single()),
+            ContextMenu,
+        ))
+        .with_children(|menu| {
+            for entry in MenuEntry::ALL {
+                menu.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(4.0 * pixel), Val::Px(1.0 * pixel)),
+                            ..default()
+                        },
+                        background_color: Color::NONE.into(),
+                        ..default()
+                    },
+                    entry,
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        entry.label(),
+                        TextStyle {
+                            font_size: 8.0 * pixel,
+                            color: TEXT_COLOR,
+                            ..default()
+                        },
+                    ));
+                });
+            }
+        });
+
+    next_state.set(MenuState::Open);
+}
+
+fn highlight_entries(
+    mut entries: Query<(&Interaction, &MenuEntry, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, _, mut background) in &mut entries {
+        *background = match interaction {
+            Interaction::Hovered | Interaction::Pressed => ENTRY_HOVER_COLOR.into(),
+            _ => Color::NONE.into(),
+        };
+    }
+}
+
+fn pick_entry(
+    mut commands: Commands,
+    pointer: CanvasPointer,
+    mut mood: ResMut<Mood>,
+    entries: Query<(&Interaction, &MenuEntry), Changed<Interaction>>,
+    query_cube: Query<Entity, With<Cube>>,
+    mut exit: EventWriter<AppExit>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    let Some((_, &entry)) = entries
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+    else {
+        return;
+    };
+
+    match entry {
+        MenuEntry::Pet => pet(&mut commands, query_cube.single(), &mut mood, 1.0),
+        MenuEntry::Feed => {
+            // falls from the menu, straight to the cube
+            if let Some(cursor) = pointer.cursor() {
+                spawn_food(
+                    &mut commands,
+                    canvas_to_world(cursor, pointer.canvas.size()),
+                );
+            }
+        }
+        MenuEntry::Settings => {
+            next_state.set(MenuState::Settings);
+            return;
+        }
+        MenuEntry::Quit => {
+            exit.send(AppExit::Success);
+        }
+    }
+
+    next_state.set(MenuState::Closed);
+}
+
+// Clicking anywhere off the menu, or Escape, puts it away.
+fn close_menu(
+    input: Res<InputState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    entries: Query<&Interaction, With<MenuEntry>>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    let clicked_off = mouse.any_just_pressed([MouseButton::Left, MouseButton::Right])
+        && entries
+            .iter()
+            .all(|interaction| *interaction == Interaction::None);
+
+    if clicked_off || input.just_did(Action::Cancel) {
+        next_state.set(MenuState::Closed);
+    }
+}
+
+fn despawn_menu(mut commands: Commands, menus: Query<Entity, With<ContextMenu>>) {
+    for menu in &menus {
+        commands.entity(menu).despawn_recursive();
+    }
+}
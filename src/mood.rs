@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+use crate::{idle::window_visible, input::InputState, pause::AppMode};
+
+pub struct MoodPlugin;
+
+impl Plugin for MoodPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Mood>().add_systems(
+            Update,
+            // by the real clock so slow motion doesn't sour it, and held like the rest of the cube
+            // while it's paused or can't be seen
+            mood_update
+                .run_if(in_state(AppMode::Running))
+                .run_if(window_visible),
+        );
+    }
+}
+
+// How the cube feels overall, from 0.0 (miserable) to 1.0 (delighted).
+#[derive(Resource)]
+pub struct Mood(pub f32);
+
+impl Default for Mood {
+    fn default() -> Self {
+        Mood(0.5)
+    }
+}
+
+impl Mood {
+    // A happier cube follows the cursor more eagerly.
+    pub fn look_divisor(&self) -> f32 {
+        24.0 - 8.0 * self.0
+    }
+
+    // How bright the lights are against their full brightness, see time_of_day.rs.
+    pub fn light_factor(&self) -> f32 {
+        0.6 + 0.4 * self.0
+    }
+}
+
+// Lights that dim with the mood, remembering how bright they are at full mood.
+#[derive(Component)]
+pub struct MoodLight {
+    pub base_intensity: f32,
+}
+
+fn mood_update(time: Res<Time<Real>>, input: Res<InputState>, mut mood: ResMut<Mood>) {
+    let rate = if input.pointer_present() { 0.02 } else { -0.03 };
+
+    mood.0 = (mood.0 + rate * time.delta_seconds()).clamp(0.0, 1.0);
+}
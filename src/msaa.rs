@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ambient_occlusion::AmbientOcclusion,
+    compare::{CanvasCompare, Held},
+    input::InputState,
+    keybindings::Action,
+    settings::Settings,
+};
+
+pub struct MsaaPlugin;
+
+impl Plugin for MsaaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                compare_msaa,
+                apply_msaa.run_if(
+                    resource_changed::<Settings>
+                        .or_else(resource_changed::<Msaa>)
+                        .or_else(resource_changed::<CanvasCompare>),
+                ),
+            )
+                .chain(),
+        );
+    }
+}
+
+// Smooths the cube's edges on the canvas before it's scaled up, at the cost of drawing each canvas
+// pixel several times over.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Multisampling {
+    #[default]
+    Off,
+    X2,
+    X4,
+}
+
+impl Multisampling {
+    pub fn label(self) -> &'static str {
+        match self {
+            Multisampling::Off => "Off",
+            Multisampling::X2 => "2x",
+            Multisampling::X4 => "4x",
+        }
+    }
+
+    pub fn next(self) -> Multisampling {
+        match self {
+            Multisampling::Off => Multisampling::X2,
+            Multisampling::X2 => Multisampling::X4,
+            Multisampling::X4 => Multisampling::Off,
+        }
+    }
+
+    fn msaa(self) -> Msaa {
+        match self {
+            Multisampling::Off => Msaa::Off,
+            Multisampling::X2 => Msaa::Sample2,
+            Multisampling::X4 => Msaa::Sample4,
+        }
+    }
+}
+
+// What the settings ask for, unless something on the canvas camera can't be drawn with it. Both of
+// those read the depth prepass, which multisampling would swap for one they can't bind.
+pub fn allowed_msaa(settings: &Settings) -> (Msaa, Option<&'static str>) {
+    let blocker = if settings.multisampling == Multisampling::Off {
+        None
+    } else if settings.ambient_occlusion != AmbientOcclusion::Off {
+        Some("ambient occlusion")
+    } else if !settings.outline_off {
+        Some("the outline")
+    } else {
+        None
+    };
+
+    match blocker {
+        Some(_) => (Msaa::Off, blocker),
+        None => (settings.multisampling.msaa(), None),
+    }
+}
+
+// The left half of the canvas as it looks without multisampling, next to the live half with it.
+// Msaa is one setting for every camera, so the two can't both be live.
+fn compare_msaa(
+    mut commands: Commands,
+    input: Res<InputState>,
+    settings: Res<Settings>,
+    mut compare: ResMut<CanvasCompare>,
+) {
+    if !input.just_did(Action::CompareMsaa) {
+        return;
+    }
+
+    if compare.comparing() == Some(Held::MsaaOff) {
+        compare.stop(&mut commands);
+        return;
+    }
+    match allowed_msaa(&settings) {
+        (Msaa::Off, Some(blocker)) => {
+            warn!("multisampling is off for {blocker}, there's nothing to compare");
+        }
+        (Msaa::Off, None) => warn!("turn multisampling on to compare it against off"),
+        _ => compare.start(&mut commands, Held::MsaaOff),
+    }
+}
+
+// Also puts Msaa back if anything else changes it.
+fn apply_msaa(
+    settings: Res<Settings>,
+    compare: Res<CanvasCompare>,
+    mut msaa: ResMut<Msaa>,
+    mut warned: Local<Option<&'static str>>,
+) {
+    let (allowed, blocker) = allowed_msaa(&settings);
+    if let Some(blocker) = blocker.filter(|blocker| *warned != Some(*blocker)) {
+        warn!("multisampling doesn't work with {blocker}, keeping it off");
+    }
+    *warned = blocker;
+
+    let wanted = if compare.held() == Some(Held::MsaaOff) {
+        Msaa::Off
+    } else {
+        allowed
+    };
+    if *msaa != wanted {
+        *msaa = wanted;
+    }
+}
@@ -0,0 +1,281 @@
+use bevy::{
+    asset::load_internal_asset,
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+        prepass::{DepthPrepass, ViewPrepassTextures},
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{texture_2d, texture_depth_2d, uniform_buffer_sized},
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, BufferInitDescriptor,
+            BufferUsages, CachedRenderPipelineId, ColorTargetState, ColorWrites, FragmentState,
+            MultisampleState, Operations, PipelineCache, PrimitiveState, RenderPassColorAttachment,
+            RenderPassDescriptor, RenderPipelineDescriptor, ShaderStages, TextureFormat,
+            TextureSampleType,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+
+use crate::{
+    accessibility::{ColorPreset, ColorTables},
+    background::StateColors,
+    settings::Settings,
+    CubeState,
+};
+
+pub const OUTLINE_SHADER: Handle<Shader> =
+    Handle::weak_from_u128(0x6d63_6b65_6e7a_6965_6f75_746c_696e_6531);
+// canvas pixels, any wider and it's a border more than an outline
+const MAX_OUTLINE_WIDTH: u32 = 4;
+
+pub struct OutlinePlugin;
+
+impl Plugin for OutlinePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            OUTLINE_SHADER,
+            "../assets/shaders/outline.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_plugins(ExtractComponentPlugin::<CanvasOutline>::default())
+            .add_systems(Startup, load_outline_colors)
+            .add_systems(
+                Update,
+                color_outline.run_if(resource_exists::<OutlineColors>),
+            );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        // after tonemapping, so the outline comes out in exactly its color
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<OutlineNode>>(Core3d, OutlineLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    OutlineLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<OutlinePipeline>();
+    }
+}
+
+// A dark line around everything on the canvas camera, drawn where a pixel has something nearer
+// within `width` of it. The camera draws at the canvas's size, so the width is in canvas pixels
+// and stays crisp at any window scale.
+#[derive(Component, Clone, Copy, ExtractComponent)]
+pub struct CanvasOutline {
+    // linear, filled in from the state every frame
+    color: Vec4,
+    width: f32,
+}
+
+// What the canvas camera needs for an outline, none when it's turned off in the settings. The
+// depth prepass comes with it, so off skips that too.
+pub fn camera_outline(settings: &Settings) -> Option<(CanvasOutline, DepthPrepass)> {
+    (!settings.outline_off).then(|| {
+        let width = settings
+            .outline_width
+            .unwrap_or(1)
+            .clamp(1, MAX_OUTLINE_WIDTH);
+        (
+            CanvasOutline {
+                color: Vec4::W,
+                width: width as f32,
+            },
+            DepthPrepass,
+        )
+    })
+}
+
+// The outline's color for each state, and the preset they were picked for.
+#[derive(Resource)]
+struct OutlineColors(StateColors, ColorPreset);
+
+impl OutlineColors {
+    fn load(settings: &Settings) -> Self {
+        let colors = StateColors::load(
+            ColorTables::load().each(settings.colors, |look| look.outline.as_ref()),
+            &settings.outline_colors,
+            "outline color",
+        );
+        OutlineColors(colors, settings.colors)
+    }
+}
+
+fn load_outline_colors(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(OutlineColors::load(&settings));
+}
+
+fn color_outline(
+    state: Res<State<CubeState>>,
+    settings: Res<Settings>,
+    mut colors: ResMut<OutlineColors>,
+    mut outlines: Query<&mut CanvasOutline>,
+) {
+    if colors.1 != settings.colors {
+        *colors = OutlineColors::load(&settings);
+    }
+
+    let color = Vec4::from_array(colors.0.get(*state.get()).to_linear().to_f32_array());
+    for mut outline in &mut outlines {
+        if outline.color != color {
+            outline.color = color;
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct OutlineLabel;
+
+#[derive(Default)]
+struct OutlineNode;
+
+impl ViewNode for OutlineNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static ViewPrepassTextures,
+        &'static CanvasOutline,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, prepass_textures, outline): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let outline_pipeline = world.resource::<OutlinePipeline>();
+        let pipeline_id = if view_target.is_hdr() {
+            outline_pipeline.hdr_pipeline
+        } else {
+            outline_pipeline.pipeline
+        };
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(pipeline_id)
+        else {
+            return Ok(());
+        };
+        let Some(depth) = prepass_textures.depth_view() else {
+            return Ok(());
+        };
+
+        // laid out like the shader's struct, the width padded out to a whole vec4
+        let contents: Vec<u8> = [outline.color, Vec4::new(outline.width, 0.0, 0.0, 0.0)]
+            .iter()
+            .flat_map(|row| row.to_array())
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let render_device = render_context.render_device();
+        let uniform = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("outline_uniform"),
+            contents: &contents,
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_device.create_bind_group(
+            "outline_bind_group",
+            &outline_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                depth,
+                uniform.as_entire_binding(),
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("outline_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+// One pipeline per format the camera's texture can be in, HDR while there's bloom.
+#[derive(Resource)]
+struct OutlinePipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedRenderPipelineId,
+    hdr_pipeline: CachedRenderPipelineId,
+}
+
+impl FromWorld for OutlinePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world.resource::<RenderDevice>().create_bind_group_layout(
+            "outline_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    texture_depth_2d(),
+                    uniform_buffer_sized(false, None),
+                ),
+            ),
+        );
+
+        let pipeline_cache = world.resource_mut::<PipelineCache>();
+        let queue = |format| {
+            pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some("outline_pipeline".into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: OUTLINE_SHADER,
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+            })
+        };
+        let pipeline = queue(TextureFormat::bevy_default());
+        let hdr_pipeline = queue(ViewTarget::TEXTURE_FORMAT_HDR);
+
+        OutlinePipeline {
+            layout,
+            pipeline,
+            hdr_pipeline,
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadState},
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::{
+    canvas_material::CanvasMaterial, input::InputState, keybindings::Action, settings::Settings,
+};
+
+// What every canvas material reads its palette from, swapped out whenever the palette changes.
+pub const PALETTE_IMAGE: Handle<Image> =
+    Handle::weak_from_u128(0x6d63_6b65_6e7a_6965_7061_6c65_7474_6531);
+const PICO_8_NAME: &str = "pico-8";
+const PICO_8: [&str; 16] = [
+    "#000000", "#1d2b53", "#7e2553", "#008751", "#ab5236", "#5f574f", "#c2c3c7", "#fff1e8",
+    "#ff004d", "#ffa300", "#ffec27", "#00e436", "#29adff", "#83769c", "#ff77a8", "#ffccaa",
+];
+// the shader checks every pixel against every color, past this it's no longer a small palette
+const MAX_PALETTE_COLORS: usize = 256;
+
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        // a stand-in until there's a palette, the materials need something to bind
+        app.world_mut()
+            .resource_mut::<Assets<Image>>()
+            .insert(&PALETTE_IMAGE, palette_image(&[Color::BLACK]));
+
+        app.init_asset::<Palette>()
+            .init_asset_loader::<PaletteLoader>()
+            .add_systems(Startup, load_palettes)
+            .add_systems(
+                Update,
+                (cycle_palette, apply_palette)
+                    .chain()
+                    .run_if(resource_exists::<Palettes>),
+            );
+    }
+}
+
+// Colors read from a palette file, in the order it lists them.
+#[derive(Asset, TypePath)]
+struct Palette(Vec<Color>);
+
+// Reads JASC .pal files, what most pixel art editors export, and .hex files of one color a line.
+#[derive(Default)]
+struct PaletteLoader;
+
+impl AssetLoader for PaletteLoader {
+    type Asset = Palette;
+    type Settings = ();
+    type Error = String;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Palette, String> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let colors = if text.starts_with("JASC-PAL") {
+            // the header is the format's name, its version and how many colors follow
+            lines
+                .skip(3)
+                .map(|line| {
+                    let channels: Vec<u8> = line
+                        .split_whitespace()
+                        .map(str::parse)
+                        .collect::<Result<_, _>>()
+                        .map_err(|error| format!("{line:?} isn't a color: {error}"))?;
+                    match channels[..] {
+                        [r, g, b] => Ok(Color::srgb_u8(r, g, b)),
+                        _ => Err(format!("{line:?} isn't three channels")),
+                    }
+                })
+                .collect::<Result<Vec<_>, String>>()?
+        } else {
+            lines
+                .map(|line| {
+                    Srgba::hex(line)
+                        .map(Color::from)
+                        .map_err(|error| format!("{line:?} isn't a hex color: {error}"))
+                })
+                .collect::<Result<Vec<_>, String>>()?
+        };
+
+        if colors.is_empty() {
+            return Err("there aren't any colors in it".to_string());
+        }
+        Ok(Palette(colors))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pal", "hex"]
+    }
+}
+
+enum PaletteSource {
+    BuiltIn(Vec<Color>),
+    File(Handle<Palette>),
+    // a row of swatches, every color in it once or in blocks of any size
+    Strip(Handle<Image>),
+}
+
+struct PaletteChoice {
+    name: String,
+    source: PaletteSource,
+}
+
+// Every palette the canvas can be limited to, in the order the hotkey steps through them.
+#[derive(Resource)]
+struct Palettes {
+    choices: Vec<PaletteChoice>,
+    // none for off
+    current: Option<usize>,
+    // whether the canvas shows the current one yet, it may still be loading
+    applied: bool,
+}
+
+fn load_palettes(mut commands: Commands, settings: Res<Settings>, asset_server: Res<AssetServer>) {
+    let pico_8 = PICO_8
+        .iter()
+        .map(|hex| Color::from(Srgba::hex(hex).expect("valid hex")))
+        .collect();
+    let mut choices = vec![PaletteChoice {
+        name: PICO_8_NAME.to_string(),
+        source: PaletteSource::BuiltIn(pico_8),
+    }];
+
+    // the one picked last time, even if it's not in the list anymore
+    let mut paths = settings.palettes.clone();
+    if let Some(current) = &settings.palette {
+        if current != PICO_8_NAME && !paths.contains(current) {
+            paths.push(current.clone());
+        }
+    }
+    for path in paths {
+        let source = if path.ends_with(".png") {
+            PaletteSource::Strip(asset_server.load(path.clone()))
+        } else {
+            PaletteSource::File(asset_server.load(path.clone()))
+        };
+        choices.push(PaletteChoice { name: path, source });
+    }
+
+    let current = settings
+        .palette
+        .as_ref()
+        .and_then(|name| choices.iter().position(|choice| &choice.name == name));
+    commands.insert_resource(Palettes {
+        choices,
+        current,
+        applied: false,
+    });
+}
+
+// Steps to the next palette, off after the last one.
+fn cycle_palette(
+    input: Res<InputState>,
+    mut settings: ResMut<Settings>,
+    mut palettes: ResMut<Palettes>,
+) {
+    if !input.just_did(Action::CyclePalette) {
+        return;
+    }
+
+    let next = palettes.current.map_or(0, |current| current + 1);
+    palettes.current = (next < palettes.choices.len()).then_some(next);
+    palettes.applied = false;
+
+    settings.palette = palettes
+        .current
+        .map(|current| palettes.choices[current].name.clone());
+    match &settings.palette {
+        Some(name) => info!("limiting the canvas to the {name} palette"),
+        None => info!("drawing the canvas in every color"),
+    }
+}
+
+fn apply_palette(
+    mut palettes: ResMut<Palettes>,
+    files: Res<Assets<Palette>>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    canvases: Query<&Handle<CanvasMaterial>>,
+    mut materials: ResMut<Assets<CanvasMaterial>>,
+) {
+    if palettes.applied || canvases.is_empty() {
+        return;
+    }
+
+    let colors = match palettes.current.map(|current| &palettes.choices[current]) {
+        None => None,
+        Some(choice) => {
+            let (id, colors) = match &choice.source {
+                PaletteSource::BuiltIn(colors) => (None, Some(colors.clone())),
+                PaletteSource::File(handle) => (
+                    Some(handle.id().untyped()),
+                    files.get(handle).map(|palette| palette.0.clone()),
+                ),
+                PaletteSource::Strip(handle) => (
+                    Some(handle.id().untyped()),
+                    images.get(handle).and_then(strip_colors),
+                ),
+            };
+            if let Some(LoadState::Failed(error)) =
+                id.and_then(|id| asset_server.get_load_state(id))
+            {
+                error!("couldn't load the {} palette: {error}", choice.name);
+                palettes.current = None;
+                return;
+            }
+            // still loading
+            let Some(mut colors) = colors else {
+                return;
+            };
+            if colors.len() > MAX_PALETTE_COLORS {
+                warn!(
+                    "the {} palette has {} colors, only using the first {MAX_PALETTE_COLORS}",
+                    choice.name,
+                    colors.len()
+                );
+                colors.truncate(MAX_PALETTE_COLORS);
+            }
+            Some(colors)
+        }
+    };
+    palettes.applied = true;
+
+    if let Some(colors) = &colors {
+        images.insert(&PALETTE_IMAGE, palette_image(colors));
+    }
+    // touching the materials also gets them to pick up the new image
+    for handle in &canvases {
+        if let Some(material) = materials.get_mut(handle) {
+            material.limit_to_palette(colors.is_some());
+        }
+    }
+}
+
+// Every color in the strip once, left to right and then down. Fully transparent pixels are gaps
+// between swatches.
+fn strip_colors(image: &Image) -> Option<Vec<Color>> {
+    let pixels = image.clone().try_into_dynamic().ok()?.to_rgba8();
+    let mut colors: Vec<[u8; 4]> = Vec::new();
+    for pixel in pixels.pixels() {
+        if pixel.0[3] > 0 && !colors.contains(&pixel.0) {
+            colors.push(pixel.0);
+        }
+    }
+    Some(
+        colors
+            .into_iter()
+            .map(|[r, g, b, _]| Color::srgb_u8(r, g, b))
+            .collect(),
+    )
+}
+
+// One texel per color, stored as sRGB so the canvas comes out in exactly the palette's colors.
+fn palette_image(colors: &[Color]) -> Image {
+    let data = colors
+        .iter()
+        .flat_map(|color| color.to_srgba().to_u8_array())
+        .collect();
+    Image::new(
+        Extent3d {
+            width: colors.len() as u32,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D1,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
@@ -0,0 +1,109 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+use rand::prelude::*;
+use std::f32::consts::PI;
+
+use crate::{canvas_to_world, CanvasPointer, Cube, CubeState, CANVAS_LAYER};
+
+const SLEEP_PARTICLE_SECS: f32 = 2.0;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_sleep_particle).add_systems(
+            Update,
+            (
+                spawn_sleep_particles.run_if(in_state(CubeState::Sleepy)),
+                // ones already floating finish even after it wakes up
+                sleep_particle_update,
+            ),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct SleepParticleImage(Handle<Image>);
+
+// How often the cube puffs out a "Z" while it sleeps.
+#[derive(Component)]
+pub struct SleepParticleSpawner {
+    timer: Timer,
+}
+
+impl Default for SleepParticleSpawner {
+    fn default() -> Self {
+        SleepParticleSpawner {
+            timer: Timer::from_seconds(0.8, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component)]
+struct SleepParticle {
+    lifetime: Timer,
+    velocity: Vec2,
+    sway_phase: f32,
+}
+
+fn load_sleep_particle(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SleepParticleImage(asset_server.load("sleep-z.png")));
+}
+
+fn spawn_sleep_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    image: Res<SleepParticleImage>,
+    pointer: CanvasPointer,
+    mut query_cube: Query<(&GlobalTransform, &mut SleepParticleSpawner), With<Cube>>,
+) {
+    let (cube_global, mut spawner) = query_cube.single_mut();
+
+    if !spawner.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some((center, radius)) = pointer.cube(cube_global) else {
+        return;
+    };
+
+    let mut rng = rand::thread_rng();
+    // puffs out from the top right corner of the cube
+    let from =
+        canvas_to_world(center, pointer.canvas.size()) + Vec2::new(radius * 0.6, radius * 0.7);
+
+    commands.spawn((
+        SpriteBundle {
+            texture: image.0.clone(),
+            transform: Transform::from_translation(from.extend(1.0)),
+            ..default()
+        },
+        RenderLayers::layer(CANVAS_LAYER),
+        SleepParticle {
+            lifetime: Timer::from_seconds(SLEEP_PARTICLE_SECS, TimerMode::Once),
+            velocity: Vec2::new(rng.gen_range(6.0..10.0), rng.gen_range(14.0..20.0)),
+            sway_phase: rng.gen_range(0.0..2.0 * PI),
+        },
+    ));
+}
+
+// Drifts up and to the right, swaying a bit and fading out until it's gone.
+fn sleep_particle_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_particles: Query<(Entity, &mut Transform, &mut Sprite, &mut SleepParticle)>,
+) {
+    for (entity, mut transform, mut sprite, mut particle) in &mut query_particles {
+        particle.lifetime.tick(time.delta());
+
+        if particle.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let elapsed = particle.lifetime.elapsed_secs();
+        let sway = (elapsed * 4.0 + particle.sway_phase).sin() * 6.0;
+
+        transform.translation.x += (particle.velocity.x + sway) * time.delta_seconds();
+        transform.translation.y += particle.velocity.y * time.delta_seconds();
+        sprite.color.set_alpha(1.0 - particle.lifetime.fraction());
+    }
+}
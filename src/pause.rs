@@ -0,0 +1,233 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    celebration::Celebration,
+    input::{gather_input, InputState},
+    keybindings::Action,
+    manual_override::ManualOverride,
+    menu::MenuState,
+    CanvasZoom, Cube, MainCamera,
+};
+
+const OVERLAY_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.55);
+const PANEL_COLOR: Color = Color::srgb(0.12, 0.12, 0.15);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.25, 0.25, 0.32);
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const DISABLED_TEXT_COLOR: Color = Color::srgb(0.45, 0.45, 0.5);
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AppMode>()
+            // decided before anything else gets to treat Escape as a cancel
+            .add_systems(PreUpdate, toggle_pause.after(gather_input))
+            .add_systems(
+                Update,
+                (highlight_buttons, pick_button)
+                    .chain()
+                    .run_if(in_state(AppMode::Paused)),
+            )
+            .add_systems(OnEnter(AppMode::Paused), (pause_time, spawn_pause_overlay))
+            .add_systems(
+                OnExit(AppMode::Paused),
+                (resume_time, despawn_pause_overlay, ease_back_to_cursor),
+            );
+    }
+}
+
+// While paused the cube's behaviors and overlays don't run and virtual time stands still, so no
+// timer or meter moves on.
+#[derive(States, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum AppMode {
+    #[default]
+    Running,
+    Paused,
+}
+
+#[derive(Component)]
+struct PauseOverlay;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+enum PauseButton {
+    Resume,
+    // nothing to set yet, shown greyed out
+    Settings,
+    Quit,
+}
+
+impl PauseButton {
+    const ALL: [PauseButton; 3] = [
+        PauseButton::Resume,
+        PauseButton::Settings,
+        PauseButton::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PauseButton::Resume => "Resume",
+            PauseButton::Settings => "Settings",
+            PauseButton::Quit => "Quit",
+        }
+    }
+
+    fn enabled(self) -> bool {
+        self != PauseButton::Settings
+    }
+}
+
+// Escape only pauses when there's nothing else for it to cancel.
+fn toggle_pause(
+    input: Res<InputState>,
+    mode: Res<State<AppMode>>,
+    menu: Res<State<MenuState>>,
+    manual_override: Option<Res<ManualOverride>>,
+    celebration: Option<Res<Celebration>>,
+    mut next_mode: ResMut<NextState<AppMode>>,
+) {
+    if !input.just_did(Action::Cancel) {
+        return;
+    }
+
+    match mode.get() {
+        AppMode::Paused => next_mode.set(AppMode::Running),
+        AppMode::Running
+            if *menu.get() == MenuState::Closed
+                && manual_override.is_none()
+                && celebration.is_none() =>
+        {
+            next_mode.set(AppMode::Paused);
+        }
+        AppMode::Running => {}
+    }
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn spawn_pause_overlay(
+    mut commands: Commands,
+    zoom: Res<CanvasZoom>,
+    cameras: Query<Entity, With<MainCamera>>,
+) {
+    let pixel = zoom.ui_pixel();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: OVERLAY_COLOR.into(),
+                ..default()
+            },
+            TargetCamera(cameras.single()),
+            PauseOverlay,
+        ))
+        .with_children(|overlay| {
+            overlay
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Stretch,
+                        padding: UiRect::all(Val::Px(4.0 * pixel)),
+                        row_gap: Val::Px(2.0 * pixel),
+                        ..default()
+                    },
+                    background_color: PANEL_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|panel| {
+                    for button in PauseButton::ALL {
+                        let color = if button.enabled() {
+                            TEXT_COLOR
+                        } else {
+                            DISABLED_TEXT_COLOR
+                        };
+
+                        panel
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        justify_content: JustifyContent::Center,
+                                        padding: UiRect::axes(
+                                            Val::Px(6.0 * pixel),
+                                            Val::Px(2.0 * pixel),
+                                        ),
+                                        ..default()
+                                    },
+                                    background_color: Color::NONE.into(),
+                                    ..default()
+                                },
+                                button,
+                            ))
+                            .with_children(|button_node| {
+                                button_node.spawn(TextBundle::from_section(
+                                    button.label(),
+                                    TextStyle {
+                                        font_size: 8.0 * pixel,
+                                        color,
+                                        ..default()
+                                    },
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+fn highlight_buttons(
+    mut buttons: Query<(&Interaction, &PauseButton, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, button, mut background) in &mut buttons {
+        *background = match interaction {
+            Interaction::Hovered | Interaction::Pressed if button.enabled() => {
+                BUTTON_HOVER_COLOR.into()
+            }
+            _ => Color::NONE.into(),
+        };
+    }
+}
+
+fn pick_button(
+    buttons: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    mut exit: EventWriter<AppExit>,
+    mut next_mode: ResMut<NextState<AppMode>>,
+) {
+    let Some((_, &button)) = buttons
+        .iter()
+        .find(|(interaction, button)| **interaction == Interaction::Pressed && button.enabled())
+    else {
+        return;
+    };
+
+    match button {
+        PauseButton::Resume => next_mode.set(AppMode::Running),
+        PauseButton::Settings => {}
+        PauseButton::Quit => {
+            exit.send(AppExit::Success);
+        }
+    }
+}
+
+fn despawn_pause_overlay(mut commands: Commands, overlays: Query<Entity, With<PauseOverlay>>) {
+    for overlay in &overlays {
+        commands.entity(overlay).despawn_recursive();
+    }
+}
+
+// The cursor has likely moved while paused, turn to it instead of snapping.
+fn ease_back_to_cursor(mut query_cube: Query<&mut Cube>) {
+    for mut cube_prop in &mut query_cube {
+        cube_prop.rotate_timer = Timer::from_seconds(0.5, TimerMode::Once);
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+use std::{env, f32::consts::PI, ops::Range};
+
+pub struct PersonalityPlugin;
+
+impl Plugin for PersonalityPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Personality::from_seed(personality_seed()));
+    }
+}
+
+// Little quirks rolled once per launch, so no two runs of the cube act quite the same.
+#[derive(Resource, Debug)]
+pub struct Personality {
+    // how far it turns towards the cursor, 1.0 is the stock cube
+    pub look_sensitivity: f32,
+    // yaw (in radians) the sad wander picks from, always facing roughly away
+    pub wander_yaw: Range<f32>,
+    // scales the time between blinks, bigger blinks less often
+    pub blink_pace: f32,
+    // scales how far bored glances stray from the cursor
+    pub fidget: f32,
+}
+
+impl Personality {
+    fn from_seed(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let wander_center = PI + rng.gen_range(-0.3..0.3);
+        let wander_spread = rng.gen_range(0.35..0.65);
+
+        Personality {
+            look_sensitivity: rng.gen_range(0.8..1.2),
+            wander_yaw: wander_center - wander_spread..wander_center + wander_spread,
+            blink_pace: rng.gen_range(0.7..1.4),
+            fidget: rng.gen_range(0.6..1.4),
+        }
+    }
+}
+
+// MCKENZIE_PERSONALITY_SEED brings back a personality from the log, otherwise a new one is rolled.
+fn personality_seed() -> u64 {
+    let seed = match env::var("MCKENZIE_PERSONALITY_SEED") {
+        Ok(seed) => seed.parse().unwrap_or_else(|_| {
+            warn!("MCKENZIE_PERSONALITY_SEED should be a number, got {seed:?}");
+            rand::thread_rng().gen()
+        }),
+        Err(_) => rand::thread_rng().gen(),
+    };
+
+    info!("personality seed: {seed}");
+    seed
+}
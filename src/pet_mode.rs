@@ -0,0 +1,66 @@
+use bevy::{
+    prelude::*,
+    window::{CompositeAlphaMode, WindowMode, WindowResizeConstraints, WindowResolution},
+};
+use std::env;
+
+// canvas pixels around the cube that the borderless window shows
+const PET_REGION: Vec2 = Vec2::new(128.0, 128.0);
+// window pixels per canvas pixel, fixed since there's no background to letterbox against
+pub const PET_CANVAS_SCALE: f32 = 2.0;
+
+// Started with `--pet`, the cube floats straight on the desktop with no window around it.
+#[derive(Resource, Clone, Copy)]
+pub struct PetMode {
+    pub enabled: bool,
+}
+
+impl PetMode {
+    pub fn from_args() -> Self {
+        PetMode {
+            enabled: env::args().any(|arg| arg == "--pet"),
+        }
+    }
+
+    pub fn window(self, canvas_size: Vec2) -> Window {
+        let window = Window {
+            title: "McKenzie Bevy".into(),
+            mode: WindowMode::Windowed,
+            position: WindowPosition::Centered(MonitorSelection::Primary),
+            // any smaller and the canvas would have to shrink below one window pixel per pixel
+            resize_constraints: WindowResizeConstraints {
+                min_width: canvas_size.x,
+                min_height: canvas_size.y,
+                ..default()
+            },
+            ..default()
+        };
+        if !self.enabled {
+            return window;
+        }
+
+        let size = PET_REGION * PET_CANVAS_SCALE;
+        Window {
+            resolution: WindowResolution::new(size.x, size.y),
+            resizable: false,
+            transparent: true,
+            decorations: false,
+            // macOS only composites the window's alpha when asked to
+            composite_alpha_mode: if cfg!(target_os = "macos") {
+                CompositeAlphaMode::PostMultiplied
+            } else {
+                CompositeAlphaMode::Auto
+            },
+            ..window
+        }
+    }
+
+    // What both cameras clear to, see-through so the desktop shows around the cube.
+    pub fn clear_color(self) -> ClearColorConfig {
+        if self.enabled {
+            ClearColorConfig::Custom(Color::NONE)
+        } else {
+            ClearColorConfig::Default
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+use crate::{
+    face::EyesClosed, menu::MenuState, mood::Mood, CanvasPointer, Cube, CubeState, CubeSystems,
+};
+
+// back and forth this many times to count as a good pet
+const PET_REVERSALS: u32 = 3;
+// canvas pixels the cursor has to travel before its direction counts
+const PET_STROKE_MIN: f32 = 1.0;
+
+pub struct PettingPlugin;
+
+impl Plugin for PettingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PetStroke>().add_systems(
+            Update,
+            (
+                petting_update
+                    .run_if(in_state(CubeState::Happy))
+                    .run_if(in_state(MenuState::Closed)),
+                contented_update,
+            )
+                .chain()
+                .in_set(CubeSystems::Overlay),
+        );
+    }
+}
+
+// The stroke in progress while the button is held over the cube.
+#[derive(Resource, Default)]
+struct PetStroke {
+    last_x: Option<f32>,
+    direction: f32,
+    reversals: u32,
+}
+
+// Eyes shut and leaning into the hand for a moment after a good pet.
+#[derive(Component)]
+struct Contented {
+    timer: Timer,
+    lean: f32,
+}
+
+fn petting_update(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    pointer: CanvasPointer,
+    mut stroke: ResMut<PetStroke>,
+    mut mood: ResMut<Mood>,
+    query_cube: Query<(Entity, &GlobalTransform, Has<Contented>), With<Cube>>,
+) {
+    let (entity, cube_global, already_contented) = query_cube.single();
+
+    // letting go or sliding off the cube starts over
+    if !mouse.pressed(MouseButton::Left) || !pointer.over_cube(cube_global) {
+        *stroke = PetStroke::default();
+        return;
+    }
+    let (Some(cursor), Some((center, _))) = (pointer.cursor(), pointer.cube(cube_global)) else {
+        return;
+    };
+
+    let Some(last_x) = stroke.last_x else {
+        stroke.last_x = Some(cursor.x);
+        return;
+    };
+    let dx = cursor.x - last_x;
+    if dx.abs() < PET_STROKE_MIN {
+        return;
+    }
+
+    if stroke.direction != 0.0 && dx.signum() != stroke.direction {
+        stroke.reversals += 1;
+    }
+    stroke.direction = dx.signum();
+    stroke.last_x = Some(cursor.x);
+
+    if stroke.reversals < PET_REVERSALS || already_contented {
+        return;
+    }
+
+    *stroke = PetStroke::default();
+    // canvas x grows to the right, a positive roll leans left
+    pet(
+        &mut commands,
+        entity,
+        &mut mood,
+        -(cursor.x - center.x).signum(),
+    );
+}
+
+// Closes its eyes and leans into the hand, `lean` being the direction to roll in.
+pub fn pet(commands: &mut Commands, cube: Entity, mood: &mut Mood, lean: f32) {
+    mood.0 = (mood.0 + 0.1).min(1.0);
+    commands.entity(cube).insert((
+        Contented {
+            timer: Timer::from_seconds(1.5, TimerMode::Once),
+            lean,
+        },
+        EyesClosed,
+    ));
+}
+
+// Leans gently towards the hand on top of whatever pose the behavior computed.
+fn contented_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Transform, &mut Cube, &mut Contented)>,
+) {
+    for (entity, mut cube_transform, mut cube_prop, mut contented) in &mut query_cube {
+        contented.timer.tick(time.delta());
+
+        let t = contented.timer.elapsed_secs() / contented.timer.duration().as_secs_f32();
+        let lean = Quat::from_rotation_z(contented.lean * 0.15 * (t * PI).sin());
+
+        cube_transform.rotation *= lean;
+        cube_prop.look_offset *= lean;
+
+        if contented.timer.finished() {
+            commands
+                .entity(entity)
+                .remove::<Contented>()
+                .remove::<EyesClosed>();
+        }
+    }
+}
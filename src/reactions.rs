@@ -0,0 +1,402 @@
+use bevy::{prelude::*, window::WindowFocused};
+use rand::prelude::*;
+use std::f32::consts::PI;
+
+use crate::{
+    fade::smoothstep,
+    input::{InputState, PressTarget},
+    keybindings::Action,
+    manual_override::ManualOverride,
+    menu::MenuState,
+    screen_effects::ScreenEffects,
+    time_dilation::DilateTime,
+    CanvasPointer, Cube, CubeState, CubeSystems,
+};
+
+// every SNEEZE_ROLL_SECS there's a SNEEZE_CHANCE the cube sneezes, roughly once every few minutes
+const SNEEZE_ROLL_SECS: f32 = 10.0;
+const SNEEZE_CHANCE: f64 = 0.05;
+// two clicks on the cube within this long make a double click
+const DOUBLE_CLICK_SECS: f32 = 0.3;
+// real seconds everything stops for as the cube is startled, a beat before it flinches
+const STARTLE_FREEZE_SECS: f32 = 0.08;
+// how slowly the sneeze builds up, and for how many real seconds, most of "ah... ah..."
+const SNEEZE_SLOW_FACTOR: f32 = 0.35;
+const SNEEZE_SLOW_SECS: f32 = 1.0;
+
+pub struct ReactionsPlugin;
+
+impl Plugin for ReactionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SneezeRoll(Timer::from_seconds(
+            SNEEZE_ROLL_SECS,
+            TimerMode::Repeating,
+        )))
+        .add_systems(
+            Update,
+            (
+                (startle_on_focus, startle_update).chain(),
+                (
+                    poke_on_click
+                        .run_if(in_state(CubeState::Happy).or_else(in_state(CubeState::Sad)))
+                        .run_if(in_state(MenuState::Closed)),
+                    poke_update,
+                )
+                    .chain(),
+                (
+                    roll_sneeze
+                        .run_if(in_state(CubeState::Happy).or_else(in_state(CubeState::Sad))),
+                    sneeze_update,
+                )
+                    .chain(),
+                gesture_update,
+                (
+                    spin_on_double_click
+                        .run_if(in_state(CubeState::Happy))
+                        .run_if(in_state(MenuState::Closed)),
+                    spin_update,
+                )
+                    .chain(),
+            )
+                .in_set(CubeSystems::Overlay),
+        );
+    }
+}
+
+// Quick flinch: a scale punch and a small recoil away from the camera.
+#[derive(Component)]
+pub struct Startle {
+    timer: Timer,
+}
+
+impl Default for Startle {
+    fn default() -> Self {
+        Startle {
+            timer: Timer::from_seconds(0.2, TimerMode::Once),
+        }
+    }
+}
+
+fn startle_on_focus(
+    mut commands: Commands,
+    mut focus_events: EventReader<WindowFocused>,
+    mut effects: ResMut<ScreenEffects>,
+    mut dilations: EventWriter<DilateTime>,
+    query_cube: Query<Entity, With<Cube>>,
+) {
+    if focus_events.read().last().is_none() {
+        return;
+    }
+
+    effects.kick_aberration();
+    dilations.send(DilateTime::freeze(STARTLE_FREEZE_SECS));
+
+    // inserting again restarts a startle that is already playing
+    commands
+        .entity(query_cube.single())
+        .insert(Startle::default());
+}
+
+fn startle_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Transform, &Cube, &mut Startle)>,
+) {
+    for (entity, mut cube_transform, cube_prop, mut startle) in &mut query_cube {
+        startle.timer.tick(time.delta());
+
+        let t = startle.timer.elapsed_secs() / startle.timer.duration().as_secs_f32();
+        let punch = (t * PI).sin();
+
+        cube_transform.scale = Vec3::splat(1.0 + 0.15 * punch);
+        cube_transform.translation.z = cube_prop.home.z - 0.6 * punch;
+
+        if startle.timer.finished() {
+            cube_transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<Startle>();
+        }
+    }
+}
+
+// Squashes flat towards the camera where it was clicked, then springs back past round.
+#[derive(Component)]
+struct Poke {
+    timer: Timer,
+    // scales the squash, a harder press with a pen pokes deeper
+    strength: f32,
+}
+
+impl Poke {
+    fn new(strength: f32) -> Self {
+        Poke {
+            timer: Timer::from_seconds(0.2, TimerMode::Once),
+            strength,
+        }
+    }
+}
+
+fn poke_on_click(
+    mut commands: Commands,
+    pointer: CanvasPointer,
+    state: Res<State<CubeState>>,
+    manual_override: Option<Res<ManualOverride>>,
+    mut effects: ResMut<ScreenEffects>,
+    query_cube: Query<Entity, With<Cube>>,
+    mut next_state: ResMut<NextState<CubeState>>,
+) {
+    if !pointer.input.pressed_on(PressTarget::Cube) {
+        return;
+    }
+    let entity = query_cube.single();
+    effects.kick_aberration();
+
+    // inserting again restarts a poke that is already playing
+    commands
+        .entity(entity)
+        .insert(Poke::new(pointer.input.pressure));
+
+    // clearly the mouse is back
+    if *state.get() == CubeState::Sad && manual_override.is_none() {
+        next_state.set(CubeState::Happy);
+    }
+}
+
+fn poke_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Transform, &mut Poke)>,
+) {
+    for (entity, mut cube_transform, mut poke) in &mut query_cube {
+        poke.timer.tick(time.delta());
+
+        let t = poke.timer.elapsed_secs() / poke.timer.duration().as_secs_f32();
+        // squashed for the first half, overshooting into a stretch for the second
+        let squash = (t * 2.0 * PI).sin() * (1.0 - 0.5 * t) * poke.strength;
+
+        cube_transform.scale =
+            Vec3::new(1.0 + 0.2 * squash, 1.0 + 0.2 * squash, 1.0 - 0.35 * squash);
+
+        if poke.timer.finished() {
+            cube_transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<Poke>();
+        }
+    }
+}
+
+#[derive(Resource)]
+struct SneezeRoll(Timer);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SneezePhase {
+    // "ah... ah..."
+    Buildup,
+    // "CHOO!"
+    Snap,
+}
+
+// Owns the cube's pose while present, the state behaviors sit it out.
+#[derive(Component)]
+pub struct Sneezing {
+    timer: Timer,
+    phase: SneezePhase,
+    start: Quat,
+}
+
+fn roll_sneeze(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut roll: ResMut<SneezeRoll>,
+    mut dilations: EventWriter<DilateTime>,
+    query_cube: Query<(Entity, &Transform, Has<Sneezing>), With<Cube>>,
+) {
+    if !roll.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let (entity, cube_transform, already_sneezing) = query_cube.single();
+    if already_sneezing {
+        return;
+    }
+
+    if rand::thread_rng().gen_bool(SNEEZE_CHANCE) {
+        commands.entity(entity).insert(Sneezing {
+            timer: Timer::from_seconds(0.4, TimerMode::Once),
+            phase: SneezePhase::Buildup,
+            start: cube_transform.rotation,
+        });
+        dilations.send(DilateTime {
+            factor: SNEEZE_SLOW_FACTOR,
+            secs: SNEEZE_SLOW_SECS,
+        });
+    }
+}
+
+fn sneeze_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Transform, &Cube, &mut Sneezing)>,
+) {
+    for (entity, mut cube_transform, cube_prop, mut sneezing) in &mut query_cube {
+        sneezing.timer.tick(time.delta());
+
+        let t = sneezing.timer.elapsed_secs() / sneezing.timer.duration().as_secs_f32();
+
+        let tilt = match sneezing.phase {
+            // slowly leans its head back
+            SneezePhase::Buildup => -0.35 * t * t,
+            // snaps forward past where it started, then settles back on it
+            SneezePhase::Snap if t < 0.3 => (-0.35_f32).lerp(0.25, t / 0.3),
+            SneezePhase::Snap => 0.25 * (1.0 - (t - 0.3) / 0.7),
+        };
+        cube_transform.rotation = sneezing.start * Quat::from_rotation_x(tilt);
+
+        if sneezing.phase == SneezePhase::Snap {
+            let punch = (t * PI).sin();
+            cube_transform.scale = Vec3::splat(1.0 + 0.12 * punch);
+            cube_transform.translation.z = cube_prop.home.z - 0.3 * punch;
+        }
+
+        if sneezing.timer.finished() {
+            match sneezing.phase {
+                SneezePhase::Buildup => {
+                    sneezing.phase = SneezePhase::Snap;
+                    sneezing.timer = Timer::from_seconds(0.3, TimerMode::Once);
+                }
+                SneezePhase::Snap => {
+                    cube_transform.rotation = sneezing.start;
+                    cube_transform.scale = Vec3::ONE;
+                    commands.entity(entity).remove::<Sneezing>();
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GestureKind {
+    // two quick dips, "yes"
+    Nod,
+    // two sweeps side to side, "no"
+    Shake,
+}
+
+// Head gesture layered on top of whatever pose the behavior computes.
+#[derive(Component)]
+pub struct Gesture {
+    kind: GestureKind,
+    timer: Timer,
+    queued: Option<GestureKind>,
+}
+
+impl Gesture {
+    pub fn new(kind: GestureKind) -> Self {
+        Gesture {
+            kind,
+            timer: Timer::from_seconds(0.6, TimerMode::Once),
+            queued: None,
+        }
+    }
+
+    // plays after the current one instead of cutting it off mid-swing
+    pub fn queue(&mut self, kind: GestureKind) {
+        self.queued = Some(kind);
+    }
+}
+
+fn gesture_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Transform, &mut Cube, &mut Gesture)>,
+) {
+    for (entity, mut cube_transform, mut cube_prop, mut gesture) in &mut query_cube {
+        gesture.timer.tick(time.delta());
+
+        let t = gesture.timer.elapsed_secs() / gesture.timer.duration().as_secs_f32();
+        let offset = match gesture.kind {
+            GestureKind::Nod => Quat::from_rotation_x(0.3 * (2.0 * PI * t).sin().powi(2)),
+            GestureKind::Shake => Quat::from_rotation_y(0.35 * (4.0 * PI * t).sin() * (1.0 - t)),
+        };
+
+        cube_transform.rotation *= offset;
+        cube_prop.look_offset *= offset;
+
+        if gesture.timer.finished() {
+            match gesture.queued.take() {
+                Some(next) => *gesture = Gesture::new(next),
+                None => {
+                    commands.entity(entity).remove::<Gesture>();
+                }
+            }
+        }
+    }
+}
+
+// A full turn around Y layered on top of the tracked look, unwinding from 2π down to nothing.
+#[derive(Component)]
+struct Spin {
+    timer: Timer,
+    queued: u32,
+}
+
+impl Default for Spin {
+    fn default() -> Self {
+        Spin {
+            timer: Timer::from_seconds(0.6, TimerMode::Once),
+            queued: 0,
+        }
+    }
+}
+
+fn spin_on_double_click(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<InputState>,
+    mut query_cube: Query<(Entity, &Cube, Option<&mut Spin>)>,
+) {
+    if !input.just_did(Action::Poke) {
+        return;
+    }
+    let (entity, cube_prop, spin) = query_cube.single_mut();
+
+    // cube_click_update only records clicks that landed on the cube
+    let now = time.elapsed_seconds();
+    let [.., previous, latest] = cube_prop.recent_clicks[..] else {
+        return;
+    };
+    if latest != now || latest - previous > DOUBLE_CLICK_SECS {
+        return;
+    }
+
+    match spin {
+        // a triple click goes round again once this one is done
+        Some(mut spin) => spin.queued += 1,
+        None => {
+            commands.entity(entity).insert(Spin::default());
+        }
+    }
+}
+
+fn spin_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query_cube: Query<(Entity, &mut Transform, &mut Cube, &mut Spin)>,
+) {
+    for (entity, mut cube_transform, mut cube_prop, mut spin) in &mut query_cube {
+        spin.timer.tick(time.delta());
+
+        let t = spin.timer.elapsed_secs() / spin.timer.duration().as_secs_f32();
+        let eased = smoothstep(t);
+        let offset = Quat::from_rotation_y(2.0 * PI * (1.0 - eased));
+
+        cube_transform.rotation *= offset;
+        cube_prop.look_offset *= offset;
+
+        if spin.timer.finished() {
+            if spin.queued > 0 {
+                spin.queued -= 1;
+                spin.timer.reset();
+            } else {
+                commands.entity(entity).remove::<Spin>();
+            }
+        }
+    }
+}
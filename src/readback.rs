@@ -0,0 +1,239 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d,
+            ImageCopyBuffer, ImageDataLayout, MapMode, TextureFormat,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        texture::{GpuImage, TextureFormatPixelInfo},
+        ExtractSchedule, MainWorld, Render, RenderApp, RenderSet,
+    },
+};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use crate::CanvasImage;
+
+// copies still waiting on the GPU past which clip frames get skipped rather than queued up
+const MAX_IN_FLIGHT: usize = 4;
+
+pub struct ReadbackPlugin;
+
+impl Plugin for ReadbackPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+        let in_flight = InFlight::default();
+
+        app.init_resource::<ReadbackRequests>()
+            .insert_resource(Readbacks(Mutex::new(receiver)))
+            .insert_resource(in_flight.clone())
+            .add_event::<CanvasFrame>()
+            .add_systems(PreUpdate, receive_readbacks);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<ExtractedRequests>()
+            .insert_resource(ReadbackSender(sender))
+            .insert_resource(in_flight)
+            .add_systems(ExtractSchedule, extract_requests)
+            // once the canvas has been drawn for the frame
+            .add_systems(
+                Render,
+                copy_canvas
+                    .after(RenderSet::Render)
+                    .before(RenderSet::Cleanup),
+            );
+    }
+}
+
+// What a copy of the canvas is for, handed back with its pixels.
+#[derive(Clone, Debug)]
+pub enum ReadbackPurpose {
+    Screenshot(PathBuf),
+    // when it was asked for, since the recording started
+    ClipFrame(Duration),
+    // the still half of a comparison, see compare.rs
+    Compare,
+}
+
+impl ReadbackPurpose {
+    // A clip can miss a frame, a screenshot should always happen.
+    fn droppable(&self) -> bool {
+        matches!(self, ReadbackPurpose::ClipFrame(_))
+    }
+}
+
+// Copies of the canvas wanted off the GPU once this frame is drawn.
+#[derive(Resource, Default)]
+pub struct ReadbackRequests(Vec<ReadbackPurpose>);
+
+impl ReadbackRequests {
+    pub fn request(&mut self, purpose: ReadbackPurpose) {
+        self.0.push(purpose);
+    }
+}
+
+// The canvas as it was drawn, tightly packed rows in its own format, some frames after it was asked
+// for.
+#[derive(Event)]
+pub struct CanvasFrame {
+    pub purpose: ReadbackPurpose,
+    pub size: UVec2,
+    pub format: TextureFormat,
+    pub data: Vec<u8>,
+}
+
+// The render world's side, along with the canvas they're of.
+#[derive(Resource, Default)]
+struct ExtractedRequests {
+    canvas: Option<Handle<Image>>,
+    purposes: Vec<ReadbackPurpose>,
+}
+
+// The canvas's pixels in a buffer the CPU can read, rows padded out the way the GPU copies them.
+struct Readback {
+    purpose: ReadbackPurpose,
+    buffer: Buffer,
+    size: UVec2,
+    padded_row: usize,
+    format: TextureFormat,
+}
+
+// Mapping finishes on the render thread, whenever the GPU gets to it.
+#[derive(Resource)]
+struct ReadbackSender(Sender<Readback>);
+
+#[derive(Resource)]
+struct Readbacks(Mutex<Receiver<Readback>>);
+
+// How many copies are on their way back, shared between the two worlds.
+#[derive(Resource, Clone, Default)]
+struct InFlight(Arc<AtomicUsize>);
+
+// Taken rather than copied, so each request is only captured once.
+fn extract_requests(mut main_world: ResMut<MainWorld>, mut requests: ResMut<ExtractedRequests>) {
+    requests
+        .purposes
+        .append(&mut main_world.resource_mut::<ReadbackRequests>().0);
+    requests.canvas = main_world
+        .get_resource::<CanvasImage>()
+        .map(|canvas| canvas.0.clone());
+}
+
+fn copy_canvas(
+    mut requests: ResMut<ExtractedRequests>,
+    images: Res<RenderAssets<GpuImage>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    sender: Res<ReadbackSender>,
+    in_flight: Res<InFlight>,
+) {
+    let ExtractedRequests { canvas, purposes } = &mut *requests;
+    for purpose in purposes.drain(..) {
+        let Some(image) = canvas.as_ref().and_then(|canvas| images.get(canvas)) else {
+            warn!("the canvas isn't on the GPU yet, skipping a copy of it");
+            continue;
+        };
+        if purpose.droppable() && in_flight.0.load(Ordering::Relaxed) >= MAX_IN_FLIGHT {
+            continue;
+        }
+
+        let size = image.size;
+        let padded_row = RenderDevice::align_copy_bytes_per_row(
+            size.x as usize * image.texture_format.pixel_size(),
+        );
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("canvas_readback_buffer"),
+            size: (padded_row * size.y as usize) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("canvas_readback_encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            image.texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row as u32),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+        render_queue.submit([encoder.finish()]);
+        in_flight.0.fetch_add(1, Ordering::Relaxed);
+
+        let sender = sender.0.clone();
+        let in_flight = in_flight.clone();
+        let readback = Readback {
+            purpose,
+            buffer: buffer.clone(),
+            size,
+            padded_row,
+            format: image.texture_format,
+        };
+        // polled along with every later submit, there's no waiting on it here
+        buffer
+            .slice(..)
+            .map_async(MapMode::Read, move |result| match result {
+                Ok(()) => {
+                    // the receiver only goes away with the app
+                    let _ = sender.send(readback);
+                }
+                Err(error) => {
+                    in_flight.0.fetch_sub(1, Ordering::Relaxed);
+                    error!("couldn't read the canvas back: {error}");
+                }
+            });
+    }
+}
+
+fn receive_readbacks(
+    readbacks: Res<Readbacks>,
+    in_flight: Res<InFlight>,
+    mut frames: EventWriter<CanvasFrame>,
+) {
+    let Ok(receiver) = readbacks.0.lock() else {
+        return;
+    };
+
+    for readback in receiver.try_iter() {
+        let row = readback.size.x as usize * readback.format.pixel_size();
+        let data: Vec<u8> = readback
+            .buffer
+            .slice(..)
+            .get_mapped_range()
+            .chunks(readback.padded_row)
+            .flat_map(|padded| &padded[..row])
+            .copied()
+            .collect();
+        readback.buffer.unmap();
+        in_flight.0.fetch_sub(1, Ordering::Relaxed);
+
+        frames.send(CanvasFrame {
+            purpose: readback.purpose,
+            size: readback.size,
+            format: readback.format,
+            data,
+        });
+    }
+}
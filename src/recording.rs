@@ -0,0 +1,248 @@
+use bevy::{
+    prelude::*,
+    render::{render_resource::TextureFormat, view::RenderLayers},
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+use chrono::Local;
+use std::{collections::VecDeque, env, fs::File, io::BufWriter, path::PathBuf, time::Duration};
+
+use crate::{
+    input::InputState,
+    keybindings::Action,
+    readback::{CanvasFrame, ReadbackPurpose, ReadbackRequests},
+    UiCamera, UI_LAYER,
+};
+
+// about ten seconds at every other frame of 60, the oldest go first past that
+const MAX_CLIP_FRAMES: usize = 300;
+// a big canvas fills memory long before the frame cap
+const MAX_CLIP_BYTES: usize = 512 * 1024 * 1024;
+// for the last frame, which has nothing after it to measure against
+const LAST_FRAME_DELAY: Duration = Duration::from_millis(33);
+// logical pixels, and in from the window's corner
+const INDICATOR_SIZE: f32 = 12.0;
+const INDICATOR_MARGIN: f32 = 12.0;
+
+pub struct RecordingPlugin;
+
+impl Plugin for RecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SavingClips>().add_systems(
+            Update,
+            (
+                toggle_recording,
+                (request_clip_frames, collect_clip_frames, show_indicator)
+                    .run_if(resource_exists::<Recording>),
+                finish_clips,
+            )
+                .chain(),
+        );
+    }
+}
+
+// A clip on its way in, the canvas read back every other frame.
+#[derive(Resource, Default)]
+struct Recording {
+    frames: VecDeque<ClipFrame>,
+    // when it started, in real time
+    started: Duration,
+    odd_frame: bool,
+}
+
+struct ClipFrame {
+    at: Duration,
+    size: UVec2,
+    format: TextureFormat,
+    data: Vec<u8>,
+}
+
+// Encoding takes a while, so it happens off the main thread.
+#[derive(Resource, Default)]
+struct SavingClips(Vec<Task<Result<PathBuf, String>>>);
+
+// The dot in the corner while recording. It's on the UI's layer, so it stays out of the clip.
+#[derive(Component)]
+struct RecordingIndicator;
+
+fn toggle_recording(
+    mut commands: Commands,
+    input: Res<InputState>,
+    time: Res<Time<Real>>,
+    recording: Option<ResMut<Recording>>,
+    mut saving: ResMut<SavingClips>,
+    indicators: Query<Entity, With<RecordingIndicator>>,
+) {
+    if !input.just_did(Action::ToggleRecording) {
+        return;
+    }
+
+    let Some(mut recording) = recording else {
+        info!("recording a clip");
+        commands.insert_resource(Recording {
+            started: time.elapsed(),
+            ..default()
+        });
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.9, 0.1, 0.1),
+                    custom_size: Some(Vec2::splat(INDICATOR_SIZE)),
+                    ..default()
+                },
+                ..default()
+            },
+            RenderLayers::layer(UI_LAYER),
+            RecordingIndicator,
+        ));
+        return;
+    };
+
+    commands.remove_resource::<Recording>();
+    for indicator in &indicators {
+        commands.entity(indicator).despawn();
+    }
+
+    let frames: Vec<_> = recording.frames.drain(..).collect();
+    if frames.is_empty() {
+        warn!("stopped recording before any frames came in, nothing to save");
+        return;
+    }
+    let file_name = Local::now()
+        .format("mckenzie-%Y%m%d-%H%M%S.png")
+        .to_string();
+    let dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+        .unwrap_or_default();
+    info!("saving a clip of {} frames", frames.len());
+    saving.0.push(
+        AsyncComputeTaskPool::get().spawn(async move { save_apng(frames, dir.join(file_name)) }),
+    );
+}
+
+fn request_clip_frames(
+    time: Res<Time<Real>>,
+    mut recording: ResMut<Recording>,
+    mut readbacks: ResMut<ReadbackRequests>,
+) {
+    recording.odd_frame = !recording.odd_frame;
+    if recording.odd_frame {
+        readbacks.request(ReadbackPurpose::ClipFrame(
+            time.elapsed() - recording.started,
+        ));
+    }
+}
+
+fn collect_clip_frames(mut frames: EventReader<CanvasFrame>, mut recording: ResMut<Recording>) {
+    for frame in frames.read() {
+        let ReadbackPurpose::ClipFrame(at) = frame.purpose else {
+            continue;
+        };
+
+        // every frame of an animated PNG is the same size
+        if recording
+            .frames
+            .front()
+            .is_some_and(|first| first.size != frame.size)
+        {
+            warn!("the canvas changed size, starting the clip over");
+            recording.frames.clear();
+        }
+
+        let frame_bytes = frame.data.len().max(1);
+        let cap = MAX_CLIP_FRAMES.min(MAX_CLIP_BYTES / frame_bytes).max(1);
+        while recording.frames.len() >= cap {
+            recording.frames.pop_front();
+        }
+        recording.frames.push_back(ClipFrame {
+            at,
+            size: frame.size,
+            format: frame.format,
+            data: frame.data.clone(),
+        });
+    }
+}
+
+// Blinks in the window's top left corner.
+fn show_indicator(
+    time: Res<Time<Real>>,
+    cameras: Query<&OrthographicProjection, With<UiCamera>>,
+    mut indicators: Query<(&mut Transform, &mut Visibility), With<RecordingIndicator>>,
+) {
+    let Ok(projection) = cameras.get_single() else {
+        return;
+    };
+    let corner = Vec2::new(projection.area.min.x, projection.area.max.y)
+        + Vec2::new(1.0, -1.0) * (INDICATOR_MARGIN + INDICATOR_SIZE * 0.5);
+    let shown = time.elapsed_seconds().fract() < 0.5;
+
+    for (mut transform, mut visibility) in &mut indicators {
+        transform.translation = corner.extend(0.0);
+        *visibility = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// An animated PNG, each frame held for as long as it took to come in. Rows are RGBA, the canvas's
+// BGRA gets swapped on the way.
+fn save_apng(frames: Vec<ClipFrame>, path: PathBuf) -> Result<PathBuf, String> {
+    let size = frames[0].size;
+    let file = File::create(&path).map_err(|error| format!("couldn't create {path:?}: {error}"))?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), size.x, size.y);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, 0)
+        .map_err(|error| error.to_string())?;
+    let mut writer = encoder.write_header().map_err(|error| error.to_string())?;
+
+    let delays: Vec<Duration> = frames
+        .windows(2)
+        .map(|pair| pair[1].at.saturating_sub(pair[0].at))
+        .chain([LAST_FRAME_DELAY])
+        .collect();
+    for (frame, delay) in frames.into_iter().zip(delays) {
+        let millis = delay.as_millis().clamp(1, u16::MAX as u128) as u16;
+        writer
+            .set_frame_delay(millis, 1000)
+            .map_err(|error| error.to_string())?;
+
+        let mut data = frame.data;
+        if matches!(
+            frame.format,
+            TextureFormat::Bgra8UnormSrgb | TextureFormat::Bgra8Unorm
+        ) {
+            for pixel in data.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+        writer
+            .write_image_data(&data)
+            .map_err(|error| format!("couldn't write {path:?}: {error}"))?;
+    }
+    writer
+        .finish()
+        .map_err(|error| format!("couldn't write {path:?}: {error}"))?;
+
+    Ok(path)
+}
+
+fn finish_clips(mut saving: ResMut<SavingClips>) {
+    saving
+        .0
+        .retain_mut(|task| match block_on(future::poll_once(task)) {
+            Some(Ok(path)) => {
+                info!("saved a clip to {path:?}");
+                false
+            }
+            Some(Err(error)) => {
+                error!("couldn't save the clip: {error}");
+                false
+            }
+            None => true,
+        });
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::{menu::MenuState, pause::AppMode, CanvasPointer, Cube, CUBE_HIT_RADIUS};
+
+pub struct RepositionPlugin;
+
+impl Plugin for RepositionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MiddleDrag>().add_systems(
+            Update,
+            drag_cube_home
+                .run_if(in_state(AppMode::Running))
+                .run_if(in_state(MenuState::Closed)),
+        );
+    }
+}
+
+// The middle button drag sliding the cube around the canvas.
+#[derive(Resource, Default)]
+pub struct MiddleDrag {
+    // from the point under the cursor to the cube's home, on the cube's plane
+    grab_offset: Option<Vec3>,
+    // window pixels the cursor has travelled since the button went down
+    pub moved: f32,
+}
+
+pub fn drag_cube_home(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut cursor_moves: EventReader<CursorMoved>,
+    pointer: CanvasPointer,
+    mut drag: ResMut<MiddleDrag>,
+    mut query_cube: Query<(&mut Transform, &mut Cube)>,
+) {
+    let (mut cube_transform, mut cube_prop) = query_cube.single_mut();
+    let (camera, camera_transform) = pointer.cameras.single();
+
+    if mouse.just_pressed(MouseButton::Middle) {
+        drag.grab_offset = pointer
+            .cursor()
+            .and_then(|cursor| on_cube_plane(camera, camera_transform, cursor, cube_prop.home.z))
+            .map(|hit| cube_prop.home - hit);
+        drag.moved = 0.0;
+    }
+    if !mouse.pressed(MouseButton::Middle) {
+        drag.grab_offset = None;
+        return;
+    }
+
+    drag.moved += cursor_moves
+        .read()
+        .filter_map(|moved| moved.delta)
+        .map(Vec2::length)
+        .sum::<f32>();
+
+    let (Some(offset), Some(cursor)) = (drag.grab_offset, pointer.cursor()) else {
+        return;
+    };
+    let z = cube_prop.home.z;
+    let (Some(hit), Some(top_left), Some(bottom_right)) = (
+        on_cube_plane(camera, camera_transform, cursor, z),
+        on_cube_plane(camera, camera_transform, Vec2::ZERO, z),
+        on_cube_plane(camera, camera_transform, pointer.canvas.size(), z),
+    ) else {
+        return;
+    };
+
+    // keeps the whole cube on the canvas
+    let target = hit + offset;
+    let home = Vec3::new(
+        target.x.clamp(
+            top_left.x + CUBE_HIT_RADIUS,
+            bottom_right.x - CUBE_HIT_RADIUS,
+        ),
+        target.y.clamp(
+            bottom_right.y + CUBE_HIT_RADIUS,
+            top_left.y - CUBE_HIT_RADIUS,
+        ),
+        z,
+    );
+
+    cube_transform.translation += home - cube_prop.home;
+    cube_prop.home = home;
+}
+
+// Where a canvas pixel lands on the plane the cube rests in, at depth `z`.
+fn on_cube_plane(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    canvas: Vec2,
+    z: f32,
+) -> Option<Vec3> {
+    let ray = camera.viewport_to_world(camera_transform, canvas)?;
+    let distance = ray.intersect_plane(Vec3::new(0.0, 0.0, z), InfinitePlane3d::new(Vec3::Z))?;
+
+    Some(ray.get_point(distance))
+}
@@ -0,0 +1,121 @@
+use bevy::{
+    asset::load_internal_asset,
+    pbr::{ExtendedMaterial, MaterialExtension},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+    utils::HashMap,
+};
+
+use crate::{fade::smoothstep, skin::CubeParts, CubeState};
+
+pub const RIM_SHADER: Handle<Shader> =
+    Handle::weak_from_u128(0x6d63_6b65_6e7a_6965_7269_6d5f_7368_6472);
+// cool blue, in linear light since it's added after the lighting
+const RIM_COLOR: Vec3 = Vec3::new(0.25, 0.45, 1.0);
+// how long the rim takes to come in on Sad and go again after
+const RIM_FADE_SECS: f32 = 1.0;
+
+pub struct RimPlugin;
+
+impl Plugin for RimPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            RIM_SHADER,
+            "../assets/shaders/rim.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_plugins(MaterialPlugin::<CubeMaterial>::default())
+            .init_resource::<RimFade>()
+            .add_systems(Update, (extend_cube_materials, fade_rim).chain());
+    }
+}
+
+// What the cube is drawn with, the glb's own material plus the rim.
+pub type CubeMaterial = ExtendedMaterial<StandardMaterial, Rim>;
+
+// A fresnel glow around the cube's silhouette, strongest where the faces turn away from the camera.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct Rim {
+    // rgb is the color, a how much of it shows
+    #[uniform(100)]
+    rim: Vec4,
+}
+
+impl MaterialExtension for Rim {
+    fn fragment_shader() -> ShaderRef {
+        RIM_SHADER.into()
+    }
+}
+
+// 0 without any rim, 1 with all of it.
+#[derive(Resource, Default)]
+struct RimFade(f32);
+
+impl RimFade {
+    fn uniform(&self) -> Vec4 {
+        RIM_COLOR.extend(smoothstep(self.0))
+    }
+}
+
+// Keeps swapping the glb's own materials over as they show up. Meshes sharing a material keep
+// sharing its replacement.
+fn extend_cube_materials(
+    mut commands: Commands,
+    fade: Res<RimFade>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut cube_materials: ResMut<Assets<CubeMaterial>>,
+    mut replacements: Local<HashMap<AssetId<StandardMaterial>, Handle<CubeMaterial>>>,
+    parts: CubeParts<&Handle<StandardMaterial>>,
+) {
+    for (entity, handle) in parts.iter() {
+        let Some(material) = standard_materials.get(handle) else {
+            continue;
+        };
+
+        let replacement = replacements
+            .entry(handle.id())
+            .or_insert_with(|| {
+                cube_materials.add(CubeMaterial {
+                    base: material.clone(),
+                    extension: Rim {
+                        rim: fade.uniform(),
+                    },
+                })
+            })
+            .clone();
+        commands
+            .entity(entity)
+            .remove::<Handle<StandardMaterial>>()
+            .insert(replacement);
+    }
+}
+
+fn fade_rim(
+    time: Res<Time>,
+    state: Res<State<CubeState>>,
+    mut fade: ResMut<RimFade>,
+    mut materials: ResMut<Assets<CubeMaterial>>,
+) {
+    let target = if *state.get() == CubeState::Sad {
+        1.0
+    } else {
+        0.0
+    };
+    if fade.0 == target {
+        return;
+    }
+
+    let step = time.delta_seconds() / RIM_FADE_SECS;
+    fade.0 = if target > fade.0 {
+        (fade.0 + step).min(target)
+    } else {
+        (fade.0 - step).max(target)
+    };
+
+    let rim = fade.uniform();
+    for (_, material) in materials.iter_mut() {
+        material.extension.rim = rim;
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::{canvas_material::CanvasMaterial, settings::Settings, CubeState};
+
+// how long a flash of chromatic aberration takes to die away
+const ABERRATION_SECS: f32 = 0.2;
+// canvas pixels red and blue are pushed apart at the edges at the start of a flash
+const MAX_ABERRATION: f32 = 2.0;
+
+pub struct ScreenEffectsPlugin;
+
+impl Plugin for ScreenEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenEffects>()
+            .add_systems(OnEnter(CubeState::Angry), flash_on_anger)
+            .add_systems(Update, fade_aberration);
+    }
+}
+
+// Quick flashes over the whole canvas that anything can set off.
+#[derive(Resource, Default)]
+pub struct ScreenEffects {
+    // 1 just after a kick, down to 0 once it's gone
+    aberration: f32,
+}
+
+impl ScreenEffects {
+    // A kick partway through a flash starts it over from full rather than adding to it, so a burst
+    // of pokes never gets any stronger than one.
+    pub fn kick_aberration(&mut self) {
+        self.aberration = 1.0;
+    }
+}
+
+fn flash_on_anger(mut effects: ResMut<ScreenEffects>) {
+    effects.kick_aberration();
+}
+
+fn fade_aberration(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut effects: ResMut<ScreenEffects>,
+    canvases: Query<&Handle<CanvasMaterial>>,
+    mut materials: ResMut<Assets<CanvasMaterial>>,
+) {
+    if effects.aberration > 0.0 {
+        effects.aberration = (effects.aberration - time.delta_seconds() / ABERRATION_SECS).max(0.0);
+    }
+
+    // eased out, so most of the split is in the first few frames
+    let pixels = if settings.aberration_off {
+        0.0
+    } else {
+        MAX_ABERRATION * effects.aberration * effects.aberration
+    };
+    for handle in &canvases {
+        if materials
+            .get(handle)
+            .is_some_and(|material| material.aberration() != pixels)
+        {
+            if let Some(material) = materials.get_mut(handle) {
+                material.set_aberration(pixels);
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::RenderLayers,
+    },
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+use chrono::Local;
+use std::{fs, path::PathBuf};
+
+use crate::{
+    input::InputState,
+    keybindings::Action,
+    readback::{CanvasFrame, ReadbackPurpose, ReadbackRequests},
+    UiCamera, UI_LAYER,
+};
+
+const SCREENSHOT_DIR: &str = "screenshots";
+const TOAST_SECS: f32 = 1.5;
+// logical pixels in from the top of the window
+const TOAST_MARGIN: f32 = 24.0;
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SavingScreenshots>().add_systems(
+            Update,
+            (
+                request_screenshot,
+                save_screenshots,
+                finish_saves,
+                fade_toasts,
+            )
+                .chain(),
+        );
+    }
+}
+
+// Encoding and writing happen off the main thread so a screenshot doesn't hitch the cube.
+#[derive(Resource, Default)]
+struct SavingScreenshots(Vec<Task<Result<PathBuf, String>>>);
+
+#[derive(Component)]
+struct Toast(Timer);
+
+fn request_screenshot(input: Res<InputState>, mut readbacks: ResMut<ReadbackRequests>) {
+    if !input.just_did(Action::Screenshot) {
+        return;
+    }
+
+    let file_name = Local::now()
+        .format("mckenzie-%Y%m%d-%H%M%S.png")
+        .to_string();
+    readbacks.request(ReadbackPurpose::Screenshot(
+        PathBuf::from(SCREENSHOT_DIR).join(file_name),
+    ));
+}
+
+fn save_screenshots(mut frames: EventReader<CanvasFrame>, mut saving: ResMut<SavingScreenshots>) {
+    for frame in frames.read() {
+        let ReadbackPurpose::Screenshot(path) = &frame.purpose else {
+            continue;
+        };
+
+        let (data, size, format, path) =
+            (frame.data.clone(), frame.size, frame.format, path.clone());
+        saving
+            .0
+            .push(IoTaskPool::get().spawn(async move { save_png(data, size, format, path) }));
+    }
+}
+
+fn save_png(
+    data: Vec<u8>,
+    size: UVec2,
+    format: TextureFormat,
+    path: PathBuf,
+) -> Result<PathBuf, String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| format!("couldn't create {dir:?}: {error}"))?;
+    }
+
+    let image = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        RenderAssetUsages::MAIN_WORLD,
+    );
+    // keeps the alpha, pet mode's shots come out see-through around the cube
+    image
+        .try_into_dynamic()
+        .map_err(|error| error.to_string())?
+        .to_rgba8()
+        .save(&path)
+        .map_err(|error| format!("couldn't write {path:?}: {error}"))?;
+
+    Ok(path)
+}
+
+fn finish_saves(
+    mut commands: Commands,
+    mut saving: ResMut<SavingScreenshots>,
+    cameras: Query<&OrthographicProjection, With<UiCamera>>,
+) {
+    let mut finished = Vec::new();
+    saving
+        .0
+        .retain_mut(|task| match block_on(future::poll_once(task)) {
+            Some(result) => {
+                finished.push(result);
+                false
+            }
+            None => true,
+        });
+
+    for result in finished {
+        let text = match result {
+            Ok(path) => {
+                info!("saved a screenshot to {path:?}");
+                "saved!"
+            }
+            Err(error) => {
+                error!("couldn't save the screenshot: {error}");
+                "couldn't save"
+            }
+        };
+
+        // on the UI's layer, so it isn't in the next screenshot
+        let Ok(projection) = cameras.get_single() else {
+            continue;
+        };
+        let top = Vec2::new(0.0, projection.area.max.y - TOAST_MARGIN);
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                // at the window's resolution, not blown up with the canvas
+                transform: Transform::from_translation(top.extend(0.0)),
+                ..default()
+            },
+            RenderLayers::layer(UI_LAYER),
+            Toast(Timer::from_seconds(TOAST_SECS, TimerMode::Once)),
+        ));
+    }
+}
+
+fn fade_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut Toast, &mut Text)>,
+) {
+    for (entity, mut toast, mut text) in &mut toasts {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let alpha = (toast.0.remaining_secs() / (TOAST_SECS * 0.5)).min(1.0);
+        for section in &mut text.sections {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
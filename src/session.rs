@@ -0,0 +1,101 @@
+use bevy::{app::AppExit, prelude::*};
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{mood::Mood, shutdown::SaveOnExit};
+
+// mood lost for every hour the app stays closed
+const MOOD_DECAY_PER_HOUR: f32 = 0.05;
+// gone for longer than this and the cube starts out sad
+const SAD_AFTER_HOURS: u64 = 4;
+
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LastSession::load())
+            .add_systems(Startup, restore_mood)
+            // AppExit goes out in PostUpdate when the window closes
+            .add_systems(Last, save_session.in_set(SaveOnExit));
+    }
+}
+
+// What the cube remembers from the last time the app was running.
+#[derive(Resource, Default)]
+pub struct LastSession {
+    closed_for: Duration,
+    mood: Option<f32>,
+}
+
+impl LastSession {
+    fn path() -> Option<PathBuf> {
+        Some(dirs::data_dir()?.join("mckenzie-bevy").join("session"))
+    }
+
+    // A missing or unreadable file just means a fresh, happy start.
+    fn load() -> Self {
+        let Some(contents) = Self::path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return LastSession::default();
+        };
+
+        let parsed = contents
+            .trim()
+            .split_once(' ')
+            .and_then(|(closed_at, mood)| {
+                Some((closed_at.parse::<u64>().ok()?, mood.parse::<f32>().ok()?))
+            });
+
+        let Some((closed_at, mood)) = parsed else {
+            warn!("ignoring a corrupt session file");
+            return LastSession::default();
+        };
+
+        let closed_for = SystemTime::now()
+            .duration_since(UNIX_EPOCH + Duration::from_secs(closed_at))
+            .unwrap_or_default();
+
+        LastSession {
+            closed_for,
+            mood: Some(mood.clamp(0.0, 1.0)),
+        }
+    }
+
+    pub fn missed_for_long(&self) -> bool {
+        self.closed_for > Duration::from_secs(SAD_AFTER_HOURS * 60 * 60)
+    }
+}
+
+fn restore_mood(last_session: Res<LastSession>, mut mood: ResMut<Mood>) {
+    let Some(last_mood) = last_session.mood else {
+        return;
+    };
+
+    let hours = last_session.closed_for.as_secs_f32() / 3600.0;
+    mood.0 = (last_mood - MOOD_DECAY_PER_HOUR * hours).max(0.0);
+}
+
+fn save_session(mut exits: EventReader<AppExit>, mood: Res<Mood>) {
+    if exits.read().last().is_none() {
+        return;
+    }
+    let Some(path) = LastSession::path() else {
+        return;
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let saved = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, format!("{now} {}\n", mood.0)));
+
+    if let Err(error) = saved {
+        warn!("couldn't save the session to {path:?}: {error}");
+    }
+}
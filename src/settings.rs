@@ -0,0 +1,151 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+use crate::{
+    accessibility::ColorPreset, ambient_occlusion::AmbientOcclusion, background::Background,
+    canvas::CanvasScaling, canvas_material::Dither, low_power::LowPower, msaa::Multisampling,
+    shadows::ShadowQuality, shutdown::SaveOnExit, time_of_day::DayCycle, tonemapping::Tonemap,
+    CubeState,
+};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // main loads them itself, the window has to be built from them before any plugin runs
+        app.add_systems(
+            Last,
+            save_settings
+                .in_set(SaveOnExit)
+                .run_if(resource_changed::<Settings>),
+        );
+    }
+}
+
+// Choices made while running that should still hold next time, written back whenever one changes.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub always_on_top: bool,
+    // let the window take any size and letterbox the canvas, rather than snapping to whole multiples
+    pub freeform_resize: bool,
+    // keep the window still when the cube gets angry
+    pub skip_window_shake: bool,
+    // how long the cube takes to catch up with the cursor, 0.08 seconds when missing
+    pub look_smoothing_secs: Option<f32>,
+    // draw as fast as the GPU allows instead of waiting on the display
+    pub vsync_off: bool,
+    // frames per second to stay under, uncapped when missing
+    pub fps_cap: Option<u32>,
+    // half the canvas, one light and 30 frames per second, always or only while on battery
+    pub low_power: LowPower,
+    // no glow around the lights, for GPUs that struggle with it
+    pub bloom_off: bool,
+    // how the bright HDR light is brought down to the canvas's colors
+    pub tonemapping: Tonemap,
+    pub shadows: ShadowQuality,
+    pub ambient_occlusion: AmbientOcclusion,
+    // smooth the stair steps along the cube's edges before the canvas is scaled up
+    pub fxaa: bool,
+    // multisampling on the canvas camera, held off while ambient occlusion or the outline is on
+    pub multisampling: Multisampling,
+    pub background: Background,
+    // the hours the lights and background follow, or one to stay at
+    pub day_cycle: DayCycle,
+    // hex colors the plain background fades to in each state, on top of the built-in ones
+    pub clear_colors: HashMap<CubeState, String>,
+    // no dark line around the cube
+    pub outline_off: bool,
+    // in canvas pixels, 1 when missing
+    pub outline_width: Option<u32>,
+    // hex colors of the line around the cube in each state, on top of the built-in ones
+    pub outline_colors: HashMap<CubeState, String>,
+    // the color tables the background, lights and outline pick from, with a state badge for high
+    // contrast
+    pub colors: ColorPreset,
+    // scanlines, curved glass and dark corners over the canvas
+    pub crt: bool,
+    // how dark every other canvas row is, 0 for none, with or without the CRT look
+    pub scanlines: f32,
+    // no flash of color fringing when the cube is poked, startled or angered
+    pub aberration_off: bool,
+    // ordered dithering over the canvas, none when missing
+    pub dither: Option<Dither>,
+    // the palette the canvas is limited to, "pico-8" or one of the files below, every color when
+    // missing
+    pub palette: Option<String>,
+    // .pal, .hex or swatch strip .png palettes under assets/ for F5 to step through after PICO-8's
+    pub palettes: Vec<String>,
+    // canvas pixels across and down, 640x360 when missing
+    pub canvas_size: Option<UVec2>,
+    pub scaling: CanvasScaling,
+    // scale the canvas up smoothly even by whole multiples, rather than keeping its pixels square
+    pub linear_upscale: bool,
+    // let the canvas sit wherever the middle of the window falls, rather than nudging it onto whole
+    // screen pixels
+    pub smooth_canvas: bool,
+    // hex color of the bars around the canvas when it doesn't fill the window, the window's clear
+    // color when missing
+    pub letterbox_color: Option<String>,
+    // tile a patterned frame over those bars, tinted by their color
+    pub letterbox_frame: bool,
+    // where the window was left, missing until it's first moved or resized
+    pub window: Option<WindowPlacement>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WindowPlacement {
+    // outer corner in physical pixels
+    pub position: IVec2,
+    // logical size while windowed, kept as it was while fullscreen or a pet, and missing if it's
+    // only ever been either
+    pub size: Option<Vec2>,
+    pub monitor: Option<String>,
+    pub fullscreen: bool,
+}
+
+impl Settings {
+    fn path() -> Option<PathBuf> {
+        Some(
+            dirs::config_dir()?
+                .join("mckenzie-bevy")
+                .join("settings.ron"),
+        )
+    }
+
+    // A missing file is a first run, a broken one is reported and replaced on the next save.
+    pub fn load() -> Self {
+        let Some(contents) = Self::path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return Settings::default();
+        };
+
+        ron::from_str(&contents).unwrap_or_else(|error| {
+            warn!("ignoring unreadable settings: {error}");
+            Settings::default()
+        })
+    }
+}
+
+pub fn save_settings(settings: Res<Settings>) {
+    // just loaded, nothing new to write
+    if settings.is_added() {
+        return;
+    }
+    let Some(path) = Settings::path() else {
+        return;
+    };
+
+    let saved = ron::ser::to_string_pretty(&*settings, Default::default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| {
+            path.parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&path, contents))
+                .map_err(|error| error.to_string())
+        });
+
+    if let Err(error) = saved {
+        warn!("couldn't save the settings to {path:?}: {error}");
+    }
+}
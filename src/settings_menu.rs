@@ -0,0 +1,379 @@
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use crate::{
+    input::InputState, keybindings::Action, menu::MenuState, settings::Settings, CanvasZoom,
+    MainCamera,
+};
+
+const PANEL_COLOR: Color = Color::srgb(0.12, 0.12, 0.15);
+const TRACK_COLOR: Color = Color::srgb(0.25, 0.25, 0.32);
+const FILL_COLOR: Color = Color::srgb(0.65, 0.65, 0.8);
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+// in canvas pixels, see CanvasZoom::ui_pixel
+const TRACK_WIDTH: f32 = 60.0;
+const TRACK_HEIGHT: f32 = 4.0;
+const CHECKBOX_SIZE: f32 = 6.0;
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Settings), open_settings)
+            .add_systems(
+                Update,
+                (
+                    drag_sliders,
+                    show_sliders,
+                    flip_toggles,
+                    show_toggles,
+                    step_choices,
+                    show_choices,
+                    close_settings,
+                )
+                    .chain()
+                    .run_if(in_state(MenuState::Settings)),
+            )
+            .add_systems(OnExit(MenuState::Settings), despawn_settings);
+    }
+}
+
+// Opened from the context menu's Settings entry, where it was right clicked.
+#[derive(Component)]
+struct SettingsPanel;
+
+// A value from 0 to 1 in the settings, adjusted live by dragging along its track.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+enum Slider {
+    Scanlines,
+}
+
+impl Slider {
+    const ALL: [Slider; 1] = [Slider::Scanlines];
+
+    fn label(self) -> &'static str {
+        match self {
+            Slider::Scanlines => "Scanlines",
+        }
+    }
+
+    fn get(self, settings: &Settings) -> f32 {
+        match self {
+            Slider::Scanlines => settings.scanlines,
+        }
+    }
+
+    fn set(self, settings: &mut Settings, value: f32) {
+        match self {
+            Slider::Scanlines => settings.scanlines = value,
+        }
+    }
+}
+
+// The filled part of a slider's track, as wide as its value.
+#[derive(Component)]
+struct SliderFill(Slider);
+
+// Something in the settings that's either on or off, flipped by clicking its row.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+enum Toggle {
+    Fxaa,
+    LinearUpscale,
+}
+
+impl Toggle {
+    const ALL: [Toggle; 2] = [Toggle::Fxaa, Toggle::LinearUpscale];
+
+    fn label(self) -> &'static str {
+        match self {
+            Toggle::Fxaa => "Smooth edges",
+            Toggle::LinearUpscale => "Smooth upscale",
+        }
+    }
+
+    fn get(self, settings: &Settings) -> bool {
+        match self {
+            Toggle::Fxaa => settings.fxaa,
+            Toggle::LinearUpscale => settings.linear_upscale,
+        }
+    }
+
+    fn set(self, settings: &mut Settings, on: bool) {
+        match self {
+            Toggle::Fxaa => settings.fxaa = on,
+            Toggle::LinearUpscale => settings.linear_upscale = on,
+        }
+    }
+}
+
+// The box at the start of a toggle's row, filled in while it's on.
+#[derive(Component)]
+struct ToggleBox(Toggle);
+
+// Something in the settings with a few options, stepped on to the next by clicking its row.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+enum Choice {
+    Multisampling,
+    Tonemapping,
+    Colors,
+    LowPower,
+}
+
+impl Choice {
+    const ALL: [Choice; 4] = [
+        Choice::Multisampling,
+        Choice::Tonemapping,
+        Choice::Colors,
+        Choice::LowPower,
+    ];
+
+    fn text(self, settings: &Settings) -> String {
+        match self {
+            Choice::Multisampling => format!("Multisampling: {}", settings.multisampling.label()),
+            Choice::Tonemapping => format!("Tonemapping: {}", settings.tonemapping.label()),
+            Choice::Colors => format!("Colors: {}", settings.colors.label()),
+            Choice::LowPower => format!("Low power: {}", settings.low_power.label()),
+        }
+    }
+
+    fn step(self, settings: &mut Settings) {
+        match self {
+            Choice::Multisampling => settings.multisampling = settings.multisampling.next(),
+            Choice::Tonemapping => settings.tonemapping = settings.tonemapping.next(),
+            Choice::Colors => settings.colors = settings.colors.next(),
+            Choice::LowPower => settings.low_power = settings.low_power.next(),
+        }
+    }
+}
+
+// The text on a choice's row, naming what it's on.
+#[derive(Component)]
+struct ChoiceText(Choice);
+
+fn toggle_color(on: bool) -> Color {
+    if on {
+        FILL_COLOR
+    } else {
+        TRACK_COLOR
+    }
+}
+
+fn open_settings(
+    mut commands: Commands,
+    input: Res<InputState>,
+    zoom: Res<CanvasZoom>,
+    settings: Res<Settings>,
+    cameras: Query<Entity, With<MainCamera>>,
+) {
+    let position = input.pointer.unwrap_or_default();
+    let pixel = zoom.ui_pixel();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(position.x),
+                    top: Val::Px(position.y),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.0 * pixel),
+                    padding: UiRect::all(Val::Px(4.0 * pixel)),
+                    ..default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..default()
+            },
+            // so a click on the panel itself doesn't count as clicking off it
+            Interaction::default(),
+            TargetCamera(cameras.single()),
+            SettingsPanel,
+        ))
+        .with_children(|panel| {
+            let text_style = TextStyle {
+                font_size: 8.0 * pixel,
+                color: TEXT_COLOR,
+                ..default()
+            };
+
+            for slider in Slider::ALL {
+                panel.spawn(TextBundle::from_section(slider.label(), text_style.clone()));
+                panel
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(TRACK_WIDTH * pixel),
+                                height: Val::Px(TRACK_HEIGHT * pixel),
+                                ..default()
+                            },
+                            background_color: TRACK_COLOR.into(),
+                            ..default()
+                        },
+                        RelativeCursorPosition::default(),
+                        slider,
+                    ))
+                    .with_children(|track| {
+                        track.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(slider.get(&settings) * 100.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                background_color: FILL_COLOR.into(),
+                                ..default()
+                            },
+                            SliderFill(slider),
+                        ));
+                    });
+            }
+
+            for toggle in Toggle::ALL {
+                panel
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(3.0 * pixel),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        toggle,
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Px(CHECKBOX_SIZE * pixel),
+                                    height: Val::Px(CHECKBOX_SIZE * pixel),
+                                    ..default()
+                                },
+                                background_color: toggle_color(toggle.get(&settings)).into(),
+                                ..default()
+                            },
+                            ToggleBox(toggle),
+                        ));
+                        row.spawn(TextBundle::from_section(toggle.label(), text_style.clone()));
+                    });
+            }
+
+            for choice in Choice::ALL {
+                panel
+                    .spawn((
+                        ButtonBundle {
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        choice,
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            TextBundle::from_section(choice.text(&settings), text_style.clone()),
+                            ChoiceText(choice),
+                        ));
+                    });
+            }
+        });
+}
+
+// Held down anywhere along the track, the value follows the cursor across it.
+fn drag_sliders(
+    mut settings: ResMut<Settings>,
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &Slider)>,
+) {
+    for (interaction, cursor, slider) in &sliders {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+
+        let value = position.x.clamp(0.0, 1.0);
+        if slider.get(&settings) != value {
+            slider.set(&mut settings, value);
+        }
+    }
+}
+
+fn show_sliders(settings: Res<Settings>, mut fills: Query<(&mut Style, &SliderFill)>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (mut style, fill) in &mut fills {
+        style.width = Val::Percent(fill.0.get(&settings).clamp(0.0, 1.0) * 100.0);
+    }
+}
+
+fn flip_toggles(
+    mut settings: ResMut<Settings>,
+    toggles: Query<(&Interaction, &Toggle), Changed<Interaction>>,
+) {
+    for (interaction, toggle) in &toggles {
+        if *interaction == Interaction::Pressed {
+            let on = toggle.get(&settings);
+            toggle.set(&mut settings, !on);
+        }
+    }
+}
+
+fn show_toggles(settings: Res<Settings>, mut boxes: Query<(&mut BackgroundColor, &ToggleBox)>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (mut color, toggle_box) in &mut boxes {
+        *color = toggle_color(toggle_box.0.get(&settings)).into();
+    }
+}
+
+fn step_choices(
+    mut settings: ResMut<Settings>,
+    choices: Query<(&Interaction, &Choice), Changed<Interaction>>,
+) {
+    for (interaction, choice) in &choices {
+        if *interaction == Interaction::Pressed {
+            choice.step(&mut settings);
+        }
+    }
+}
+
+fn show_choices(settings: Res<Settings>, mut texts: Query<(&mut Text, &ChoiceText)>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (mut text, choice_text) in &mut texts {
+        text.sections[0].value = choice_text.0.text(&settings);
+    }
+}
+
+// Clicking anywhere off the panel, or Escape, puts it away.
+fn close_settings(
+    input: Res<InputState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    panels: Query<&Interaction, With<SettingsPanel>>,
+    sliders: Query<&Interaction, With<Slider>>,
+    toggles: Query<&Interaction, With<Toggle>>,
+    choices: Query<&Interaction, With<Choice>>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    let clicked_off = mouse.any_just_pressed([MouseButton::Left, MouseButton::Right])
+        && panels
+            .iter()
+            .chain(&sliders)
+            .chain(&toggles)
+            .chain(&choices)
+            .all(|interaction| *interaction == Interaction::None);
+
+    if clicked_off || input.just_did(Action::Cancel) {
+        next_state.set(MenuState::Closed);
+    }
+}
+
+fn despawn_settings(mut commands: Commands, panels: Query<Entity, With<SettingsPanel>>) {
+    for panel in &panels {
+        commands.entity(panel).despawn_recursive();
+    }
+}
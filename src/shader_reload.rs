@@ -0,0 +1,225 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{
+            CachedPipelineState, PipelineCache, PipelineCacheError, PipelineDescriptor,
+        },
+        Render, RenderApp, RenderSet,
+    },
+    utils::HashSet,
+};
+use std::sync::{
+    mpsc::{self, Receiver, Sender},
+    Mutex,
+};
+
+use crate::{
+    canvas_material::CANVAS_SHADER, input::InputState, keybindings::Action,
+    outline::OUTLINE_SHADER, rim::RIM_SHADER,
+};
+
+// frames a new shader has to go without an error for it to count as working
+const CONFIRM_FRAMES: u32 = 120;
+// frames after a swap in which errors can still be about the shader before it
+const STALE_FRAMES: u32 = 3;
+
+pub struct ShaderReloadPlugin;
+
+impl Plugin for ShaderReloadPlugin {
+    fn build(&self, app: &mut App) {
+        if !cfg!(feature = "shader-reload") {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let watched = [
+            (CANVAS_SHADER, "shaders/canvas.wgsl"),
+            (OUTLINE_SHADER, "shaders/outline.wgsl"),
+            (RIM_SHADER, "shaders/rim.wgsl"),
+        ];
+        let ids: HashSet<_> = watched.iter().map(|(handle, _)| handle.id()).collect();
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        app.insert_resource(ShaderFiles(
+            watched
+                .map(|(embedded, path)| ShaderFile {
+                    embedded,
+                    loaded: asset_server.load(path),
+                    path,
+                    trial: None,
+                })
+                .into(),
+        ))
+        .insert_resource(ShaderErrors(Mutex::new(receiver)))
+        .add_systems(Update, (reload_shaders, keep_working_shaders).chain());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .insert_resource(WatchedShaders(ids))
+            .insert_resource(ShaderErrorSender(sender))
+            // once the pipelines have had their go at compiling for the frame
+            .add_systems(
+                Render,
+                report_shader_errors
+                    .after(RenderSet::Render)
+                    .before(RenderSet::Cleanup),
+            );
+    }
+}
+
+// Watching the asset folder is what turns a saved file into a reload, so it's on with the feature.
+pub fn asset_plugin() -> AssetPlugin {
+    AssetPlugin {
+        watch_for_changes_override: cfg!(feature = "shader-reload").then_some(true),
+        ..default()
+    }
+}
+
+// One of the embedded shaders, and its file in the asset folder that's copied over it each time the
+// asset server loads it.
+struct ShaderFile {
+    // what the materials and pipelines draw with
+    embedded: Handle<Shader>,
+    loaded: Handle<Shader>,
+    path: &'static str,
+    trial: Option<Trial>,
+}
+
+// A new shader that hasn't been seen to compile yet.
+struct Trial {
+    // what to go back to if it doesn't
+    working: Shader,
+    frames: u32,
+}
+
+impl ShaderFile {
+    fn swap_in(&mut self, shaders: &mut Assets<Shader>) {
+        let Some(source) = shaders.get(&self.loaded).cloned() else {
+            return;
+        };
+
+        // a reload partway through a trial still goes back to the last one that was confirmed
+        let working = match self.trial.take() {
+            Some(trial) => Some(trial.working),
+            None => shaders.get(&self.embedded).cloned(),
+        };
+        self.trial = working.map(|working| Trial { working, frames: 0 });
+        shaders.insert(&self.embedded, source);
+        info!("reloaded {}", self.path);
+    }
+}
+
+#[derive(Resource)]
+struct ShaderFiles(Vec<ShaderFile>);
+
+// The shaders that failed to compile, found on the render thread.
+#[derive(Resource)]
+struct ShaderErrorSender(Sender<AssetId<Shader>>);
+
+#[derive(Resource)]
+struct ShaderErrors(Mutex<Receiver<AssetId<Shader>>>);
+
+#[derive(Resource)]
+struct WatchedShaders(HashSet<AssetId<Shader>>);
+
+// Every file the asset server has loaded or reloaded since the last frame, or all of them again on
+// the key. Where there's nothing in the asset folder the embedded shaders stay.
+fn reload_shaders(
+    input: Res<InputState>,
+    mut events: EventReader<AssetEvent<Shader>>,
+    asset_server: Res<AssetServer>,
+    mut shader_files: ResMut<ShaderFiles>,
+    mut shaders: ResMut<Assets<Shader>>,
+) {
+    if input.just_did(Action::ReloadShaders) {
+        for file in &shader_files.0 {
+            asset_server.reload(file.path);
+        }
+    }
+
+    let changed: HashSet<_> = events
+        .read()
+        .filter_map(|event| match *event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(id),
+            _ => None,
+        })
+        .collect();
+    for file in &mut shader_files.0 {
+        if changed.contains(&file.loaded.id()) {
+            file.swap_in(&mut shaders);
+        }
+    }
+}
+
+// A shader that doesn't compile is swapped straight back for the one before it, rather than leaving
+// whatever it draws blank. One that comes through its trial is the one to go back to from then on.
+fn keep_working_shaders(
+    errors: Res<ShaderErrors>,
+    mut shader_files: ResMut<ShaderFiles>,
+    mut shaders: ResMut<Assets<Shader>>,
+) {
+    let failed: HashSet<_> = errors
+        .0
+        .lock()
+        .map(|errors| errors.try_iter().collect())
+        .unwrap_or_default();
+
+    for file in &mut shader_files.0 {
+        let Some(trial) = &mut file.trial else {
+            continue;
+        };
+        trial.frames += 1;
+
+        if trial.frames > STALE_FRAMES && failed.contains(&file.embedded.id()) {
+            error!(
+                "{} doesn't compile, see the error above, keeping the shader there was until it's fixed",
+                file.path
+            );
+            shaders.insert(&file.embedded, trial.working.clone());
+            file.trial = None;
+        } else if trial.frames >= CONFIRM_FRAMES {
+            file.trial = None;
+        }
+    }
+}
+
+fn report_shader_errors(
+    pipeline_cache: Res<PipelineCache>,
+    watched: Res<WatchedShaders>,
+    sender: Res<ShaderErrorSender>,
+) {
+    let failed: HashSet<_> = pipeline_cache
+        .pipelines()
+        // not loaded yet or waiting on an import are tried again by themselves
+        .filter(|pipeline| {
+            matches!(
+                pipeline.state,
+                CachedPipelineState::Err(
+                    PipelineCacheError::ProcessShaderError(_)
+                        | PipelineCacheError::CreateShaderModule(_)
+                )
+            )
+        })
+        .flat_map(|pipeline| match &pipeline.descriptor {
+            PipelineDescriptor::RenderPipelineDescriptor(descriptor) => [
+                Some(descriptor.vertex.shader.id()),
+                descriptor
+                    .fragment
+                    .as_ref()
+                    .map(|fragment| fragment.shader.id()),
+            ],
+            PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+                [Some(descriptor.shader.id()), None]
+            }
+        })
+        .flatten()
+        .filter(|id| watched.0.contains(id))
+        .collect();
+
+    for id in failed {
+        // the main world is gone when shutting down
+        let _ = sender.0.send(id);
+    }
+}
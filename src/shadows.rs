@@ -0,0 +1,42 @@
+use bevy::{pbr::PointLightShadowMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+pub struct ShadowsPlugin;
+
+impl Plugin for ShadowsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_shadows);
+    }
+}
+
+// How sharp the shadow behind the cube is. Off casts nothing at all, so the shadow passes don't run.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShadowQuality {
+    Off,
+    #[default]
+    Low,
+    High,
+}
+
+impl ShadowQuality {
+    pub fn enabled(self) -> bool {
+        self != ShadowQuality::Off
+    }
+
+    // texels along each side of the cube map, a 640x360 canvas doesn't show much past the low one
+    fn map_size(self) -> usize {
+        match self {
+            ShadowQuality::Off | ShadowQuality::Low => 512,
+            ShadowQuality::High => 2048,
+        }
+    }
+}
+
+// The wall that catches the shadow is the background's, see background.rs.
+fn setup_shadows(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(PointLightShadowMap {
+        size: settings.shadows.map_size(),
+    });
+}
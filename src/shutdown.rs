@@ -0,0 +1,30 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::history::StateHistory;
+
+pub struct ShutdownPlugin;
+
+impl Plugin for ShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        // the runner only stops once the frame the AppExit went out in has finished, so everything
+        // in Last still gets its turn
+        app.add_systems(
+            Last,
+            farewell.after(SaveOnExit).run_if(on_event::<AppExit>()),
+        );
+    }
+}
+
+// Everything that writes to disk on the way out. Every way of quitting ends in an AppExit: the
+// menus, the tray, and closing the window where there's no tray to close to. A save that fails
+// warns about it and the app closes anyway.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SaveOnExit;
+
+fn farewell(time: Res<Time<Real>>, history: Res<StateHistory>) {
+    let minutes = time.elapsed_seconds() / 60.0;
+    match history.visited().first() {
+        Some(state) => info!("bye, that was {minutes:.0} minutes, mostly {state:?}"),
+        None => info!("bye, that was {minutes:.0} minutes"),
+    }
+}
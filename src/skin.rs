@@ -0,0 +1,166 @@
+use bevy::{
+    ecs::{
+        query::{QueryData, QueryFilter, ROQueryItem},
+        system::SystemParam,
+    },
+    gltf::Gltf,
+    input::mouse::MouseWheel,
+    prelude::*,
+    scene::{SceneInstance, SceneSpawner},
+    utils::HashMap,
+};
+use serde::Deserialize;
+
+use crate::{
+    config::read_override,
+    face::{CubeFace, ExpressionPaths, Expressions},
+    light_mood::{SkinLights, StateLights},
+    yawn::CubeClips,
+    Cube, CubeState,
+};
+
+const DEFAULT_SKIN: &str = "mckenzie-cube.glb";
+const SKINS_FILE_NAME: &str = "skins.ron";
+
+pub struct SkinPlugin;
+
+impl Plugin for SkinPlugin {
+    fn build(&self, app: &mut App) {
+        // the cube is spawned from the current skin during Startup
+        app.add_systems(PreStartup, load_skins)
+            .add_systems(Update, cycle_skin);
+    }
+}
+
+// The entities under the cube that match D and F, whatever skin it's in. The scene spawns
+// asynchronously, and again with each new skin, so they're looked up each time they're needed.
+#[derive(SystemParam)]
+pub struct CubeParts<'w, 's, D: QueryData + 'static, F: QueryFilter + 'static = ()> {
+    cubes: Query<'w, 's, Entity, With<Cube>>,
+    children: Query<'w, 's, &'static Children>,
+    parts: Query<'w, 's, D, F>,
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter> CubeParts<'w, 's, D, F> {
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, ROQueryItem<'_, D>)> {
+        self.cubes
+            .iter()
+            .flat_map(|cube| self.children.iter_descendants(cube))
+            .filter_map(|entity| Some((entity, self.parts.get(entity).ok()?)))
+    }
+}
+
+// Extra glbs, relative to the assets folder, offered after the built-in cube.
+#[derive(Deserialize)]
+struct SkinsFile {
+    skins: Vec<String>,
+    // a png to use for the window and tray icon, also relative to the assets folder
+    #[serde(default)]
+    icon: Option<String>,
+    // the face textures for each of those glbs that has its own, the rest keep the face they came with
+    #[serde(default)]
+    expressions: HashMap<String, ExpressionPaths>,
+    // lights for each glb that should look its own way, any state left out keeps lights.ron's
+    #[serde(default)]
+    lights: HashMap<String, HashMap<CubeState, StateLights>>,
+}
+
+pub struct Skin {
+    pub scene: Handle<Scene>,
+    // the whole file, for the animations authored alongside the scene
+    pub gltf: Handle<Gltf>,
+    pub expressions: Option<Expressions>,
+    pub lights: Option<SkinLights>,
+}
+
+#[derive(Resource)]
+pub struct SkinLibrary {
+    skins: Vec<Skin>,
+    current: usize,
+    icon: Option<String>,
+}
+
+impl SkinLibrary {
+    pub fn current(&self) -> &Skin {
+        &self.skins[self.current]
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+}
+
+pub fn load_skins(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut paths = vec![DEFAULT_SKIN.to_string()];
+    let mut icon = None;
+    let mut expressions = HashMap::new();
+    let mut lights = HashMap::new();
+    if let Some(file) = read_override::<SkinsFile>(SKINS_FILE_NAME) {
+        paths.extend(file.skins);
+        icon = file.icon;
+        expressions = file.expressions;
+        lights = file.lights;
+    }
+    // a skins.ron can give the built-in cube a different set too
+    expressions
+        .entry(DEFAULT_SKIN.to_string())
+        .or_insert_with(ExpressionPaths::mckenzie);
+
+    let skins = paths
+        .into_iter()
+        .map(|path| Skin {
+            scene: asset_server.load(format!("{path}#Scene0")),
+            expressions: expressions
+                .remove(&path)
+                .map(|paths| Expressions::load(&asset_server, paths)),
+            lights: lights.remove(&path).map(SkinLights::new),
+            gltf: asset_server.load(path),
+        })
+        .collect();
+
+    commands.insert_resource(SkinLibrary {
+        skins,
+        current: 0,
+        icon,
+    });
+}
+
+// Ctrl and the wheel step through the skins, wrapping round at either end. Swapping the scene handle
+// has bevy despawn the old hierarchy and spawn the new one under the same entity, so the cube keeps
+// its transform, its Cube and everything else it's carrying.
+fn cycle_skin(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    scene_spawner: Res<SceneSpawner>,
+    mut skins: ResMut<SkinLibrary>,
+    mut query_cube: Query<(&mut Handle<Scene>, &mut Cube, Option<&SceneInstance>)>,
+) {
+    let scrolled: f32 = wheel_events.read().map(|event| event.y).sum();
+    if scrolled == 0.0 || !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    if skins.skins.len() < 2 {
+        return;
+    }
+
+    let (mut scene, mut cube_prop, instance) = query_cube.single_mut();
+    // still spawning the last one, scrolling quickly shouldn't queue up a string of swaps
+    if !instance.is_some_and(|instance| scene_spawner.instance_is_ready(**instance)) {
+        return;
+    }
+
+    let count = skins.skins.len();
+    skins.current = if scrolled > 0.0 {
+        (skins.current + 1) % count
+    } else {
+        (skins.current + count - 1) % count
+    };
+    *scene = skins.current().scene.clone();
+
+    // the face material and animation player are about to go, find the new skin's ones
+    commands.remove_resource::<CubeFace>();
+    commands.remove_resource::<CubeClips>();
+    // a yawn cut off halfway would otherwise never hand the rotation back
+    cube_prop.playing_clip = false;
+}
@@ -0,0 +1,129 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMoved},
+};
+
+use crate::{Cube, CubeSystems};
+
+// how fast the cube swings back and forth, in radians per second
+const SWAY_FREQUENCY: f32 = 8.0;
+// fraction of critical damping, low enough for a couple of swings before it settles
+const SWAY_DAMPING: f32 = 0.25;
+// window pixels between the imaginary hand holding the cube and the cube, longer swings less
+const SWAY_PENDULUM_LENGTH: f32 = 260.0;
+// never further than this either way, however hard the window is flung
+const MAX_SWAY_ANGLE: f32 = 0.5;
+// world units the cube trails behind per radian of swing
+const SWAY_LAG: f32 = 0.6;
+// window velocity is eased over this long, moves don't arrive every frame
+const WINDOW_VELOCITY_SMOOTHING_SECS: f32 = 0.05;
+// the pendulum is stepped at least this finely so a slow frame can't blow it up
+const SWAY_STEP_SECS: f32 = 1.0 / 120.0;
+
+pub struct SwayPlugin;
+
+impl Plugin for SwayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WindowSway>()
+            .add_systems(Update, remove_sway_lag.before(CubeSystems::Behavior))
+            .add_systems(Update, sway_update.in_set(CubeSystems::Overlay));
+    }
+}
+
+// The cube hangs like a pendulum from the window, so dragging the window around swings it.
+#[derive(Resource, Default)]
+struct WindowSway {
+    last_position: Option<IVec2>,
+    // window pixels per second, smoothed
+    velocity: Vec2,
+    angle: f32,
+    angular_velocity: f32,
+    // how far the cube was pushed this frame, taken back off before the behaviors run
+    lag: Vec3,
+}
+
+impl WindowSway {
+    // A pendulum in an accelerating frame: pushed back against the window's acceleration and pulled
+    // down to hang straight.
+    fn step(&mut self, acceleration: f32, delta_secs: f32) {
+        let steps = (delta_secs / SWAY_STEP_SECS).ceil().max(1.0);
+        let dt = delta_secs / steps;
+
+        for _ in 0..steps as u32 {
+            let spring = -SWAY_FREQUENCY * SWAY_FREQUENCY * self.angle;
+            let damping = -2.0 * SWAY_DAMPING * SWAY_FREQUENCY * self.angular_velocity;
+            self.angular_velocity += (spring + damping - acceleration / SWAY_PENDULUM_LENGTH) * dt;
+            self.angle += self.angular_velocity * dt;
+
+            if self.angle.abs() > MAX_SWAY_ANGLE {
+                self.angle = self.angle.clamp(-MAX_SWAY_ANGLE, MAX_SWAY_ANGLE);
+                self.angular_velocity = 0.0;
+            }
+        }
+    }
+}
+
+fn remove_sway_lag(
+    mut sway: ResMut<WindowSway>,
+    mut query_cube: Query<&mut Transform, With<Cube>>,
+) {
+    if sway.lag == Vec3::ZERO {
+        return;
+    }
+
+    for mut cube_transform in &mut query_cube {
+        cube_transform.translation -= sway.lag;
+    }
+    sway.lag = Vec3::ZERO;
+}
+
+// Tilts and trails on top of the behavior's pose, same as the other overlays.
+fn sway_update(
+    time: Res<Time>,
+    mut moved_events: EventReader<WindowMoved>,
+    primary: Query<(), With<PrimaryWindow>>,
+    mut sway: ResMut<WindowSway>,
+    mut query_cube: Query<(&mut Transform, &mut Cube)>,
+) {
+    let delta_secs = time.delta_seconds();
+    if delta_secs <= 0.0 {
+        return;
+    }
+
+    let position = moved_events
+        .read()
+        .filter(|event| primary.contains(event.window))
+        .last()
+        .map(|event| event.position);
+    // the first one is just where the window started out
+    let moved = match (sway.last_position, position) {
+        (Some(last), Some(position)) => (position - last).as_vec2(),
+        _ => Vec2::ZERO,
+    };
+    if position.is_some() {
+        sway.last_position = position;
+    }
+
+    let velocity = sway.velocity.lerp(
+        moved / delta_secs,
+        1.0 - (-delta_secs / WINDOW_VELOCITY_SMOOTHING_SECS).exp(),
+    );
+    let acceleration = (velocity - sway.velocity) / delta_secs;
+    sway.velocity = velocity;
+    // only side to side, lifting the window doesn't swing a hanging thing much
+    sway.step(acceleration.x, delta_secs);
+
+    if sway.angle.abs() < 1e-4 && sway.angular_velocity.abs() < 1e-3 {
+        return;
+    }
+
+    let tilt = Quat::from_rotation_z(sway.angle);
+    // swinging to one side also carries the cube that way, hanging below whatever holds it
+    let lag = Vec3::X * sway.angle.sin() * SWAY_LAG;
+    for (mut cube_transform, mut cube_prop) in &mut query_cube {
+        cube_transform.rotation *= tilt;
+        cube_prop.look_offset *= tilt;
+        cube_transform.translation += lag;
+    }
+    sway.lag = lag;
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+pub struct TimeDilationPlugin;
+
+impl Plugin for TimeDilationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeDilation>()
+            .add_event::<DilateTime>()
+            // after everything that could have asked this frame, so the next one already runs at
+            // the new speed
+            .add_systems(PostUpdate, dilate_time);
+    }
+}
+
+// Asks for the whole scene to run at `factor` times its speed for `secs` real seconds, 0 to freeze
+// it. A request while one is going replaces it, the same as a flash starting over.
+#[derive(Event, Clone, Copy)]
+pub struct DilateTime {
+    pub factor: f32,
+    pub secs: f32,
+}
+
+impl DilateTime {
+    pub fn freeze(secs: f32) -> Self {
+        DilateTime { factor: 0.0, secs }
+    }
+}
+
+// How fast virtual time runs and how much longer for, back to 1 once it's over. Only what goes by
+// `Time` follows, the idle and mood clocks go by `Time<Real>` so a slow moment never reads as the
+// cursor having gone quiet.
+#[derive(Resource)]
+pub struct TimeDilation {
+    factor: f32,
+    remaining: Timer,
+}
+
+impl Default for TimeDilation {
+    fn default() -> Self {
+        TimeDilation {
+            factor: 1.0,
+            remaining: Timer::default(),
+        }
+    }
+}
+
+fn dilate_time(
+    real: Res<Time<Real>>,
+    mut requests: EventReader<DilateTime>,
+    mut dilation: ResMut<TimeDilation>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if let Some(request) = requests.read().last() {
+        *dilation = TimeDilation {
+            factor: request.factor.max(0.0),
+            remaining: Timer::from_seconds(request.secs, TimerMode::Once),
+        };
+    } else if !time.is_paused() && dilation.remaining.tick(real.delta()).finished() {
+        // counting only while the clock runs, so pausing partway through keeps the rest for after
+        dilation.factor = 1.0;
+    }
+
+    if time.relative_speed() != dilation.factor {
+        time.set_relative_speed(dilation.factor);
+    }
+}
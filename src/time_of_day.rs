@@ -0,0 +1,318 @@
+use bevy::prelude::*;
+use chrono::{Local, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fade::smoothstep,
+    light_mood::{KeyLight, LightMood},
+    mood::{Mood, MoodLight},
+    settings::Settings,
+};
+
+// the clock only moves the light a little in a minute
+const REFRESH_SECS: f32 = 60.0;
+// how long dawn and dusk take on either side of sunrise and sunset
+const TWILIGHT_HOURS: f32 = 1.0;
+// how much sooner the cube dozes off in the middle of the night
+const MAX_SLEEP_BIAS: f32 = 0.6;
+
+const NIGHT: DayLight = DayLight {
+    brightness: 0.45,
+    color: Vec3::new(0.55, 0.65, 1.0),
+};
+const TWILIGHT: DayLight = DayLight {
+    brightness: 0.75,
+    color: Vec3::new(1.0, 0.72, 0.5),
+};
+const DAY: DayLight = DayLight {
+    brightness: 1.0,
+    color: Vec3::new(1.0, 0.97, 0.9),
+};
+
+pub struct TimeOfDayPlugin;
+
+impl Plugin for TimeOfDayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeOfDay>()
+            .add_systems(Startup, refresh_time_of_day)
+            // ahead of the celebrations and parties, so their colors win while they last
+            .add_systems(PreUpdate, (tick_time_of_day, lighting_update).chain());
+    }
+}
+
+// When the sun comes up and goes down, in hours on the local clock.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct DayCycle {
+    pub sunrise: f32,
+    pub sunset: f32,
+    // holds the clock at this hour, 13.5 for half past one, so screenshots come out the same
+    pub fixed_hour: Option<f32>,
+}
+
+impl Default for DayCycle {
+    fn default() -> Self {
+        DayCycle {
+            sunrise: 7.0,
+            sunset: 19.0,
+            fixed_hour: None,
+        }
+    }
+}
+
+// How the lights look at one point in the day, the color scaling them in sRGB.
+#[derive(Clone, Copy)]
+struct DayLight {
+    brightness: f32,
+    color: Vec3,
+}
+
+impl DayLight {
+    fn lerp(self, other: DayLight, t: f32) -> DayLight {
+        DayLight {
+            brightness: self.brightness + (other.brightness - self.brightness) * t,
+            color: self.color.lerp(other.color, t),
+        }
+    }
+}
+
+// Where the local clock has the day, looked at again once a minute.
+#[derive(Resource)]
+pub struct TimeOfDay {
+    light: DayLight,
+    // 0 through the day and evening, 1 in the dead of night
+    sleepiness: f32,
+    refresh: Timer,
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        TimeOfDay {
+            light: DAY,
+            sleepiness: 0.0,
+            refresh: Timer::from_seconds(REFRESH_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl TimeOfDay {
+    fn set(&mut self, cycle: &DayCycle) {
+        let now = Local::now();
+        let hour = cycle
+            .fixed_hour
+            .unwrap_or(now.hour() as f32 + now.minute() as f32 / 60.0);
+        self.light = light_at(hour, cycle);
+        self.sleepiness = sleepiness_at(hour, cycle);
+    }
+
+    // What the background's colors get multiplied by, in sRGB.
+    pub fn tint(&self) -> Vec3 {
+        self.light.color * self.light.brightness
+    }
+
+    pub fn brightness(&self) -> f32 {
+        self.light.brightness
+    }
+
+    // What the idle wait before dozing off gets multiplied by.
+    pub fn idle_scale(&self) -> f32 {
+        1.0 - MAX_SLEEP_BIAS * self.sleepiness
+    }
+}
+
+// Eased between night, dawn, day, dusk and back round to night.
+fn light_at(hour: f32, cycle: &DayCycle) -> DayLight {
+    let keyframes = [
+        (cycle.sunrise - TWILIGHT_HOURS, NIGHT),
+        (cycle.sunrise, TWILIGHT),
+        (cycle.sunrise + TWILIGHT_HOURS, DAY),
+        (cycle.sunset - TWILIGHT_HOURS, DAY),
+        (cycle.sunset, TWILIGHT),
+        (cycle.sunset + TWILIGHT_HOURS, NIGHT),
+    ];
+    // in hours since the first keyframe, so the night wraps round midnight
+    let start = keyframes[0].0;
+    let since = |hour: f32| (hour - start).rem_euclid(24.0);
+    let hour = since(hour);
+
+    let mut previous = (0.0, NIGHT);
+    for (at, light) in keyframes
+        .iter()
+        .skip(1)
+        .map(|(at, light)| (since(*at), *light))
+    {
+        if hour < at {
+            let t = (hour - previous.0) / (at - previous.0).max(f32::EPSILON);
+            return previous.1.lerp(light, smoothstep(t));
+        }
+        previous = (at, light);
+    }
+    NIGHT
+}
+
+// Creeps up a couple of hours after sunset and is gone again by sunrise.
+fn sleepiness_at(hour: f32, cycle: &DayCycle) -> f32 {
+    let between = |from: f32, to: f32, x: f32| smoothstep((x - from) / (to - from));
+    let night = (cycle.sunrise - cycle.sunset).rem_euclid(24.0);
+    let after_sunset = (hour - cycle.sunset).rem_euclid(24.0);
+    between(2.0, 4.0, after_sunset) * (1.0 - between(night - 1.0, night, after_sunset))
+}
+
+fn refresh_time_of_day(settings: Res<Settings>, mut time_of_day: ResMut<TimeOfDay>) {
+    time_of_day.set(&settings.day_cycle);
+}
+
+fn tick_time_of_day(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut time_of_day: ResMut<TimeOfDay>,
+) {
+    if time_of_day.refresh.tick(time.delta()).just_finished() {
+        time_of_day.set(&settings.day_cycle);
+    }
+}
+
+// Every frame, so the lights come back to the time of day after a party recolors them. The
+// state's look goes on top, see light_mood.rs.
+pub fn lighting_update(
+    mood: Res<Mood>,
+    time_of_day: Res<TimeOfDay>,
+    light_mood: Res<LightMood>,
+    mut lights: Query<(&mut PointLight, &MoodLight, Has<KeyLight>)>,
+) {
+    for (mut light, mood_light, is_key) in &mut lights {
+        let look = if is_key {
+            light_mood.key()
+        } else {
+            light_mood.fill()
+        };
+        light.intensity = mood_light.base_intensity
+            * mood.light_factor()
+            * time_of_day.brightness()
+            * look.intensity;
+        let color = time_of_day.light.color * look.color;
+        light.color = Color::srgb(color.x, color.y, color.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(sunrise: f32, sunset: f32) -> DayCycle {
+        DayCycle {
+            sunrise,
+            sunset,
+            fixed_hour: None,
+        }
+    }
+
+    fn assert_light(light: DayLight, expected: DayLight) {
+        assert!(
+            (light.brightness - expected.brightness).abs() < 1e-4
+                && light.color.abs_diff_eq(expected.color, 1e-4),
+            "got {} {:?}, expected {} {:?}",
+            light.brightness,
+            light.color,
+            expected.brightness,
+            expected.color
+        );
+    }
+
+    #[test]
+    fn lands_on_each_keyframe() {
+        let cycle = cycle(7.0, 19.0);
+
+        assert_light(light_at(6.0, &cycle), NIGHT);
+        assert_light(light_at(7.0, &cycle), TWILIGHT);
+        assert_light(light_at(8.0, &cycle), DAY);
+        assert_light(light_at(13.0, &cycle), DAY);
+        assert_light(light_at(18.0, &cycle), DAY);
+        assert_light(light_at(19.0, &cycle), TWILIGHT);
+        assert_light(light_at(20.0, &cycle), NIGHT);
+    }
+
+    #[test]
+    fn eases_between_keyframes() {
+        let cycle = cycle(7.0, 19.0);
+
+        let dawn = light_at(7.5, &cycle).brightness;
+        assert!(TWILIGHT.brightness < dawn && dawn < DAY.brightness);
+        let dusk = light_at(19.5, &cycle).brightness;
+        assert!(NIGHT.brightness < dusk && dusk < TWILIGHT.brightness);
+        // no sudden change of speed coming into a keyframe
+        let step = light_at(7.99, &cycle).brightness - light_at(7.98, &cycle).brightness;
+        assert!(step.abs() < 1e-3);
+    }
+
+    #[test]
+    fn night_carries_on_through_midnight() {
+        let cycle = cycle(7.0, 19.0);
+
+        assert_light(light_at(23.99, &cycle), NIGHT);
+        assert_light(light_at(0.0, &cycle), NIGHT);
+        assert_light(light_at(0.01, &cycle), NIGHT);
+        assert_light(light_at(24.0, &cycle), NIGHT);
+        assert!((sleepiness_at(23.99, &cycle) - 1.0).abs() < 1e-4);
+        assert!((sleepiness_at(0.01, &cycle) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn dusk_wraps_round_midnight() {
+        // the last of the twilight isn't gone until half past midnight
+        let cycle = cycle(6.0, 23.5);
+
+        assert_light(light_at(23.5, &cycle), TWILIGHT);
+        assert_light(light_at(0.5, &cycle), NIGHT);
+        let before = light_at(23.99, &cycle).brightness;
+        let after = light_at(0.01, &cycle).brightness;
+        assert!(NIGHT.brightness < after && after < before && before < TWILIGHT.brightness);
+        assert!(before - after < 0.01);
+    }
+
+    #[test]
+    fn dawn_wraps_round_midnight() {
+        // night is over at a quarter to midnight, with dawn starting before it
+        let cycle = cycle(0.25, 12.0);
+
+        assert_light(light_at(23.25, &cycle), NIGHT);
+        assert_light(light_at(0.25, &cycle), TWILIGHT);
+        let before = light_at(23.99, &cycle).brightness;
+        let after = light_at(0.01, &cycle).brightness;
+        assert!(NIGHT.brightness < before && before < after && after < TWILIGHT.brightness);
+    }
+
+    #[test]
+    fn sleepiness_follows_the_night() {
+        let cycle = cycle(7.0, 19.0);
+
+        assert_eq!(sleepiness_at(19.0, &cycle), 0.0);
+        assert_eq!(sleepiness_at(21.0, &cycle), 0.0);
+        assert!((sleepiness_at(23.0, &cycle) - 1.0).abs() < 1e-4);
+        assert!((sleepiness_at(5.0, &cycle) - 1.0).abs() < 1e-4);
+        assert!(sleepiness_at(6.5, &cycle) < 1.0);
+        assert_eq!(sleepiness_at(7.0, &cycle), 0.0);
+        assert_eq!(sleepiness_at(13.0, &cycle), 0.0);
+    }
+
+    #[test]
+    fn fixed_hour_ignores_the_clock() {
+        let mut time_of_day = TimeOfDay::default();
+
+        time_of_day.set(&DayCycle {
+            fixed_hour: Some(2.0),
+            ..cycle(7.0, 19.0)
+        });
+        assert_light(time_of_day.light, NIGHT);
+        assert!((time_of_day.sleepiness - 1.0).abs() < 1e-4);
+        assert!(time_of_day.idle_scale() < 1.0);
+
+        time_of_day.set(&DayCycle {
+            fixed_hour: Some(13.5),
+            ..cycle(7.0, 19.0)
+        });
+        assert_light(time_of_day.light, DAY);
+        assert_eq!(time_of_day.sleepiness, 0.0);
+        assert_eq!(time_of_day.idle_scale(), 1.0);
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::{core_pipeline::tonemapping::Tonemapping, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compare::{CanvasCompare, Held},
+    input::InputState,
+    keybindings::Action,
+    settings::Settings,
+};
+
+pub struct TonemappingPlugin;
+
+impl Plugin for TonemappingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                compare_tonemapping,
+                apply_tonemapping.run_if(
+                    resource_changed::<Settings>.or_else(resource_changed::<CanvasCompare>),
+                ),
+            )
+                .chain(),
+        );
+    }
+}
+
+// How the canvas camera's HDR light is squeezed down to what the canvas can hold. The key light is
+// bright enough that the plain clamp of `None` clips its highlights flat.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tonemap {
+    None,
+    Reinhard,
+    ReinhardLuminance,
+    AcesFitted,
+    AgX,
+    SomewhatBoringDisplayTransform,
+    #[default]
+    TonyMcMapface,
+    BlenderFilmic,
+}
+
+impl Tonemap {
+    const ALL: [Tonemap; 8] = [
+        Tonemap::None,
+        Tonemap::Reinhard,
+        Tonemap::ReinhardLuminance,
+        Tonemap::AcesFitted,
+        Tonemap::AgX,
+        Tonemap::SomewhatBoringDisplayTransform,
+        Tonemap::TonyMcMapface,
+        Tonemap::BlenderFilmic,
+    ];
+
+    fn tonemapping(self) -> Tonemapping {
+        match self {
+            Tonemap::None => Tonemapping::None,
+            Tonemap::Reinhard => Tonemapping::Reinhard,
+            Tonemap::ReinhardLuminance => Tonemapping::ReinhardLuminance,
+            Tonemap::AcesFitted => Tonemapping::AcesFitted,
+            Tonemap::AgX => Tonemapping::AgX,
+            Tonemap::SomewhatBoringDisplayTransform => Tonemapping::SomewhatBoringDisplayTransform,
+            Tonemap::TonyMcMapface => Tonemapping::TonyMcMapface,
+            Tonemap::BlenderFilmic => Tonemapping::BlenderFilmic,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tonemap::None => "None",
+            Tonemap::Reinhard => "Reinhard",
+            Tonemap::ReinhardLuminance => "Reinhard luminance",
+            Tonemap::AcesFitted => "ACES",
+            Tonemap::AgX => "AgX",
+            Tonemap::SomewhatBoringDisplayTransform => "Boring display",
+            Tonemap::TonyMcMapface => "Tony McMapface",
+            Tonemap::BlenderFilmic => "Blender Filmic",
+        }
+    }
+
+    pub fn next(self) -> Tonemap {
+        let index = Tonemap::ALL.iter().position(|tonemap| *tonemap == self);
+        Tonemap::ALL[index.map_or(0, |index| (index + 1) % Tonemap::ALL.len())]
+    }
+}
+
+pub fn camera_tonemapping(settings: &Settings) -> Tonemapping {
+    settings.tonemapping.tonemapping()
+}
+
+// Each press puts the next tonemapper on the left half of the canvas, next to the live half with
+// the one from the settings, until they've all been round and it goes away.
+fn compare_tonemapping(
+    mut commands: Commands,
+    input: Res<InputState>,
+    settings: Res<Settings>,
+    mut compare: ResMut<CanvasCompare>,
+) {
+    if !input.just_did(Action::CompareTonemapping) {
+        return;
+    }
+
+    let after = match compare.comparing() {
+        Some(Held::Tonemapping(tonemap)) => Tonemap::ALL.iter().position(|t| *t == tonemap),
+        _ => None,
+    };
+    let next = Tonemap::ALL
+        .iter()
+        .skip(after.map_or(0, |index| index + 1))
+        .find(|tonemap| **tonemap != settings.tonemapping);
+
+    match next {
+        Some(tonemap) => {
+            info!(
+                "{} on the left, {} on the right",
+                tonemap.label(),
+                settings.tonemapping.label()
+            );
+            compare.start(&mut commands, Held::Tonemapping(*tonemap));
+        }
+        None => compare.stop(&mut commands),
+    }
+}
+
+fn apply_tonemapping(
+    settings: Res<Settings>,
+    compare: Res<CanvasCompare>,
+    mut cameras: Query<&mut Tonemapping, With<Camera3d>>,
+) {
+    let wanted = match compare.held() {
+        Some(Held::Tonemapping(tonemap)) => tonemap,
+        _ => settings.tonemapping,
+    }
+    .tonemapping();
+
+    for mut tonemapping in &mut cameras {
+        if *tonemapping != wanted {
+            *tonemapping = wanted;
+        }
+    }
+}
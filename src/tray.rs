@@ -0,0 +1,156 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowCloseRequested},
+};
+#[cfg(any(windows, target_os = "macos"))]
+use {
+    crate::{window_controls::ClickThrough, window_icon::AppIcon},
+    tray_icon::{
+        menu::{CheckMenuItem, Menu, MenuEvent, MenuId, MenuItem},
+        Icon, TrayIcon, TrayIconBuilder,
+    },
+};
+
+pub struct TrayPlugin;
+
+impl Plugin for TrayPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(any(windows, target_os = "macos"))]
+        app.add_systems(Startup, create_tray).add_systems(
+            Update,
+            (
+                hide_to_tray,
+                (
+                    pick_tray_entry,
+                    check_click_through.run_if(resource_changed::<ClickThrough>),
+                )
+                    .chain()
+                    .run_if(|tray: Option<NonSend<Tray>>| tray.is_some()),
+            ),
+        );
+
+        // no tray to come back from, the close button closes
+        #[cfg(not(any(windows, target_os = "macos")))]
+        app.add_systems(Update, exit_on_close);
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn exit_on_close(
+    mut close_events: EventReader<WindowCloseRequested>,
+    primary: Query<(), With<PrimaryWindow>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if close_events
+        .read()
+        .any(|event| primary.contains(event.window))
+    {
+        exit.send(AppExit::Success);
+    }
+}
+
+// Has to live on the main thread, and the icon goes away when it's dropped.
+#[cfg(any(windows, target_os = "macos"))]
+struct Tray {
+    _icon: TrayIcon,
+    show: MenuId,
+    // the one way back out of click-through once the window no longer takes clicks
+    click_through: CheckMenuItem,
+    quit: MenuId,
+}
+
+#[cfg(any(windows, target_os = "macos"))]
+fn create_tray(world: &mut World) {
+    let show = MenuItem::new("Show", true, None);
+    let click_through = CheckMenuItem::new("Click through", true, false, None);
+    let quit = MenuItem::new("Quit", true, None);
+    let menu = Menu::new();
+    let icon = world
+        .get_resource::<AppIcon>()
+        .and_then(|icon| Icon::from_rgba(icon.rgba.clone(), icon.size.x, icon.size.y).ok());
+
+    let tray = menu
+        .append_items(&[&show, &click_through, &quit])
+        .map_err(|error| error.to_string())
+        .and_then(|_| {
+            let builder = TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_tooltip("McKenzie Bevy");
+            match icon {
+                Some(icon) => builder.with_icon(icon),
+                None => builder,
+            }
+            .build()
+            .map_err(|error| error.to_string())
+        });
+
+    match tray {
+        Ok(icon) => {
+            world.insert_non_send_resource(Tray {
+                _icon: icon,
+                show: show.id().clone(),
+                click_through,
+                quit: quit.id().clone(),
+            });
+            world.resource_mut::<ClickThrough>().way_out = true;
+        }
+        Err(error) => error!("couldn't add the tray icon, closing will quit: {error}"),
+    }
+}
+
+// Closing hides the window instead, and IdlePlugin winds everything down until it's shown again.
+#[cfg(any(windows, target_os = "macos"))]
+fn hide_to_tray(
+    tray: Option<NonSend<Tray>>,
+    mut close_events: EventReader<WindowCloseRequested>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    // other windows close the ordinary way
+    if !close_events
+        .read()
+        .any(|event| windows.contains(event.window))
+    {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    if tray.is_none() {
+        exit.send(AppExit::Success);
+        return;
+    }
+
+    window.visible = false;
+}
+
+#[cfg(any(windows, target_os = "macos"))]
+fn pick_tray_entry(
+    tray: NonSend<Tray>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut click_through: ResMut<ClickThrough>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for event in MenuEvent::receiver().try_iter() {
+        if event.id == tray.quit {
+            exit.send(AppExit::Success);
+        } else if event.id == *tray.click_through.id() {
+            // the menu has already ticked or unticked itself
+            click_through.enabled = tray.click_through.is_checked();
+        } else if event.id == tray.show {
+            let Ok(mut window) = windows.get_single_mut() else {
+                continue;
+            };
+
+            // hiding never moved it, so it comes back where it was
+            window.visible = true;
+            window.focused = true;
+        }
+    }
+}
+
+// Keeps the tick in step when the hotkey is what changed it.
+#[cfg(any(windows, target_os = "macos"))]
+fn check_click_through(tray: NonSend<Tray>, click_through: Res<ClickThrough>) {
+    tray.click_through.set_checked(click_through.enabled);
+}
@@ -0,0 +1,101 @@
+use bevy::{
+    input::{keyboard::KeyboardInput, ButtonState, InputSystem},
+    prelude::*,
+};
+use std::collections::VecDeque;
+
+use crate::{menu::MenuState, pause::AppMode};
+
+// typing rate is measured over this long
+const TYPING_WINDOW_SECS: f32 = 2.0;
+// this much of a pause and it stops watching the keyboard
+const TYPING_SILENCE_SECS: f32 = 1.5;
+// characters per second that count as typing flat out
+const FULL_SPEED_RATE: f32 = 8.0;
+// how fast the dip follows the typing, per second
+const DIP_EASING: f32 = 3.0;
+
+pub struct TypingPlugin;
+
+impl Plugin for TypingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TypingActivity>().add_systems(
+            PreUpdate,
+            // with the menu or the pause overlay up the keys are meant for them, a text field would
+            // belong in this list too
+            track_typing
+                .after(InputSystem)
+                .run_if(in_state(MenuState::Closed))
+                .run_if(in_state(AppMode::Running)),
+        );
+    }
+}
+
+// How much the cube is watching the keyboard, eased so it drifts down and back up.
+#[derive(Resource, Default)]
+pub struct TypingActivity {
+    presses: VecDeque<f32>,
+    last_press: Option<f32>,
+    // 0 looking at the cursor, 1 watching someone type as fast as they can
+    dip: f32,
+}
+
+impl TypingActivity {
+    pub fn dip(&self) -> f32 {
+        self.dip
+    }
+
+    fn rate(&self) -> f32 {
+        self.presses.len() as f32 / TYPING_WINDOW_SECS
+    }
+}
+
+fn is_modifier(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::ShiftLeft
+            | KeyCode::ShiftRight
+            | KeyCode::ControlLeft
+            | KeyCode::ControlRight
+            | KeyCode::AltLeft
+            | KeyCode::AltRight
+            | KeyCode::SuperLeft
+            | KeyCode::SuperRight
+            | KeyCode::CapsLock
+            | KeyCode::Fn
+    )
+}
+
+fn track_typing(
+    time: Res<Time<Real>>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut typing: ResMut<TypingActivity>,
+) {
+    let now = time.elapsed_seconds();
+
+    for event in keyboard_events.read() {
+        if event.state == ButtonState::Pressed && !is_modifier(event.key_code) {
+            typing.presses.push_back(now);
+            typing.last_press = Some(now);
+        }
+    }
+    while typing
+        .presses
+        .front()
+        .is_some_and(|press| now - press > TYPING_WINDOW_SECS)
+    {
+        typing.presses.pop_front();
+    }
+
+    let typing_now = typing
+        .last_press
+        .is_some_and(|last_press| now - last_press < TYPING_SILENCE_SECS);
+    let target = if typing_now {
+        (typing.rate() / FULL_SPEED_RATE).min(1.0)
+    } else {
+        0.0
+    };
+    typing.dip = typing
+        .dip
+        .lerp(target, 1.0 - (-DIP_EASING * time.delta_seconds()).exp());
+}
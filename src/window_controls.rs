@@ -0,0 +1,398 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowLevel, WindowMode, WindowMoved, WindowResized},
+    winit::WinitWindows,
+};
+
+use crate::{
+    canvas::{CanvasScaling, CanvasSettings},
+    input::{InputState, PressTarget},
+    keybindings::Action,
+    pet_mode::PetMode,
+    settings::Settings,
+    window_shake::WindowShake,
+    CanvasFit,
+};
+
+// logical pixels from a monitor edge that the window snaps flush to it
+const EDGE_SNAP_DISTANCE: f32 = 20.0;
+// and how far it then has to be pulled to come unstuck, so a drag along the edge doesn't flicker
+const EDGE_RELEASE_DISTANCE: f32 = 40.0;
+
+pub struct WindowControlsPlugin;
+
+impl Plugin for WindowControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClickThrough>()
+            .add_systems(Startup, restore_window_level)
+            .add_systems(
+                Update,
+                (
+                    toggle_always_on_top,
+                    toggle_fullscreen,
+                    drag_window,
+                    snap_window_size,
+                    snap_to_edges,
+                    (
+                        toggle_click_through,
+                        apply_click_through.run_if(resource_changed::<ClickThrough>),
+                    )
+                        .chain(),
+                ),
+            );
+    }
+}
+
+// Clicks fall through the window to whatever is underneath. Off on every launch, so nobody is left
+// with a window they can't click on and forgot how to fix.
+#[derive(Resource, Default)]
+pub struct ClickThrough {
+    pub enabled: bool,
+    // whether the tray has an entry to turn it back off, without one it stays off
+    pub way_out: bool,
+}
+
+fn window_level(always_on_top: bool) -> WindowLevel {
+    if always_on_top {
+        WindowLevel::AlwaysOnTop
+    } else {
+        WindowLevel::Normal
+    }
+}
+
+fn restore_window_level(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    window.window_level = window_level(settings.always_on_top);
+}
+
+// Only the level changes, the size stays put, so there's no resize for fit_canvas to pick up.
+fn toggle_always_on_top(
+    input: Res<InputState>,
+    mut settings: ResMut<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !input.just_did(Action::AlwaysOnTop) {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    settings.always_on_top = !settings.always_on_top;
+    window.window_level = window_level(settings.always_on_top);
+
+    if settings.always_on_top {
+        info!("staying on top of other windows");
+    } else {
+        info!("no longer on top of other windows");
+    }
+}
+
+// Not every platform sends a resize for a mode change, so the canvas is refit here as well, from
+// the monitor's size going fullscreen and the remembered windowed size coming back.
+fn toggle_fullscreen(
+    input: Res<InputState>,
+    winit_windows: NonSend<WinitWindows>,
+    mut fit: CanvasFit,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    mut windowed_size: Local<Option<Vec2>>,
+) {
+    if !input.just_did(Action::ToggleFullscreen) {
+        return;
+    }
+    let Ok((entity, mut window)) = windows.get_single_mut() else {
+        return;
+    };
+
+    let physical_size = match window.mode {
+        WindowMode::Windowed => {
+            let Some(monitor) = winit_windows
+                .get_window(entity)
+                .and_then(|winit_window| winit_window.current_monitor())
+            else {
+                return;
+            };
+            let monitor_size = monitor.size();
+
+            *windowed_size = Some(window.size());
+            window.mode = WindowMode::BorderlessFullscreen;
+            Vec2::new(monitor_size.width as f32, monitor_size.height as f32)
+        }
+        _ => {
+            let size = windowed_size.take().unwrap_or(window.size());
+
+            window.mode = WindowMode::Windowed;
+            window.resolution.set(size.x, size.y);
+            window.position = WindowPosition::Centered(MonitorSelection::Primary);
+            size * window.scale_factor()
+        }
+    };
+
+    fit.refit(physical_size, window.scale_factor());
+}
+
+// Without a title bar there's nothing else to move the window by, so empty canvas stands in for it.
+fn drag_window(
+    input: Res<InputState>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+) {
+    if !input.pressed_on(PressTarget::Background) {
+        return;
+    }
+    let Ok((entity, window)) = windows.get_single() else {
+        return;
+    };
+    if window.decorations {
+        return;
+    }
+
+    if let Some(Err(error)) = winit_windows
+        .get_window(entity)
+        .map(|winit_window| winit_window.drag_window())
+    {
+        warn!("couldn't drag the window: {error}");
+    }
+}
+
+// A size snap_window_size asked for, until the window comes back at it.
+struct SnapRequest {
+    size: Vec2,
+    // asked a second time after the window manager put it somewhere else
+    retried: bool,
+}
+
+// Resizing settles on the nearest whole multiple of the canvas in physical pixels that still fits
+// on the monitor, so fit_canvas never has to letterbox. The resize this asks for comes back as an
+// event of its own, which is left alone. A window manager that clamps or maximizes the window sends
+// something else back, and gets asked once more before it has its way.
+fn snap_window_size(
+    mut resize_events: EventReader<WindowResized>,
+    settings: Res<Settings>,
+    pet_mode: Res<PetMode>,
+    canvas: Res<CanvasSettings>,
+    winit_windows: NonSend<WinitWindows>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    mut requested: Local<Option<SnapRequest>>,
+) {
+    let Ok((entity, mut window)) = windows.get_single_mut() else {
+        return;
+    };
+    if !resize_events.read().any(|event| event.window == entity) {
+        return;
+    }
+    // only whole multiples leave anything to snap to
+    let fractional = settings.scaling != CanvasScaling::Integer;
+    if settings.freeform_resize
+        || fractional
+        || pet_mode.enabled
+        || window.mode != WindowMode::Windowed
+    {
+        *requested = None;
+        return;
+    }
+
+    let size = Vec2::new(
+        window.resolution.physical_width() as f32,
+        window.resolution.physical_height() as f32,
+    );
+    if requested
+        .as_ref()
+        .is_some_and(|request| request.size.distance(size) < 1.0)
+    {
+        *requested = None;
+        return;
+    }
+
+    let canvas = canvas.size();
+    let monitor = winit_windows
+        .get_window(entity)
+        .and_then(|winit_window| winit_window.current_monitor())
+        .map(|monitor| Vec2::new(monitor.size().width as f32, monitor.size().height as f32));
+    let fits = monitor.map_or(f32::INFINITY, |monitor| {
+        (monitor / canvas).min_element().floor()
+    });
+    let scale = (size / canvas).min_element().round().min(fits).max(1.0);
+    let snapped = canvas * scale;
+    if snapped.distance(size) < 1.0 {
+        *requested = None;
+        return;
+    }
+
+    match requested.as_mut() {
+        Some(request) if request.size.distance(snapped) < 1.0 => {
+            if request.retried {
+                // it keeps coming back at the other size, that's where it stays
+                *requested = None;
+                return;
+            }
+            request.retried = true;
+        }
+        _ => {
+            *requested = Some(SnapRequest {
+                size: snapped,
+                retried: false,
+            })
+        }
+    }
+    window
+        .resolution
+        .set_physical_resolution(snapped.x as u32, snapped.y as u32);
+}
+
+// The hotkey only reaches the window while it has focus, so the tray entry has to be there as the
+// way out that always works. Linux has no tray, and the tray can fail to show up anywhere else.
+fn toggle_click_through(input: Res<InputState>, mut click_through: ResMut<ClickThrough>) {
+    if !input.just_did(Action::ClickThrough) {
+        return;
+    }
+
+    if click_through.enabled || click_through.way_out {
+        click_through.enabled = !click_through.enabled;
+    } else {
+        warn!("click-through needs the tray icon to turn it back off, and there isn't one");
+    }
+}
+
+// The in-window cursor stops coming with the clicks, gather_input follows the global one instead.
+fn apply_click_through(
+    click_through: Res<ClickThrough>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if click_through.is_added() {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    window.cursor.hit_test = !click_through.enabled;
+    if !click_through.enabled {
+        info!("taking clicks again");
+    } else if cfg!(feature = "global-cursor") {
+        info!("clicks now go through to whatever is underneath");
+    } else {
+        warn!("clicks now go through, but the cube can't see the cursor without the global-cursor feature");
+    }
+}
+
+// The edges the window is stuck to, one per axis, as the outer position that keeps it flush.
+#[derive(Default)]
+struct EdgeSnap {
+    x: Option<i32>,
+    y: Option<i32>,
+    // where this last put the window, so the move that comes back isn't taken for the user's
+    placed: Option<IVec2>,
+}
+
+// Picks the edge, the near or the far one, the window is closest to along one axis, and keeps it
+// while it's within reach.
+fn snap_axis(
+    stuck: &mut Option<i32>,
+    position: i32,
+    extent: i32,
+    monitor_start: i32,
+    monitor_extent: i32,
+    scale_factor: f32,
+) -> i32 {
+    let near = monitor_start;
+    let far = monitor_start + monitor_extent - extent;
+    let reach = |distance: f32| (distance * scale_factor).round() as i32;
+
+    if let Some(edge) = *stuck {
+        if (position - edge).abs() <= reach(EDGE_RELEASE_DISTANCE) {
+            return edge;
+        }
+        *stuck = None;
+    }
+
+    let edge = if (position - near).abs() <= (position - far).abs() {
+        near
+    } else {
+        far
+    };
+    if (position - edge).abs() <= reach(EDGE_SNAP_DISTANCE) {
+        *stuck = Some(edge);
+        return edge;
+    }
+    position
+}
+
+// Monitors can sit anywhere on the desktop, so the one that counts is whichever the window covers
+// the most of.
+fn snap_to_edges(
+    mut moved_events: EventReader<WindowMoved>,
+    winit_windows: NonSend<WinitWindows>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    shake: Res<WindowShake>,
+    mut snap: Local<EdgeSnap>,
+) {
+    let Ok((entity, mut window)) = windows.get_single_mut() else {
+        return;
+    };
+    let Some(position) = moved_events
+        .read()
+        .filter(|event| event.window == entity)
+        .last()
+        .map(|event| event.position)
+    else {
+        return;
+    };
+    // the shake has edges of its own to keep to, and comes back to where it started
+    if snap.placed.take() == Some(position)
+        || window.mode != WindowMode::Windowed
+        || shake.shaking()
+    {
+        return;
+    }
+    let Some(winit_window) = winit_windows.get_window(entity) else {
+        return;
+    };
+
+    let outer = winit_window.outer_size();
+    let size = IVec2::new(outer.width as i32, outer.height as i32);
+    let overlap = |monitor: &winit::monitor::MonitorHandle| {
+        let start = IVec2::new(monitor.position().x, monitor.position().y);
+        let end = start + IVec2::new(monitor.size().width as i32, monitor.size().height as i32);
+        let covered = (end.min(position + size) - start.max(position)).max(IVec2::ZERO);
+        covered.x as i64 * covered.y as i64
+    };
+    let Some(monitor) = winit_window
+        .available_monitors()
+        .max_by_key(|monitor| overlap(monitor))
+        .filter(|monitor| overlap(monitor) > 0)
+    else {
+        return;
+    };
+
+    let scale_factor = monitor.scale_factor() as f32;
+    let snapped = IVec2::new(
+        snap_axis(
+            &mut snap.x,
+            position.x,
+            size.x,
+            monitor.position().x,
+            monitor.size().width as i32,
+            scale_factor,
+        ),
+        snap_axis(
+            &mut snap.y,
+            position.y,
+            size.y,
+            monitor.position().y,
+            monitor.size().height as i32,
+            scale_factor,
+        ),
+    );
+
+    if snapped != position {
+        window.position = WindowPosition::At(snapped);
+        snap.placed = Some(snapped);
+    }
+}
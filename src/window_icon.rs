@@ -0,0 +1,99 @@
+use bevy::{
+    asset::io::file::FileAssetReader,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        texture::{CompressedImageFormats, ImageSampler, ImageType},
+    },
+    window::PrimaryWindow,
+    winit::WinitWindows,
+};
+use std::fs;
+use winit::window::Icon;
+
+use crate::skin::{load_skins, SkinLibrary};
+
+const EMBEDDED_ICON: &[u8] = include_bytes!("../assets/icon.png");
+
+pub struct WindowIconPlugin;
+
+impl Plugin for WindowIconPlugin {
+    fn build(&self, app: &mut App) {
+        // decoded up front, the tray wants it during Startup too
+        app.add_systems(PreStartup, load_app_icon.after(load_skins))
+            .add_systems(Startup, set_window_icon);
+    }
+}
+
+// The picture for the taskbar, the alt-tab switcher and the tray, as straight RGBA.
+#[derive(Resource)]
+pub struct AppIcon {
+    pub rgba: Vec<u8>,
+    pub size: UVec2,
+}
+
+impl AppIcon {
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let image = Image::from_buffer(
+            bytes,
+            ImageType::Extension("png"),
+            CompressedImageFormats::NONE,
+            true,
+            ImageSampler::Default,
+            RenderAssetUsages::default(),
+        )
+        .map_err(|error| error.to_string())?
+        .try_into_dynamic()
+        .map_err(|error| error.to_string())?
+        .to_rgba8();
+
+        Ok(AppIcon {
+            size: UVec2::new(image.width(), image.height()),
+            rgba: image.into_raw(),
+        })
+    }
+}
+
+// A skin pack can bring its own icon, anything wrong with it leaves the built-in one.
+fn load_app_icon(mut commands: Commands, skins: Res<SkinLibrary>) {
+    let custom = skins.icon().and_then(|path| {
+        let full_path = FileAssetReader::get_base_path().join("assets").join(path);
+        match fs::read(&full_path)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| AppIcon::decode(&bytes))
+        {
+            Ok(icon) => Some(icon),
+            Err(error) => {
+                error!("couldn't load the icon {full_path:?}, using the built-in one: {error}");
+                None
+            }
+        }
+    });
+
+    match custom.map_or_else(|| AppIcon::decode(EMBEDDED_ICON), Ok) {
+        Ok(icon) => commands.insert_resource(icon),
+        Err(error) => error!("couldn't decode the built-in icon, keeping the default: {error}"),
+    }
+}
+
+// Bevy doesn't set window icons itself, winit does it once it has the window.
+fn set_window_icon(
+    icon: Option<Res<AppIcon>>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+) {
+    let Some(icon) = icon else {
+        return;
+    };
+    let Ok(entity) = windows.get_single() else {
+        return;
+    };
+    let Some(winit_window) = winit_windows.get_window(entity) else {
+        return;
+    };
+
+    match Icon::from_rgba(icon.rgba.clone(), icon.size.x, icon.size.y) {
+        Ok(icon) => winit_window.set_window_icon(Some(icon)),
+        Err(error) => error!("couldn't set the window icon: {error}"),
+    }
+}
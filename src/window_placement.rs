@@ -0,0 +1,305 @@
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode, WindowMoved, WindowResized},
+    winit::WinitWindows,
+};
+use std::env;
+
+use crate::{
+    pet_mode::PetMode,
+    settings::{save_settings, Settings, WindowPlacement},
+    shutdown::SaveOnExit,
+};
+
+// moving or resizing settles for this long before it's written out
+const PLACEMENT_SAVE_DELAY_SECS: f32 = 1.0;
+
+pub struct WindowPlacementPlugin;
+
+impl Plugin for WindowPlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingPlacement>()
+            .add_systems(
+                Startup,
+                (list_monitors, check_saved_monitor, move_to_chosen_monitor).chain(),
+            )
+            .add_systems(Update, queue_placement_save)
+            .add_systems(
+                Last,
+                record_placement.before(save_settings).in_set(SaveOnExit),
+            );
+    }
+}
+
+// Puts the window back where it was last time. The pet window keeps its own size.
+pub fn restore_placement(
+    window: Window,
+    pet_mode: PetMode,
+    placement: Option<&WindowPlacement>,
+) -> Window {
+    let Some(placement) = placement else {
+        return window;
+    };
+
+    let mut window = Window {
+        position: WindowPosition::At(placement.position),
+        ..window
+    };
+    if !pet_mode.enabled {
+        if let Some(size) = placement.size {
+            window.resolution.set(size.x, size.y);
+        }
+        if placement.fullscreen {
+            window.mode = WindowMode::BorderlessFullscreen;
+        }
+    }
+    window
+}
+
+// Picked with `--monitor <index|name>`, winning over wherever the window was last time.
+// `--list-monitors` prints what there is to pick from and quits.
+#[derive(Resource, Clone, Default)]
+pub struct MonitorChoice {
+    // as typed, an index into the list or a monitor's name
+    monitor: Option<String>,
+    list: bool,
+}
+
+impl MonitorChoice {
+    pub fn from_args() -> Self {
+        let mut choice = MonitorChoice::default();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--monitor" => choice.monitor = Some(args.next().unwrap_or_default()),
+                "--list-monitors" => choice.list = true,
+                _ => {}
+            }
+        }
+        choice
+    }
+
+    // An index can go straight to winit, a name has to wait for the monitor list at startup.
+    pub fn window(&self, window: Window) -> Window {
+        if self.list {
+            // nothing to show before quitting
+            return Window {
+                visible: false,
+                ..window
+            };
+        }
+
+        match self.monitor.as_deref().map(str::parse) {
+            Some(Ok(index)) => Window {
+                position: WindowPosition::Centered(MonitorSelection::Index(index)),
+                ..window
+            },
+            _ => window,
+        }
+    }
+}
+
+fn list_monitors(
+    choice: Res<MonitorChoice>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !choice.list {
+        return;
+    }
+    let Some(winit_window) = windows
+        .get_single()
+        .ok()
+        .and_then(|entity| winit_windows.get_window(entity))
+    else {
+        return;
+    };
+
+    attach_console();
+    let primary = winit_window.primary_monitor();
+    for (index, monitor) in winit_window.available_monitors().enumerate() {
+        let size = monitor.size();
+        let position = monitor.position();
+        println!(
+            "{index}: {} {}x{} at {},{}, {}x scaling{}",
+            monitor.name().unwrap_or_else(|| "(no name)".into()),
+            size.width,
+            size.height,
+            position.x,
+            position.y,
+            monitor.scale_factor(),
+            if primary.as_ref() == Some(&monitor) {
+                ", primary"
+            } else {
+                ""
+            },
+        );
+    }
+    exit.send(AppExit::Success);
+}
+
+// Started from a terminal, the windows subsystem leaves it without a console to print to, so it
+// borrows the terminal's. Started any other way there's none to borrow and the list goes nowhere.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // SAFETY: takes no pointers, failing only means there's no console to attach to
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+// Waits out a burst of move and resize events, so dragging the window doesn't write every frame.
+#[derive(Resource, Default)]
+struct PendingPlacement(Option<Timer>);
+
+// A monitor that's since been unplugged leaves the window somewhere it can't be seen.
+fn check_saved_monitor(
+    settings: Res<Settings>,
+    winit_windows: NonSend<WinitWindows>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+) {
+    let Some(saved_monitor) = settings
+        .window
+        .as_ref()
+        .and_then(|placement| placement.monitor.as_ref())
+    else {
+        return;
+    };
+    let Ok((entity, mut window)) = windows.get_single_mut() else {
+        return;
+    };
+    let Some(winit_window) = winit_windows.get_window(entity) else {
+        return;
+    };
+
+    let still_there = winit_window
+        .available_monitors()
+        .any(|monitor| monitor.name().as_ref() == Some(saved_monitor));
+    if !still_there {
+        info!("{saved_monitor} is gone, centering the window on the primary monitor");
+        window.position = WindowPosition::Centered(MonitorSelection::Primary);
+    }
+}
+
+// Sorts out what `--monitor` meant, anything that doesn't match a monitor opens on the primary one.
+fn move_to_chosen_monitor(
+    choice: Res<MonitorChoice>,
+    winit_windows: NonSend<WinitWindows>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+) {
+    let Some(requested) = choice.monitor.as_deref() else {
+        return;
+    };
+    let Ok((entity, mut window)) = windows.get_single_mut() else {
+        return;
+    };
+    let Some(winit_window) = winit_windows.get_window(entity) else {
+        return;
+    };
+    let monitors: Vec<_> = winit_window.available_monitors().collect();
+
+    let index = match requested.parse::<usize>() {
+        Ok(index) if index < monitors.len() => Some(index),
+        Ok(index) => {
+            warn!(
+                "there's no monitor {index}, only 0 to {}, opening on the primary monitor",
+                monitors.len().saturating_sub(1)
+            );
+            None
+        }
+        Err(_) if requested.is_empty() => {
+            warn!("--monitor needs an index or a name, opening on the primary monitor");
+            None
+        }
+        Err(_) => {
+            let found = monitors
+                .iter()
+                .position(|monitor| monitor.name().as_deref() == Some(requested));
+            if found.is_none() {
+                warn!("there's no monitor called {requested}, opening on the primary monitor");
+            }
+            found
+        }
+    };
+
+    window.position =
+        WindowPosition::Centered(index.map_or(MonitorSelection::Primary, MonitorSelection::Index));
+}
+
+fn queue_placement_save(
+    time: Res<Time<Real>>,
+    mut moved_events: EventReader<WindowMoved>,
+    mut resized_events: EventReader<WindowResized>,
+    primary: Query<(), With<PrimaryWindow>>,
+    mut pending: ResMut<PendingPlacement>,
+) {
+    let moved = moved_events
+        .read()
+        .any(|event| primary.contains(event.window));
+    let resized = resized_events
+        .read()
+        .any(|event| primary.contains(event.window));
+    if moved || resized {
+        pending.0 = Some(Timer::from_seconds(
+            PLACEMENT_SAVE_DELAY_SECS,
+            TimerMode::Once,
+        ));
+    }
+
+    if let Some(timer) = &mut pending.0 {
+        timer.tick(time.delta());
+    }
+}
+
+fn record_placement(
+    mut exits: EventReader<AppExit>,
+    pet_mode: Res<PetMode>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut pending: ResMut<PendingPlacement>,
+    mut settings: ResMut<Settings>,
+) {
+    let exiting = exits.read().last().is_some();
+    let settled = pending.0.as_ref().is_some_and(Timer::finished);
+    if !exiting && !settled {
+        return;
+    }
+    pending.0 = None;
+
+    let Ok((entity, window)) = windows.get_single() else {
+        return;
+    };
+    let WindowPosition::At(position) = window.position else {
+        // never moved and still centered, nothing worth remembering
+        return;
+    };
+
+    let fullscreen = window.mode != WindowMode::Windowed;
+    // the fullscreen size says nothing about the windowed one, and the pet one is fixed
+    let size = if fullscreen || pet_mode.enabled {
+        settings
+            .window
+            .as_ref()
+            .and_then(|placement| placement.size)
+    } else {
+        Some(window.size())
+    };
+    let monitor = winit_windows
+        .get_window(entity)
+        .and_then(|winit_window| winit_window.current_monitor())
+        .and_then(|monitor| monitor.name());
+
+    settings.window = Some(WindowPlacement {
+        position,
+        size,
+        monitor,
+        fullscreen,
+    });
+}
@@ -0,0 +1,133 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode, WindowMoved},
+    winit::WinitWindows,
+};
+use rand::prelude::*;
+
+use crate::{settings::Settings, CubeState};
+
+const SHAKE_SECS: f32 = 0.5;
+// logical pixels either way at the start, dying down to nothing by the end
+const SHAKE_DISTANCE: f32 = 8.0;
+
+pub struct WindowShakePlugin;
+
+impl Plugin for WindowShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WindowShake>()
+            .add_systems(OnEnter(CubeState::Angry), start_window_shake)
+            .add_systems(Update, shake_window);
+    }
+}
+
+// An angry cube rattles the whole window for a moment, then puts it back exactly where it was.
+#[derive(Resource, Default)]
+pub struct WindowShake(Option<Shake>);
+
+struct Shake {
+    origin: IVec2,
+    timer: Timer,
+    // every position asked for so far, any other move is someone dragging the window
+    placed: Vec<IVec2>,
+    // how far the window may go without leaving its monitor, outer corner in physical pixels
+    min: IVec2,
+    max: IVec2,
+    scale_factor: f32,
+}
+
+impl WindowShake {
+    pub fn shaking(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+fn start_window_shake(
+    settings: Res<Settings>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut shake: ResMut<WindowShake>,
+) {
+    // a held button may well be the window being dragged already
+    if settings.skip_window_shake || shake.shaking() || mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    let Ok((entity, window)) = windows.get_single() else {
+        return;
+    };
+    if window.mode != WindowMode::Windowed || !window.visible {
+        return;
+    }
+    let Some(winit_window) = winit_windows.get_window(entity) else {
+        return;
+    };
+    let (Ok(origin), Some(monitor)) = (
+        winit_window.outer_position(),
+        winit_window.current_monitor(),
+    ) else {
+        return;
+    };
+
+    let origin = IVec2::new(origin.x, origin.y);
+    let outer = winit_window.outer_size();
+    let start = IVec2::new(monitor.position().x, monitor.position().y);
+    let end = start + IVec2::new(monitor.size().width as i32, monitor.size().height as i32)
+        - IVec2::new(outer.width as i32, outer.height as i32);
+    shake.0 = Some(Shake {
+        origin,
+        timer: Timer::from_seconds(SHAKE_SECS, TimerMode::Once),
+        placed: Vec::new(),
+        // never further off the monitor than it already was
+        min: start.min(origin),
+        max: end.max(origin),
+        scale_factor: monitor.scale_factor() as f32,
+    });
+}
+
+fn shake_window(
+    // runs out on time even if the pause menu comes up halfway
+    time: Res<Time<Real>>,
+    mut moved_events: EventReader<WindowMoved>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    mut shake: ResMut<WindowShake>,
+) {
+    let Ok((entity, mut window)) = windows.get_single_mut() else {
+        return;
+    };
+    // read even when still, or the first frame of a shake would see moves from before it
+    let moved: Vec<IVec2> = moved_events
+        .read()
+        .filter(|event| event.window == entity)
+        .map(|event| event.position)
+        .collect();
+    let Some(current) = &mut shake.0 else {
+        return;
+    };
+
+    if moved
+        .iter()
+        .any(|position| !current.placed.contains(position))
+    {
+        // wherever it's being taken is where it should go, not back
+        shake.0 = None;
+        return;
+    }
+
+    if current.timer.tick(time.delta()).finished() {
+        window.position = WindowPosition::At(current.origin);
+        shake.0 = None;
+        return;
+    }
+
+    let distance = SHAKE_DISTANCE * current.scale_factor * current.timer.fraction_remaining();
+    let mut rng = rand::thread_rng();
+    let offset = Vec2::new(
+        rng.gen_range(-distance..=distance),
+        rng.gen_range(-distance..=distance),
+    );
+    let position = (current.origin + offset.round().as_ivec2()).clamp(current.min, current.max);
+
+    window.position = WindowPosition::At(position);
+    current.placed.push(position);
+}
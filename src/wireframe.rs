@@ -0,0 +1,76 @@
+use bevy::{
+    pbr::wireframe::{Wireframe, WireframeConfig, WireframePlugin},
+    prelude::*,
+    render::settings::WgpuFeatures,
+};
+
+use crate::{input::InputState, keybindings::Action, skin::CubeParts};
+
+// bright against any background and every mood's lights
+const WIREFRAME_COLOR: Color = Color::srgb(0.0, 1.0, 0.4);
+
+pub struct WireframeViewPlugin;
+
+impl Plugin for WireframeViewPlugin {
+    fn build(&self, app: &mut App) {
+        if !cfg!(feature = "debug-tools") {
+            return;
+        }
+
+        app.add_plugins(WireframePlugin)
+            .insert_resource(WireframeConfig {
+                global: false,
+                default_color: WIREFRAME_COLOR,
+            })
+            .init_resource::<WireframeView>()
+            .add_systems(Update, (toggle_wireframe, tag_wireframes).chain());
+    }
+}
+
+// What the GPU has to be asked for when it's set up, for the wireframe pipeline to be built at all.
+pub fn wireframe_features() -> WgpuFeatures {
+    if cfg!(feature = "debug-tools") {
+        WgpuFeatures::POLYGON_MODE_LINE
+    } else {
+        WgpuFeatures::empty()
+    }
+}
+
+// The cube's triangles drawn over it, to see how the glb is put together. Only the cube's meshes,
+// not the backdrop or anything else in the scene.
+#[derive(Resource, Default)]
+struct WireframeView(bool);
+
+fn toggle_wireframe(
+    mut commands: Commands,
+    input: Res<InputState>,
+    mut view: ResMut<WireframeView>,
+    tagged: Query<Entity, With<Wireframe>>,
+) {
+    if !input.just_did(Action::ToggleWireframe) {
+        return;
+    }
+
+    view.0 = !view.0;
+    if !view.0 {
+        for entity in &tagged {
+            commands.entity(entity).remove::<Wireframe>();
+        }
+    }
+    info!("wireframe {}", if view.0 { "on" } else { "off" });
+}
+
+// New meshes under the cube are picked up as they show up for as long as it's on.
+fn tag_wireframes(
+    mut commands: Commands,
+    view: Res<WireframeView>,
+    meshes: CubeParts<(), (With<Handle<Mesh>>, Without<Wireframe>)>,
+) {
+    if !view.0 {
+        return;
+    }
+
+    for (entity, _) in meshes.iter() {
+        commands.entity(entity).insert(Wireframe);
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::{gltf::Gltf, prelude::*};
+use rand::prelude::*;
+
+use crate::{skin::SkinLibrary, Cube, CubeState};
+
+pub struct YawnPlugin;
+
+impl Plugin for YawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                find_cube_clips.run_if(not(resource_exists::<CubeClips>)),
+                yawn_update.run_if(
+                    resource_exists::<CubeClips>
+                        .and_then(in_state(CubeState::Happy).or_else(in_state(CubeState::Sad))),
+                ),
+            ),
+        );
+    }
+}
+
+// Animation clips authored in the glb, with the player and graph node they play on.
+#[derive(Resource)]
+pub struct CubeClips {
+    yawn: Option<(Entity, AnimationNodeIndex)>,
+    yawn_timer: Timer,
+}
+
+impl CubeClips {
+    fn yawn_timer() -> Timer {
+        Timer::from_seconds(rand::thread_rng().gen_range(20.0..60.0), TimerMode::Once)
+    }
+}
+
+// The player only exists once Scene0 has spawned, so this waits for both the gltf and the scene.
+fn find_cube_clips(
+    mut commands: Commands,
+    skins: Res<SkinLibrary>,
+    gltfs: Res<Assets<Gltf>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    query_cube: Query<Entity, With<Cube>>,
+    children: Query<&Children>,
+    players: Query<Entity, With<AnimationPlayer>>,
+) {
+    let Some(gltf) = gltfs.get(&skins.current().gltf) else {
+        return;
+    };
+    let Some(yawn) = gltf.named_animations.get("yawn") else {
+        // nothing authored, nothing to play
+        commands.insert_resource(CubeClips {
+            yawn: None,
+            yawn_timer: CubeClips::yawn_timer(),
+        });
+        return;
+    };
+
+    let cube = query_cube.single();
+    let Some(player) = children
+        .iter_descendants(cube)
+        .find(|entity| players.contains(*entity))
+    else {
+        return;
+    };
+
+    let (graph, node) = AnimationGraph::from_clip(yawn.clone());
+    commands.entity(player).insert(graphs.add(graph));
+
+    commands.insert_resource(CubeClips {
+        yawn: Some((player, node)),
+        yawn_timer: CubeClips::yawn_timer(),
+    });
+}
+
+fn yawn_update(
+    time: Res<Time>,
+    state: Res<State<CubeState>>,
+    mut clips: ResMut<CubeClips>,
+    mut players: Query<&mut AnimationPlayer>,
+    mut query_cube: Query<&mut Cube>,
+) {
+    let Some((player_entity, node)) = clips.yawn else {
+        return;
+    };
+    let Ok(mut player) = players.get_mut(player_entity) else {
+        return;
+    };
+    let mut cube_prop = query_cube.single_mut();
+
+    if cube_prop.playing_clip {
+        if player.animation(node).is_none_or(|yawn| yawn.is_finished()) {
+            // hand the rotation back and ease into whatever the state wants
+            cube_prop.playing_clip = false;
+            cube_prop.rotate_timer = Timer::from_seconds(0.5, TimerMode::Once);
+        }
+        return;
+    }
+
+    // only yawn while nothing is going on
+    let idle = match state.get() {
+        CubeState::Happy => cube_prop.bored,
+        _ => true,
+    };
+    if !idle {
+        return;
+    }
+
+    if clips.yawn_timer.tick(time.delta()).finished() {
+        clips.yawn_timer = CubeClips::yawn_timer();
+        player.start(node).replay();
+        cube_prop.playing_clip = true;
+    }
+}